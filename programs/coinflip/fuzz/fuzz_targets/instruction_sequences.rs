@@ -0,0 +1,815 @@
+#![no_main]
+
+//! Random sequences of game and admin instructions with random signers.
+//!
+//! After every transaction the harness checks that no lamports were created
+//! or destroyed across the accounts the program can touch, that successful
+//! privileged calls were signed by the authority on record, and that wallets
+//! are only debited when they signed the transaction.
+
+#[path = "../../tests/common/mod.rs"]
+mod common;
+
+use arbitrary::Arbitrary;
+use coinflip::instruction;
+use common::*;
+use libfuzzer_sys::fuzz_target;
+use solana_sdk::{
+    instruction::Instruction, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Keypair,
+    signer::Signer,
+};
+
+const PROGRAM_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../../target/deploy/coinflip.so"
+);
+const PLAYERS: u8 = 3;
+const SESSIONS: u8 = 2;
+const TOURNAMENT: u64 = 0;
+
+#[derive(Arbitrary, Debug, Clone, Copy)]
+enum Actor {
+    SuperAdmin,
+    Operator,
+    Finance,
+    Update,
+    Player(u8),
+    Stranger,
+}
+
+#[derive(Arbitrary, Debug, Clone, Copy)]
+enum Authority {
+    Operation,
+    Finance,
+    Update,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Action {
+    PlayGame {
+        player: u8,
+        operator: Actor,
+        is_head: bool,
+        bet: u64,
+        session: u8,
+    },
+    PlayTournamentGame {
+        player: u8,
+        operator: Actor,
+        is_head: bool,
+        bet: u64,
+        session: u8,
+    },
+    EnterTournament {
+        player: u8,
+    },
+    PlayGameFromBalance {
+        player: u8,
+        operator: Actor,
+        is_head: bool,
+        bet: u64,
+        session: u8,
+    },
+    CreateSessionKey {
+        player: u8,
+        spending_cap: u64,
+        allowed: u8,
+    },
+    PlayGameWithSessionKey {
+        player: u8,
+        operator: Actor,
+        is_head: bool,
+        bet: u64,
+        session: u8,
+    },
+    PlayDice {
+        player: u8,
+        operator: Actor,
+        target: u8,
+        bet: u64,
+        session: u8,
+    },
+    PlayMultiCoin {
+        player: u8,
+        operator: Actor,
+        coins: u8,
+        heads: u8,
+        exact: bool,
+        bet: u64,
+        session: u8,
+    },
+    PlayBatch {
+        player: u8,
+        operator: Actor,
+        flips: u8,
+        choices: u64,
+        bet: u64,
+        session: u8,
+    },
+    SetBatchResult {
+        signer: Actor,
+        player: u8,
+        outcomes: u64,
+        session: u8,
+    },
+    CreateChallenge {
+        player: u8,
+        id: u8,
+        is_head: bool,
+        stake: u64,
+    },
+    AcceptChallenge {
+        player: u8,
+        creator: u8,
+        id: u8,
+    },
+    CancelChallenge {
+        signer: Actor,
+        creator: u8,
+        id: u8,
+    },
+    SettleChallenge {
+        signer: Actor,
+        creator: u8,
+        opponent: u8,
+        id: u8,
+        is_head: bool,
+    },
+    SetResult {
+        signer: Actor,
+        player: u8,
+        round: u8,
+        is_win: bool,
+        session: u8,
+    },
+    DoubleBet {
+        player: u8,
+        operator: Actor,
+        is_head: bool,
+        session: u8,
+    },
+    ClaimReward {
+        signer: Actor,
+        player: u8,
+        session: u8,
+    },
+    ClaimRewardToBalance {
+        signer: Actor,
+        player: u8,
+        session: u8,
+    },
+    DepositBalance {
+        player: u8,
+        amount: u64,
+    },
+    WithdrawBalance {
+        player: u8,
+        amount: u64,
+    },
+    Withdraw {
+        signer: Actor,
+        recipient: Actor,
+        amount: u64,
+    },
+    SetRtp {
+        signer: Actor,
+        rtp: u64,
+    },
+    SetMaxWinAmount {
+        signer: Actor,
+        amount: u64,
+    },
+    SetMinBetAmount {
+        signer: Actor,
+        amount: u64,
+    },
+    SetJackpotConfig {
+        signer: Actor,
+        contribution_rate: u64,
+        trigger_streak: u8,
+    },
+    FundJackpot {
+        signer: Actor,
+        amount: u64,
+    },
+    SetAuthority {
+        signer: Actor,
+        authority: Authority,
+        new_authority: Actor,
+    },
+}
+
+/// One transaction, with the signer acting in the privileged role and the
+/// authority on record for that role.
+struct Call<'a> {
+    ix: Instruction,
+    signers: Vec<&'a Keypair>,
+    acting: Pubkey,
+    authority: Pubkey,
+}
+
+struct World {
+    casino: Casino,
+    players: Vec<Keypair>,
+    stranger: Keypair,
+}
+
+impl World {
+    fn new() -> Self {
+        let casino = Casino::with_program(PROGRAM_PATH);
+        let players = (0..PLAYERS)
+            .map(|_| casino.new_player(20 * LAMPORTS_PER_SOL))
+            .collect();
+        let stranger = casino.new_player(LAMPORTS_PER_SOL);
+        casino
+            .create_tournament(TOURNAMENT, LAMPORTS_PER_SOL / 10, 0, vec![1000])
+            .unwrap();
+
+        Self {
+            casino,
+            players,
+            stranger,
+        }
+    }
+
+    fn keypair(&self, actor: Actor) -> &Keypair {
+        match actor {
+            Actor::SuperAdmin => &self.casino.super_admin,
+            Actor::Operator => &self.casino.operator,
+            Actor::Finance => &self.casino.finance,
+            Actor::Update => &self.casino.update,
+            Actor::Player(index) => self.player(index),
+            Actor::Stranger => &self.stranger,
+        }
+    }
+
+    fn player(&self, index: u8) -> &Keypair {
+        &self.players[(index % PLAYERS) as usize]
+    }
+
+    /// Every account the program can move lamports between. The fee payer is
+    /// left out, so the total only changes if the program mints or burns.
+    fn tracked(&self) -> Vec<Pubkey> {
+        let mut keys = vec![
+            casino_vault(),
+            jackpot(),
+            tournament(TOURNAMENT),
+            self.casino.super_admin.pubkey(),
+            self.casino.operator.pubkey(),
+            self.casino.finance.pubkey(),
+            self.casino.update.pubkey(),
+            self.stranger.pubkey(),
+        ];
+        for player in &self.players {
+            keys.push(player.pubkey());
+            keys.push(player_balance(&player.pubkey()));
+            keys.push(session_key(&player.pubkey(), &self.stranger.pubkey()));
+            keys.push(tournament_entry(TOURNAMENT, &player.pubkey()));
+            for session in 0..SESSIONS as u64 {
+                keys.push(player_pool(&player.pubkey(), session));
+                keys.push(game_vault(&player.pubkey(), session));
+                keys.push(challenge(&player.pubkey(), session));
+            }
+        }
+        keys
+    }
+
+    fn build(&self, action: &Action) -> Call<'_> {
+        let global = self.casino.global_pool();
+        let session = |session: u8| (session % SESSIONS) as u64;
+
+        match *action {
+            Action::PlayGame {
+                player,
+                operator,
+                is_head,
+                bet,
+                session: id,
+            } => {
+                let player = self.player(player);
+                let operator = self.keypair(operator);
+                Call {
+                    ix: play_game_ix(
+                        &player.pubkey(),
+                        &operator.pubkey(),
+                        is_head,
+                        bet % (5 * LAMPORTS_PER_SOL),
+                        session(id),
+                    ),
+                    signers: vec![player, operator],
+                    acting: operator.pubkey(),
+                    authority: global.operation_authority,
+                }
+            }
+            Action::PlayTournamentGame {
+                player,
+                operator,
+                is_head,
+                bet,
+                session: id,
+            } => {
+                let player = self.player(player);
+                let operator = self.keypair(operator);
+                Call {
+                    ix: play_game_in_tournament_ix(
+                        &player.pubkey(),
+                        &operator.pubkey(),
+                        Some(TOURNAMENT),
+                        is_head,
+                        bet % (5 * LAMPORTS_PER_SOL),
+                        session(id),
+                    ),
+                    signers: vec![player, operator],
+                    acting: operator.pubkey(),
+                    authority: global.operation_authority,
+                }
+            }
+            Action::EnterTournament { player } => {
+                let player = self.player(player);
+                Call {
+                    ix: enter_tournament_ix(&player.pubkey(), TOURNAMENT),
+                    signers: vec![player],
+                    acting: player.pubkey(),
+                    authority: player.pubkey(),
+                }
+            }
+            Action::PlayGameFromBalance {
+                player,
+                operator,
+                is_head,
+                bet,
+                session: id,
+            } => {
+                let player = self.player(player);
+                let operator = self.keypair(operator);
+                Call {
+                    ix: play_game_from_balance_ix(
+                        &player.pubkey(),
+                        &operator.pubkey(),
+                        is_head,
+                        bet % (5 * LAMPORTS_PER_SOL),
+                        session(id),
+                    ),
+                    signers: vec![player, operator],
+                    acting: operator.pubkey(),
+                    authority: global.operation_authority,
+                }
+            }
+            // The stranger is the session key every player can authorize
+            Action::CreateSessionKey {
+                player,
+                spending_cap,
+                allowed,
+            } => {
+                let player = self.player(player);
+                Call {
+                    ix: create_session_key_ix(
+                        &player.pubkey(),
+                        &self.stranger.pubkey(),
+                        spending_cap % (5 * LAMPORTS_PER_SOL),
+                        self.casino.now() + 3600,
+                        allowed,
+                    ),
+                    signers: vec![player],
+                    acting: player.pubkey(),
+                    authority: player.pubkey(),
+                }
+            }
+            Action::PlayGameWithSessionKey {
+                player,
+                operator,
+                is_head,
+                bet,
+                session: id,
+            } => {
+                let operator = self.keypair(operator);
+                Call {
+                    ix: play_game_with_session_key_ix(
+                        &self.stranger.pubkey(),
+                        &self.player(player).pubkey(),
+                        &operator.pubkey(),
+                        is_head,
+                        bet % (5 * LAMPORTS_PER_SOL),
+                        session(id),
+                    ),
+                    signers: vec![&self.stranger, operator],
+                    acting: operator.pubkey(),
+                    authority: global.operation_authority,
+                }
+            }
+            Action::PlayDice {
+                player,
+                operator,
+                target,
+                bet,
+                session: id,
+            } => {
+                let player = self.player(player);
+                let operator = self.keypair(operator);
+                Call {
+                    ix: play_dice_ix(
+                        &player.pubkey(),
+                        &operator.pubkey(),
+                        target,
+                        bet % (5 * LAMPORTS_PER_SOL),
+                        session(id),
+                    ),
+                    signers: vec![player, operator],
+                    acting: operator.pubkey(),
+                    authority: global.operation_authority,
+                }
+            }
+            Action::PlayMultiCoin {
+                player,
+                operator,
+                coins,
+                heads,
+                exact,
+                bet,
+                session: id,
+            } => {
+                let player = self.player(player);
+                let operator = self.keypair(operator);
+                Call {
+                    ix: play_multi_coin_ix(
+                        &player.pubkey(),
+                        &operator.pubkey(),
+                        coins,
+                        heads,
+                        exact,
+                        bet % (5 * LAMPORTS_PER_SOL),
+                        session(id),
+                    ),
+                    signers: vec![player, operator],
+                    acting: operator.pubkey(),
+                    authority: global.operation_authority,
+                }
+            }
+            Action::PlayBatch {
+                player,
+                operator,
+                flips,
+                choices,
+                bet,
+                session: id,
+            } => {
+                let player = self.player(player);
+                let operator = self.keypair(operator);
+                let flips = (flips % 8) as usize;
+                Call {
+                    ix: play_batch_ix(
+                        &player.pubkey(),
+                        &operator.pubkey(),
+                        (0..flips).map(|i| choices >> i & 1 == 1).collect(),
+                        vec![bet % LAMPORTS_PER_SOL; flips],
+                        session(id),
+                    ),
+                    signers: vec![player, operator],
+                    acting: operator.pubkey(),
+                    authority: global.operation_authority,
+                }
+            }
+            Action::SetBatchResult {
+                signer,
+                player,
+                outcomes,
+                session: id,
+            } => {
+                let signer = self.keypair(signer);
+                Call {
+                    ix: set_batch_result_ix(
+                        &signer.pubkey(),
+                        &self.player(player).pubkey(),
+                        1,
+                        outcomes,
+                        session(id),
+                    ),
+                    signers: vec![signer],
+                    acting: signer.pubkey(),
+                    authority: global.operation_authority,
+                }
+            }
+            Action::CreateChallenge {
+                player,
+                id,
+                is_head,
+                stake,
+            } => {
+                let player = self.player(player);
+                Call {
+                    ix: create_challenge_ix(
+                        &player.pubkey(),
+                        session(id),
+                        is_head,
+                        stake % (5 * LAMPORTS_PER_SOL),
+                    ),
+                    signers: vec![player],
+                    acting: player.pubkey(),
+                    authority: player.pubkey(),
+                }
+            }
+            Action::AcceptChallenge {
+                player,
+                creator,
+                id,
+            } => {
+                let player = self.player(player);
+                Call {
+                    ix: accept_challenge_ix(
+                        &player.pubkey(),
+                        &self.player(creator).pubkey(),
+                        session(id),
+                    ),
+                    signers: vec![player],
+                    acting: player.pubkey(),
+                    authority: player.pubkey(),
+                }
+            }
+            Action::CancelChallenge {
+                signer,
+                creator,
+                id,
+            } => {
+                let signer = self.keypair(signer);
+                let creator = self.player(creator).pubkey();
+                Call {
+                    ix: cancel_challenge_ix(&signer.pubkey(), &creator, session(id)),
+                    signers: vec![signer],
+                    acting: signer.pubkey(),
+                    authority: creator,
+                }
+            }
+            Action::SettleChallenge {
+                signer,
+                creator,
+                opponent,
+                id,
+                is_head,
+            } => {
+                let signer = self.keypair(signer);
+                Call {
+                    ix: settle_challenge_ix(
+                        &signer.pubkey(),
+                        &self.player(creator).pubkey(),
+                        &self.player(opponent).pubkey(),
+                        session(id),
+                        is_head,
+                    ),
+                    signers: vec![signer],
+                    acting: signer.pubkey(),
+                    authority: global.operation_authority,
+                }
+            }
+            Action::SetResult {
+                signer,
+                player,
+                round,
+                is_win,
+                session: id,
+            } => {
+                let signer = self.keypair(signer);
+                Call {
+                    ix: set_result_ix(
+                        &signer.pubkey(),
+                        &self.player(player).pubkey(),
+                        round % 4,
+                        is_win,
+                        session(id),
+                    ),
+                    signers: vec![signer],
+                    acting: signer.pubkey(),
+                    authority: global.operation_authority,
+                }
+            }
+            Action::DoubleBet {
+                player,
+                operator,
+                is_head,
+                session: id,
+            } => {
+                let player = self.player(player);
+                let operator = self.keypair(operator);
+                Call {
+                    ix: double_bet_ix(&player.pubkey(), &operator.pubkey(), is_head, session(id)),
+                    signers: vec![player, operator],
+                    acting: operator.pubkey(),
+                    authority: global.operation_authority,
+                }
+            }
+            Action::ClaimReward {
+                signer,
+                player,
+                session: id,
+            } => {
+                let signer = self.keypair(signer);
+                Call {
+                    ix: claim_reward_ix(
+                        &signer.pubkey(),
+                        &self.player(player).pubkey(),
+                        session(id),
+                    ),
+                    signers: vec![signer],
+                    acting: signer.pubkey(),
+                    authority: global.operation_authority,
+                }
+            }
+            Action::ClaimRewardToBalance {
+                signer,
+                player,
+                session: id,
+            } => {
+                let signer = self.keypair(signer);
+                Call {
+                    ix: claim_reward_to_balance_ix(
+                        &signer.pubkey(),
+                        &self.player(player).pubkey(),
+                        session(id),
+                    ),
+                    signers: vec![signer],
+                    acting: signer.pubkey(),
+                    authority: global.operation_authority,
+                }
+            }
+            Action::DepositBalance { player, amount } => {
+                let player = self.player(player);
+                Call {
+                    ix: deposit_balance_ix(&player.pubkey(), amount % (5 * LAMPORTS_PER_SOL)),
+                    signers: vec![player],
+                    acting: player.pubkey(),
+                    authority: player.pubkey(),
+                }
+            }
+            Action::WithdrawBalance { player, amount } => {
+                let player = self.player(player);
+                Call {
+                    ix: withdraw_balance_ix(&player.pubkey(), amount % (5 * LAMPORTS_PER_SOL)),
+                    signers: vec![player],
+                    acting: player.pubkey(),
+                    authority: player.pubkey(),
+                }
+            }
+            Action::Withdraw {
+                signer,
+                recipient,
+                amount,
+            } => {
+                let signer = self.keypair(signer);
+                Call {
+                    ix: withdraw_ix(
+                        &signer.pubkey(),
+                        &self.keypair(recipient).pubkey(),
+                        amount % (2 * CASINO_FUNDS),
+                    ),
+                    signers: vec![signer],
+                    acting: signer.pubkey(),
+                    authority: global.finance_authority,
+                }
+            }
+            Action::SetRtp { signer, rtp } => {
+                let signer = self.keypair(signer);
+                Call {
+                    ix: set_global_pool_ix(&signer.pubkey(), instruction::SetRtp { new_rtp: rtp }),
+                    signers: vec![signer],
+                    acting: signer.pubkey(),
+                    authority: global.update_authority,
+                }
+            }
+            Action::SetMaxWinAmount { signer, amount } => {
+                let signer = self.keypair(signer);
+                Call {
+                    ix: set_global_pool_ix(
+                        &signer.pubkey(),
+                        instruction::SetMaxWinAmount {
+                            new_max_win_amount: amount,
+                        },
+                    ),
+                    signers: vec![signer],
+                    acting: signer.pubkey(),
+                    authority: global.update_authority,
+                }
+            }
+            Action::SetMinBetAmount { signer, amount } => {
+                let signer = self.keypair(signer);
+                Call {
+                    ix: set_global_pool_ix(
+                        &signer.pubkey(),
+                        instruction::SetMinBetAmount {
+                            new_min_bet_amount: amount,
+                        },
+                    ),
+                    signers: vec![signer],
+                    acting: signer.pubkey(),
+                    authority: global.update_authority,
+                }
+            }
+            Action::SetJackpotConfig {
+                signer,
+                contribution_rate,
+                trigger_streak,
+            } => {
+                let signer = self.keypair(signer);
+                Call {
+                    ix: set_jackpot_ix(
+                        &signer.pubkey(),
+                        instruction::SetJackpotConfig {
+                            contribution_rate: contribution_rate % 100,
+                            trigger_streak: trigger_streak % 4,
+                        },
+                    ),
+                    signers: vec![signer],
+                    acting: signer.pubkey(),
+                    authority: global.update_authority,
+                }
+            }
+            Action::FundJackpot { signer, amount } => {
+                let signer = self.keypair(signer);
+                Call {
+                    ix: set_jackpot_ix(
+                        &signer.pubkey(),
+                        instruction::FundJackpot {
+                            amount: amount % LAMPORTS_PER_SOL,
+                        },
+                    ),
+                    signers: vec![signer],
+                    acting: signer.pubkey(),
+                    authority: global.update_authority,
+                }
+            }
+            Action::SetAuthority {
+                signer,
+                authority,
+                new_authority,
+            } => {
+                let signer = self.keypair(signer);
+                let new_authority = self.keypair(new_authority).pubkey();
+                let ix = match authority {
+                    Authority::Operation => set_authority_ix(
+                        &signer.pubkey(),
+                        instruction::SetOperationAuthority {
+                            new_operation_authority: new_authority,
+                        },
+                    ),
+                    Authority::Finance => set_authority_ix(
+                        &signer.pubkey(),
+                        instruction::SetFinanceAuthority {
+                            new_finance_authority: new_authority,
+                        },
+                    ),
+                    Authority::Update => set_authority_ix(
+                        &signer.pubkey(),
+                        instruction::SetUpdateAuthority {
+                            new_update_authority: new_authority,
+                        },
+                    ),
+                };
+                Call {
+                    ix,
+                    signers: vec![signer],
+                    acting: signer.pubkey(),
+                    authority: global.super_admin,
+                }
+            }
+        }
+    }
+}
+
+fuzz_target!(|actions: Vec<Action>| {
+    let world = World::new();
+    let tracked = world.tracked();
+    let total = world.casino.total(&tracked);
+
+    for action in actions.iter().take(64) {
+        let call = world.build(action);
+        let before: Vec<u64> = tracked
+            .iter()
+            .map(|key| world.casino.balance(key))
+            .collect();
+
+        let result = world.casino.send(&[call.ix], &call.signers);
+
+        assert_eq!(
+            world.casino.total(&tracked),
+            total,
+            "lamports created or destroyed by {action:?}"
+        );
+
+        if result.is_ok() {
+            assert_eq!(
+                call.acting, call.authority,
+                "unauthorized {action:?} succeeded"
+            );
+        }
+
+        // Wallets can only be debited with their own signature; the vaults
+        // and pools are PDAs moved by the program itself
+        for (key, before) in tracked.iter().zip(before) {
+            let is_wallet = key.is_on_curve();
+            if is_wallet && world.casino.balance(key) < before {
+                assert!(
+                    call.signers.iter().any(|signer| signer.pubkey() == *key),
+                    "{action:?} debited {key} without its signature"
+                );
+            }
+        }
+    }
+});
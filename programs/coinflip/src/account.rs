@@ -1,28 +1,160 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::hash::hash};
 use solana_program::pubkey::Pubkey;
 
-use crate::constants::{GLOBAL_AUTHORITY_SEED, PLAYER_POOL_SEED, VAULT_AUTHORITY_SEED};
+use crate::constants::{
+    ACCESS_ALLOWLIST, ACCESS_DENYLIST, ACCESS_ENTRY_SEED, ATTESTATION_SEED, CHALLENGE_SEED,
+    COIN_WIN_CHANCE, DAY, GLOBAL_AUTHORITY_SEED, JACKPOT_SEED, LEADERBOARD_DAILY, LEADERBOARD_SEED,
+    LEADERBOARD_SIZE, LEADERBOARD_WEEKLY, LIMITS_COOLING_OFF, LIMIT_PERIODS, MAX_COINS,
+    MAX_PRIZE_PLACES, MAX_VIP_TIERS, MAX_WITHDRAWAL_APPROVERS, OPERATOR_BOND_SEED, PERCENT,
+    PERMILLE, PLAYER_BALANCE_SEED, PLAYER_LIMITS_SEED, PLAYER_POOL_SEED, PLAYER_STATS_SEED,
+    PROMO_CREDIT_SEED, SESSION_KEY_SEED, TOURNAMENT_ENTRY_SEED, TOURNAMENT_SCORE_STREAK,
+    TOURNAMENT_SCORE_VOLUME, TOURNAMENT_SEED, VAULT_AUTHORITY_SEED, VIP_CONFIG_SEED, WEEK,
+    WITHDRAWAL_CONFIG_SEED, WITHDRAWAL_PROPOSAL_SEED,
+};
+use crate::error::GameError;
+use crate::math::multi_coin_odds;
+use crate::utils::{DoubleBetAccounts, OpenGameAccounts};
 
 #[account]
 #[derive(Default)]
 pub struct GlobalPool {
-    pub super_admin: Pubkey,         // 32
-    pub operation_authority: Pubkey, // 32
-    pub finance_authority: Pubkey,   // 32
-    pub update_authority: Pubkey,    // 32
-    pub rtp: u64,                    // 8
-    pub max_win_amount: u64,         // 8
-    pub min_bet_amount: u64,         // 8
+    pub super_admin: Pubkey,          // 32
+    pub operation_authority: Pubkey,  // 32
+    pub finance_authority: Pubkey,    // 32
+    pub update_authority: Pubkey,     // 32
+    pub rtp: u64,                     // 8
+    pub max_win_amount: u64,          // 8
+    pub min_bet_amount: u64,          // 8
+    pub game_nonce: u64,              // 8
+    pub randomness_provider: Pubkey,  // 32
+    pub slot_hash_delay: u64,         // 8
+    pub slot_hash_max_bet: u64,       // 8
+    pub hash_chain_head: [u8; 32],    // 32
+    pub hash_chain_assigned: u64,     // 8
+    pub hash_chain_revealed: u64,     // 8
+    pub hash_chain_end: u64,          // 8
+    pub settlement_timeout: i64,      // 8
+    pub challenge_rake: u64,          // 8
+    pub challenge_timeout: i64,       // 8
+    pub access_mode: u8,              // 1
+    pub access_merkle_root: [u8; 32], // 32
+    pub compliance_authority: Pubkey, // 32
+    pub attestation_required: bool,   // 1
+    pub hash_chain_skipped: u64,      // 8
 }
 
 impl GlobalPool {
-    pub const DATA_SIZE: usize = 32 + 32 + 32 + 32 + 8 + 8 + 8; //  152
+    pub const DATA_SIZE: usize = 32
+        + 32
+        + 32
+        + 32
+        + 8
+        + 8
+        + 8
+        + 8
+        + 32
+        + 8
+        + 8
+        + 32
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 1
+        + 32
+        + 32
+        + 1
+        + 8; //  362
+
+    // Hand out a nonce no other round will ever be settled with
+    pub fn next_nonce(&mut self) -> u64 {
+        let nonce = self.game_nonce;
+        self.game_nonce += 1;
+        nonce
+    }
+
+    // Hand out the hash chain position of a new round. Positions are settled
+    // one reveal each, in the order they were handed out
+    pub fn next_chain_position(&mut self) -> u64 {
+        self.hash_chain_assigned += 1;
+        self.hash_chain_assigned
+    }
+
+    // Deadline for the operator to settle a round starting now, 0 when
+    // deadlines are disabled
+    pub fn settlement_deadline(&self) -> Result<i64> {
+        if self.settlement_timeout == 0 {
+            return Ok(0);
+        }
+        let deadline = Clock::get()?
+            .unix_timestamp
+            .checked_add(self.settlement_timeout)
+            .ok_or(GameError::MathOverflow)?;
+        Ok(deadline)
+    }
+
+    // Whether the published chain still has positions to hand out
+    pub fn hash_chain_active(&self) -> bool {
+        self.hash_chain_assigned < self.hash_chain_end
+    }
+
+    // Pass over the next position to reveal, whose round was slashed. Its value
+    // stays secret, so the next reveal hashes to the head through it
+    pub fn skip_chain_position(&mut self) {
+        self.hash_chain_revealed += 1;
+        self.hash_chain_skipped += 1;
+    }
+
+    // Take `reveal` as the value of the next position to reveal, once it hashes
+    // to the head through the positions skipped since
+    pub fn reveal_chain_position(&mut self, reveal: [u8; 32]) -> Result<()> {
+        let mut value = hash(&reveal).to_bytes();
+        for _ in 0..self.hash_chain_skipped {
+            value = hash(&value).to_bytes();
+        }
+        require!(value == self.hash_chain_head, GameError::InvalidReveal);
+
+        self.hash_chain_head = reveal;
+        self.hash_chain_revealed += 1;
+        self.hash_chain_skipped = 0;
+        Ok(())
+    }
+
+    // Let a wallet play under the access mode, given its entry when it has one
+    pub fn check_access(&self, entry: Option<&AccessEntry>) -> Result<()> {
+        match self.access_mode {
+            ACCESS_ALLOWLIST => require!(
+                entry.is_some_and(|entry| entry.allowed),
+                GameError::PlayerNotAllowed
+            ),
+            ACCESS_DENYLIST => require!(
+                entry.is_none_or(|entry| entry.allowed),
+                GameError::PlayerDenied
+            ),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // Let a wallet play when the table requires an attestation, given its
+    // attestation when it has one
+    pub fn check_attestation(&self, attestation: Option<&Attestation>, now: i64) -> Result<()> {
+        if self.attestation_required {
+            require!(
+                attestation.is_some_and(|attestation| attestation.is_valid(now)),
+                GameError::AttestationRequired
+            );
+        }
+        Ok(())
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, PartialEq)]
 pub enum GameStatus {
     #[default]
-    Active,     // 1
+    Active, // 1
     Win,        // 1
     Lose,       // 1
     Finished,   // 1
@@ -48,6 +180,24 @@ pub struct Initialize<'info> {
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub casino_vault: AccountInfo<'info>,
 
+    #[account(
+        init,
+        space = 8 + Jackpot::DATA_SIZE,
+        seeds = [JACKPOT_SEED.as_bytes()],
+        bump,
+        payer = admin
+    )]
+    pub jackpot: Account<'info, Jackpot>,
+
+    #[account(
+        init,
+        space = 8 + VipConfig::DATA_SIZE,
+        seeds = [VIP_CONFIG_SEED.as_bytes()],
+        bump,
+        payer = admin
+    )]
+    pub vip_config: Account<'info, VipConfig>,
+
     #[account(mut)]
     pub admin: Signer<'info>,
 
@@ -72,7 +222,7 @@ pub struct PlayGame<'info> {
 
     #[account(
         mut,
-        address = global_authority.operation_authority
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
     )]
     pub operator: Signer<'info>,
 
@@ -108,31 +258,119 @@ pub struct PlayGame<'info> {
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub game_vault: AccountInfo<'info>,
 
+    #[account(
+        mut,
+        seeds = [JACKPOT_SEED.as_bytes()],
+        bump,
+    )]
+    pub jackpot: Box<Account<'info, Jackpot>>,
+
+    // Entry of a registered player, scored by the game
+    pub tournament: Option<Account<'info, Tournament>>,
+
+    #[account(mut)]
+    pub tournament_entry: Option<Account<'info, TournamentEntry>>,
+
+    // Limits the owner set on themselves, enforced once opened
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_LIMITS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read and written once this program owns it
+    pub player_limits: AccountInfo<'info>,
+
+    // Allow or deny listing of the owner, checked against the access mode
+    #[account(
+        seeds = [owner.key().as_ref(), ACCESS_ENTRY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub access_entry: AccountInfo<'info>,
+
+    // Verification of the owner, checked when the table requires it
+    #[account(
+        seeds = [owner.key().as_ref(), ATTESTATION_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub attestation: AccountInfo<'info>,
+
+    // Wagered totals of the owner, counted once they have opened them
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub player_stats: AccountInfo<'info>,
+
+    // Rakeback tiers, applied once they have been set
+    #[account(
+        seeds = [VIP_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub vip_config: AccountInfo<'info>,
+
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
-pub struct SetResultParams {
-    round_id: u8,
-    is_win: bool,
-    game_session_id: u64
+impl<'info> PlayGame<'info> {
+    pub fn open_game_accounts(&mut self) -> OpenGameAccounts<'_, 'info> {
+        OpenGameAccounts {
+            owner: &self.owner,
+            operator: &self.operator,
+            player_pool: &mut self.player_pool,
+            global_authority: &mut self.global_authority,
+            casino_vault: &self.casino_vault,
+            game_vault: &self.game_vault,
+            system_program: &self.system_program,
+            rent: &self.rent,
+            player_balance: None,
+            promo_credit: None,
+            player_limits: &self.player_limits,
+            access_entry: &self.access_entry,
+            attestation: &self.attestation,
+            player_stats: &self.player_stats,
+            vip_config: &self.vip_config,
+            jackpot: Some(&mut self.jackpot),
+            tournament: self.tournament.as_ref(),
+            tournament_entry: self.tournament_entry.as_mut(),
+        }
+    }
 }
 
 #[derive(Accounts)]
 #[instruction(
-    params: SetResultParams
+    params: InitPlayGameParams
 )]
-pub struct SetResult<'info> {
+pub struct PlayGameFromBalance<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
     #[account(
         mut,
-        address = global_authority.operation_authority
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
     )]
     pub operator: Signer<'info>,
 
-    /// CHECK:
-    #[account(mut)]
-    pub owner: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), PLAYER_BALANCE_SEED.as_bytes()],
+        bump,
+    )]
+    pub player_balance: Account<'info, PlayerBalance>,
+
+    #[account(
+        init,
+        space = 8 + PlayerPool::DATA_SIZE,
+        seeds = [&owner.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+        payer = operator
+    )]
+    pub player_pool: Account<'info, PlayerPool>,
 
     #[account(
         mut,
@@ -143,10 +381,11 @@ pub struct SetResult<'info> {
 
     #[account(
         mut,
-        seeds = [&owner.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
-        bump
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
     )]
-    pub player_pool: Account<'info, PlayerPool>,
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub casino_vault: AccountInfo<'info>,
 
     #[account(
         mut,
@@ -156,41 +395,117 @@ pub struct SetResult<'info> {
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub game_vault: AccountInfo<'info>,
 
+    // Limits the owner set on themselves, enforced once opened
     #[account(
         mut,
-        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        seeds = [owner.key().as_ref(), PLAYER_LIMITS_SEED.as_bytes()],
         bump,
     )]
-    /// CHECK: This is not dangerous because we don't read or write from this account
-    pub casino_vault: AccountInfo<'info>,
+    /// CHECK: Only read and written once this program owns it
+    pub player_limits: AccountInfo<'info>,
+
+    // Allow or deny listing of the owner, checked against the access mode
+    #[account(
+        seeds = [owner.key().as_ref(), ACCESS_ENTRY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub access_entry: AccountInfo<'info>,
+
+    // Verification of the owner, checked when the table requires it
+    #[account(
+        seeds = [owner.key().as_ref(), ATTESTATION_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub attestation: AccountInfo<'info>,
+
+    // Wagered totals of the owner, counted once they have opened them
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub player_stats: AccountInfo<'info>,
+
+    // Rakeback tiers, applied once they have been set
+    #[account(
+        seeds = [VIP_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub vip_config: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [JACKPOT_SEED.as_bytes()],
+        bump,
+    )]
+    pub jackpot: Box<Account<'info, Jackpot>>,
+
+    // Entry of a registered player, scored by the game
+    pub tournament: Option<Account<'info, Tournament>>,
+
+    #[account(mut)]
+    pub tournament_entry: Option<Account<'info, TournamentEntry>>,
 
     pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
-pub struct DoubleBetParams {
-    is_head: bool,
-    game_session_id: u64
+impl<'info> PlayGameFromBalance<'info> {
+    pub fn open_game_accounts(&mut self) -> OpenGameAccounts<'_, 'info> {
+        OpenGameAccounts {
+            owner: &self.owner,
+            operator: &self.operator,
+            player_pool: &mut self.player_pool,
+            global_authority: &mut self.global_authority,
+            casino_vault: &self.casino_vault,
+            game_vault: &self.game_vault,
+            system_program: &self.system_program,
+            rent: &self.rent,
+            player_balance: Some(&mut self.player_balance),
+            promo_credit: None,
+            player_limits: &self.player_limits,
+            access_entry: &self.access_entry,
+            attestation: &self.attestation,
+            player_stats: &self.player_stats,
+            vip_config: &self.vip_config,
+            jackpot: Some(&mut self.jackpot),
+            tournament: self.tournament.as_ref(),
+            tournament_entry: self.tournament_entry.as_mut(),
+        }
+    }
 }
 
 #[derive(Accounts)]
 #[instruction(
-    params: DoubleBetParams
+    params: InitPlayGameParams
 )]
-pub struct DoubleBet<'info> {
+pub struct PlayGameWithPromoCredit<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
     #[account(
         mut,
-        address = global_authority.operation_authority
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
     )]
     pub operator: Signer<'info>,
 
     #[account(
         mut,
+        seeds = [&owner.key().as_ref(), PROMO_CREDIT_SEED.as_bytes()],
+        bump,
+    )]
+    pub promo_credit: Account<'info, PromoCredit>,
+
+    #[account(
+        init,
+        space = 8 + PlayerPool::DATA_SIZE,
         seeds = [&owner.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
         bump,
+        payer = operator
     )]
     pub player_pool: Account<'info, PlayerPool>,
 
@@ -217,33 +532,125 @@ pub struct DoubleBet<'info> {
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub game_vault: AccountInfo<'info>,
 
+    // Limits the owner set on themselves, enforced once opened
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_LIMITS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read and written once this program owns it
+    pub player_limits: AccountInfo<'info>,
+
+    // Allow or deny listing of the owner, checked against the access mode
+    #[account(
+        seeds = [owner.key().as_ref(), ACCESS_ENTRY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub access_entry: AccountInfo<'info>,
+
+    // Verification of the owner, checked when the table requires it
+    #[account(
+        seeds = [owner.key().as_ref(), ATTESTATION_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub attestation: AccountInfo<'info>,
+
+    // Wagered totals of the owner, counted once they have opened them
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub player_stats: AccountInfo<'info>,
+
+    // Rakeback tiers, applied once they have been set
+    #[account(
+        seeds = [VIP_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub vip_config: AccountInfo<'info>,
+
+    // Entry of a registered player, scored by the game
+    pub tournament: Option<Account<'info, Tournament>>,
+
+    #[account(mut)]
+    pub tournament_entry: Option<Account<'info, TournamentEntry>>,
+
     pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> PlayGameWithPromoCredit<'info> {
+    pub fn open_game_accounts(&mut self, casino_bump: u8) -> OpenGameAccounts<'_, 'info> {
+        OpenGameAccounts {
+            owner: &self.owner,
+            operator: &self.operator,
+            player_pool: &mut self.player_pool,
+            global_authority: &mut self.global_authority,
+            casino_vault: &self.casino_vault,
+            game_vault: &self.game_vault,
+            system_program: &self.system_program,
+            rent: &self.rent,
+            player_balance: None,
+            promo_credit: Some((&mut self.promo_credit, casino_bump)),
+            player_limits: &self.player_limits,
+            access_entry: &self.access_entry,
+            attestation: &self.attestation,
+            player_stats: &self.player_stats,
+            vip_config: &self.vip_config,
+            jackpot: None,
+            tournament: self.tournament.as_ref(),
+            tournament_entry: self.tournament_entry.as_mut(),
+        }
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
-pub struct ClaimRewardParams {
-    game_session_id: u64
+pub struct PlayGameWithIntentParams {
+    pub is_head: bool,
+    pub bet_amount: u64,
+    pub game_session_id: u64,
+    pub expires_at: i64,
+    pub nonce: u64,
 }
 
+// Bet the player signed off-chain, submitted by the operator. Without a
+// session key the intent must be signed by the owner
 #[derive(Accounts)]
 #[instruction(
-    params: ClaimRewardParams
+    params: PlayGameWithIntentParams
 )]
-pub struct ClaimReward<'info> {
+pub struct PlayGameWithIntent<'info> {
     #[account(
         mut,
-        address = global_authority.operation_authority
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
     )]
     pub operator: Signer<'info>,
 
-    /// CHECK:
+    /// CHECK: Proven by the intent signature
     #[account(mut)]
-    pub player: AccountInfo<'info>,
+    pub owner: AccountInfo<'info>,
 
     #[account(
         mut,
-        seeds = [&player.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
-        bump
+        seeds = [&owner.key().as_ref(), PLAYER_BALANCE_SEED.as_bytes()],
+        bump,
+    )]
+    pub player_balance: Account<'info, PlayerBalance>,
+
+    #[account(mut)]
+    pub session_key: Option<Account<'info, SessionKey>>,
+
+    #[account(
+        init,
+        space = 8 + PlayerPool::DATA_SIZE,
+        seeds = [&owner.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+        payer = operator
     )]
     pub player_pool: Account<'info, PlayerPool>,
 
@@ -264,78 +671,3352 @@ pub struct ClaimReward<'info> {
 
     #[account(
         mut,
-        seeds = [&player.key().as_ref(), VAULT_AUTHORITY_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        seeds = [&owner.key().as_ref(), VAULT_AUTHORITY_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
         bump,
     )]
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub game_vault: AccountInfo<'info>,
 
-    pub system_program: Program<'info, System>,
-}
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Instructions sysvar, checked by address
+    pub instructions: AccountInfo<'info>,
 
-#[derive(Accounts)]
-pub struct Withdraw<'info> {
+    // Limits the owner set on themselves, enforced once opened
     #[account(
         mut,
-        address = global_authority.finance_authority
+        seeds = [owner.key().as_ref(), PLAYER_LIMITS_SEED.as_bytes()],
+        bump,
     )]
-    pub financial_admin: Signer<'info>,
+    /// CHECK: Only read and written once this program owns it
+    pub player_limits: AccountInfo<'info>,
 
-    /// CHECK:
-    #[account(mut)]
-    pub recipient: AccountInfo<'info>,
+    // Allow or deny listing of the owner, checked against the access mode
+    #[account(
+        seeds = [owner.key().as_ref(), ACCESS_ENTRY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub access_entry: AccountInfo<'info>,
+
+    // Verification of the owner, checked when the table requires it
+    #[account(
+        seeds = [owner.key().as_ref(), ATTESTATION_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub attestation: AccountInfo<'info>,
 
+    // Wagered totals of the owner, counted once they have opened them
     #[account(
         mut,
-        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        seeds = [owner.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
         bump,
     )]
-    pub global_authority: Box<Account<'info, GlobalPool>>,
+    /// CHECK: Only read once this program owns it
+    pub player_stats: AccountInfo<'info>,
+
+    // Rakeback tiers, applied once they have been set
+    #[account(
+        seeds = [VIP_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub vip_config: AccountInfo<'info>,
 
     #[account(
         mut,
-        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        seeds = [JACKPOT_SEED.as_bytes()],
         bump,
     )]
-    /// CHECK: This is not dangerous because we don't read or write from this account
-    pub casino_vault: AccountInfo<'info>,
+    pub jackpot: Box<Account<'info, Jackpot>>,
+
+    // Entry of a registered player, scored by the game
+    pub tournament: Option<Account<'info, Tournament>>,
+
+    #[account(mut)]
+    pub tournament_entry: Option<Account<'info, TournamentEntry>>,
 
     pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
-#[derive(Accounts)]
-pub struct SetGlobalPool<'info> {
-    #[account(address = global_pool.update_authority)]
-    pub admin: Signer<'info>, // ADMIN_C must be this signer
+impl<'info> PlayGameWithIntent<'info> {
+    pub fn open_game_accounts(&mut self) -> OpenGameAccounts<'_, 'info> {
+        OpenGameAccounts {
+            owner: &self.owner,
+            operator: &self.operator,
+            player_pool: &mut self.player_pool,
+            global_authority: &mut self.global_authority,
+            casino_vault: &self.casino_vault,
+            game_vault: &self.game_vault,
+            system_program: &self.system_program,
+            rent: &self.rent,
+            player_balance: Some(&mut self.player_balance),
+            promo_credit: None,
+            player_limits: &self.player_limits,
+            access_entry: &self.access_entry,
+            attestation: &self.attestation,
+            player_stats: &self.player_stats,
+            vip_config: &self.vip_config,
+            jackpot: Some(&mut self.jackpot),
+            tournament: self.tournament.as_ref(),
+            tournament_entry: self.tournament_entry.as_mut(),
+        }
+    }
+}
 
-    #[account(mut)]
-    pub global_pool: Account<'info, GlobalPool>,
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct PlayDiceParams {
+    pub target: u8,
+    pub bet_amount: u64,
+    pub game_session_id: u64,
 }
 
 #[derive(Accounts)]
-pub struct SetAuthority<'info> {
-    #[account(address = global_pool.super_admin)]
-    pub admin: Signer<'info>,
-
+#[instruction(
+    params: PlayDiceParams
+)]
+pub struct PlayDice<'info> {
     #[account(mut)]
-    pub global_pool: Account<'info, GlobalPool>,
-}
+    pub owner: Signer<'info>,
 
-#[account]
-#[derive(Default)]
-pub struct PlayerPool {
-    // 104
-    pub first_bet: u64,     // 8
-    pub status: GameStatus, // 5
-    pub round: u8,          // 1
-    pub player: Pubkey,     // 32
-}
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    pub operator: Signer<'info>,
 
-impl PlayerPool {
-    pub const DATA_SIZE: usize = 8 + 5 + 1 + 32; // 46
+    #[account(
+        init,
+        space = 8 + PlayerPool::DATA_SIZE,
+        seeds = [&owner.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+        payer = operator
+    )]
+    pub player_pool: Account<'info, PlayerPool>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub casino_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), VAULT_AUTHORITY_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub game_vault: AccountInfo<'info>,
+
+    // Limits the owner set on themselves, enforced once opened
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_LIMITS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read and written once this program owns it
+    pub player_limits: AccountInfo<'info>,
+
+    // Allow or deny listing of the owner, checked against the access mode
+    #[account(
+        seeds = [owner.key().as_ref(), ACCESS_ENTRY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub access_entry: AccountInfo<'info>,
+
+    // Verification of the owner, checked when the table requires it
+    #[account(
+        seeds = [owner.key().as_ref(), ATTESTATION_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub attestation: AccountInfo<'info>,
+
+    // Wagered totals of the owner, counted once they have opened them
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub player_stats: AccountInfo<'info>,
+
+    // Rakeback tiers, applied once they have been set
+    #[account(
+        seeds = [VIP_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub vip_config: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [JACKPOT_SEED.as_bytes()],
+        bump,
+    )]
+    pub jackpot: Box<Account<'info, Jackpot>>,
+
+    // Entry of a registered player, scored by the game
+    pub tournament: Option<Account<'info, Tournament>>,
+
+    #[account(mut)]
+    pub tournament_entry: Option<Account<'info, TournamentEntry>>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> PlayDice<'info> {
+    pub fn open_game_accounts(&mut self) -> OpenGameAccounts<'_, 'info> {
+        OpenGameAccounts {
+            owner: &self.owner,
+            operator: &self.operator,
+            player_pool: &mut self.player_pool,
+            global_authority: &mut self.global_authority,
+            casino_vault: &self.casino_vault,
+            game_vault: &self.game_vault,
+            system_program: &self.system_program,
+            rent: &self.rent,
+            player_balance: None,
+            promo_credit: None,
+            player_limits: &self.player_limits,
+            access_entry: &self.access_entry,
+            attestation: &self.attestation,
+            player_stats: &self.player_stats,
+            vip_config: &self.vip_config,
+            jackpot: Some(&mut self.jackpot),
+            tournament: self.tournament.as_ref(),
+            tournament_entry: self.tournament_entry.as_mut(),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct PlayMultiCoinParams {
+    pub coins: u8,
+    pub heads: u8,
+    pub exact: bool,
+    pub bet_amount: u64,
+    pub game_session_id: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: PlayMultiCoinParams
+)]
+pub struct PlayMultiCoin<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    pub operator: Signer<'info>,
+
+    #[account(
+        init,
+        space = 8 + PlayerPool::DATA_SIZE,
+        seeds = [&owner.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+        payer = operator
+    )]
+    pub player_pool: Account<'info, PlayerPool>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub casino_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), VAULT_AUTHORITY_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub game_vault: AccountInfo<'info>,
+
+    // Limits the owner set on themselves, enforced once opened
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_LIMITS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read and written once this program owns it
+    pub player_limits: AccountInfo<'info>,
+
+    // Allow or deny listing of the owner, checked against the access mode
+    #[account(
+        seeds = [owner.key().as_ref(), ACCESS_ENTRY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub access_entry: AccountInfo<'info>,
+
+    // Verification of the owner, checked when the table requires it
+    #[account(
+        seeds = [owner.key().as_ref(), ATTESTATION_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub attestation: AccountInfo<'info>,
+
+    // Wagered totals of the owner, counted once they have opened them
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub player_stats: AccountInfo<'info>,
+
+    // Rakeback tiers, applied once they have been set
+    #[account(
+        seeds = [VIP_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub vip_config: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [JACKPOT_SEED.as_bytes()],
+        bump,
+    )]
+    pub jackpot: Box<Account<'info, Jackpot>>,
+
+    // Entry of a registered player, scored by the game
+    pub tournament: Option<Account<'info, Tournament>>,
+
+    #[account(mut)]
+    pub tournament_entry: Option<Account<'info, TournamentEntry>>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> PlayMultiCoin<'info> {
+    pub fn open_game_accounts(&mut self) -> OpenGameAccounts<'_, 'info> {
+        OpenGameAccounts {
+            owner: &self.owner,
+            operator: &self.operator,
+            player_pool: &mut self.player_pool,
+            global_authority: &mut self.global_authority,
+            casino_vault: &self.casino_vault,
+            game_vault: &self.game_vault,
+            system_program: &self.system_program,
+            rent: &self.rent,
+            player_balance: None,
+            promo_credit: None,
+            player_limits: &self.player_limits,
+            access_entry: &self.access_entry,
+            attestation: &self.attestation,
+            player_stats: &self.player_stats,
+            vip_config: &self.vip_config,
+            jackpot: Some(&mut self.jackpot),
+            tournament: self.tournament.as_ref(),
+            tournament_entry: self.tournament_entry.as_mut(),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct PlayBatchParams {
+    pub choices: Vec<bool>,
+    pub amounts: Vec<u64>,
+    pub game_session_id: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: PlayBatchParams
+)]
+pub struct PlayBatch<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    pub operator: Signer<'info>,
+
+    #[account(
+        init,
+        space = 8 + PlayerPool::DATA_SIZE + 8 * params.amounts.len(),
+        seeds = [&owner.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+        payer = operator
+    )]
+    pub player_pool: Account<'info, PlayerPool>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub casino_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), VAULT_AUTHORITY_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub game_vault: AccountInfo<'info>,
+
+    // Limits the owner set on themselves, enforced once opened
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_LIMITS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read and written once this program owns it
+    pub player_limits: AccountInfo<'info>,
+
+    // Allow or deny listing of the owner, checked against the access mode
+    #[account(
+        seeds = [owner.key().as_ref(), ACCESS_ENTRY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub access_entry: AccountInfo<'info>,
+
+    // Verification of the owner, checked when the table requires it
+    #[account(
+        seeds = [owner.key().as_ref(), ATTESTATION_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub attestation: AccountInfo<'info>,
+
+    // Wagered totals of the owner, counted once they have opened them
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub player_stats: AccountInfo<'info>,
+
+    // Rakeback tiers, applied once they have been set
+    #[account(
+        seeds = [VIP_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub vip_config: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [JACKPOT_SEED.as_bytes()],
+        bump,
+    )]
+    pub jackpot: Box<Account<'info, Jackpot>>,
+
+    // Entry of a registered player, scored by the game
+    pub tournament: Option<Account<'info, Tournament>>,
+
+    #[account(mut)]
+    pub tournament_entry: Option<Account<'info, TournamentEntry>>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> PlayBatch<'info> {
+    pub fn open_game_accounts(&mut self) -> OpenGameAccounts<'_, 'info> {
+        OpenGameAccounts {
+            owner: &self.owner,
+            operator: &self.operator,
+            player_pool: &mut self.player_pool,
+            global_authority: &mut self.global_authority,
+            casino_vault: &self.casino_vault,
+            game_vault: &self.game_vault,
+            system_program: &self.system_program,
+            rent: &self.rent,
+            player_balance: None,
+            promo_credit: None,
+            player_limits: &self.player_limits,
+            access_entry: &self.access_entry,
+            attestation: &self.attestation,
+            player_stats: &self.player_stats,
+            vip_config: &self.vip_config,
+            jackpot: Some(&mut self.jackpot),
+            tournament: self.tournament.as_ref(),
+            tournament_entry: self.tournament_entry.as_mut(),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct SetResultParams {
+    round_id: u8,
+    is_win: bool,
+    game_session_id: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: SetResultParams
+)]
+pub struct SetResult<'info> {
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    pub operator: Signer<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump
+    )]
+    pub player_pool: Account<'info, PlayerPool>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), VAULT_AUTHORITY_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub game_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub casino_vault: AccountInfo<'info>,
+
+    // Wagered totals of the owner, counted and ranked once they have opened them
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub player_stats: AccountInfo<'info>,
+
+    // Rakeback tiers, applied once they have been set
+    #[account(
+        seeds = [VIP_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub vip_config: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct SetBatchResultParams {
+    round_id: u8,
+    outcomes: u64,
+    game_session_id: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: SetBatchResultParams
+)]
+pub struct SetBatchResult<'info> {
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    pub operator: Signer<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump
+    )]
+    pub player_pool: Account<'info, PlayerPool>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), VAULT_AUTHORITY_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub game_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub casino_vault: AccountInfo<'info>,
+
+    // Wagered totals of the owner, counted and ranked once they have opened them
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub player_stats: AccountInfo<'info>,
+
+    // Rakeback tiers, applied once they have been set
+    #[account(
+        seeds = [VIP_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub vip_config: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct SettleWithSignatureParams {
+    round_id: u8,
+    is_win: bool,
+    game_session_id: u64,
+    nonce: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: SettleWithSignatureParams
+)]
+pub struct SettleWithSignature<'info> {
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    /// CHECK: Only receives the player pool rent, the result is signed off-chain
+    pub operator: AccountInfo<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump
+    )]
+    pub player_pool: Account<'info, PlayerPool>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), VAULT_AUTHORITY_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub game_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub casino_vault: AccountInfo<'info>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Instructions sysvar, checked by address
+    pub instructions: AccountInfo<'info>,
+
+    // Wagered totals of the owner, counted once they have opened them
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub player_stats: AccountInfo<'info>,
+
+    // Rakeback tiers, applied once they have been set
+    #[account(
+        seeds = [VIP_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub vip_config: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct ConsumeRandomnessParams {
+    game_session_id: u64,
+    randomness: [u8; 32],
+}
+
+// Callback from the randomness provider, see `randomness.rs`. Every account
+// after `provider_authority` is handed to the provider when the round starts
+// and must keep this order
+#[derive(Accounts)]
+#[instruction(
+    params: ConsumeRandomnessParams
+)]
+pub struct ConsumeRandomness<'info> {
+    pub provider_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    /// CHECK: Only receives the player pool rent on a loss
+    pub operator: AccountInfo<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump
+    )]
+    pub player_pool: Account<'info, PlayerPool>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), VAULT_AUTHORITY_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub game_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub casino_vault: AccountInfo<'info>,
+
+    // Wagered totals of the owner, counted once they have opened them
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub player_stats: AccountInfo<'info>,
+
+    // Rakeback tiers, applied once they have been set
+    #[account(
+        seeds = [VIP_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub vip_config: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct SettleWithSlotHashParams {
+    game_session_id: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: SettleWithSlotHashParams
+)]
+pub struct SettleWithSlotHash<'info> {
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    /// CHECK: Only receives the player pool rent on a loss
+    pub operator: AccountInfo<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump
+    )]
+    pub player_pool: Account<'info, PlayerPool>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), VAULT_AUTHORITY_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub game_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub casino_vault: AccountInfo<'info>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    /// CHECK: SlotHashes sysvar, checked by address and read raw since it is too large to deserialize
+    pub slot_hashes: AccountInfo<'info>,
+
+    // Wagered totals of the owner, counted once they have opened them
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub player_stats: AccountInfo<'info>,
+
+    // Rakeback tiers, applied once they have been set
+    #[account(
+        seeds = [VIP_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub vip_config: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct SettleWithRevealParams {
+    game_session_id: u64,
+    reveal: [u8; 32],
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: SettleWithRevealParams
+)]
+pub struct SettleWithReveal<'info> {
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    /// CHECK: Only receives the player pool rent, the reveal is checked against the chain
+    pub operator: AccountInfo<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump
+    )]
+    pub player_pool: Account<'info, PlayerPool>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), VAULT_AUTHORITY_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub game_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub casino_vault: AccountInfo<'info>,
+
+    // Wagered totals of the owner, counted once they have opened them
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub player_stats: AccountInfo<'info>,
+
+    // Rakeback tiers, applied once they have been set
+    #[account(
+        seeds = [VIP_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub vip_config: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct DoubleBetParams {
+    is_head: bool,
+    game_session_id: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: DoubleBetParams
+)]
+pub struct DoubleBet<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    pub operator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+    )]
+    pub player_pool: Account<'info, PlayerPool>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub casino_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), VAULT_AUTHORITY_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub game_vault: AccountInfo<'info>,
+
+    // Limits the owner set on themselves, enforced once opened
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_LIMITS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read and written once this program owns it
+    pub player_limits: AccountInfo<'info>,
+
+    // Wagered totals of the owner, counted once they have opened them
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub player_stats: AccountInfo<'info>,
+
+    // Rakeback tiers, applied once they have been set
+    #[account(
+        seeds = [VIP_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub vip_config: AccountInfo<'info>,
+
+    // Entry of a registered player, scored by the game
+    pub tournament: Option<Account<'info, Tournament>>,
+
+    #[account(mut)]
+    pub tournament_entry: Option<Account<'info, TournamentEntry>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DoubleBet<'info> {
+    pub fn double_bet_accounts(&mut self) -> DoubleBetAccounts<'_, 'info> {
+        DoubleBetAccounts {
+            owner: &self.owner,
+            operator: &self.operator,
+            player_pool: &mut self.player_pool,
+            global_authority: &mut self.global_authority,
+            casino_vault: &self.casino_vault,
+            game_vault: &self.game_vault,
+            system_program: &self.system_program,
+            player_limits: &self.player_limits,
+            player_stats: &self.player_stats,
+            vip_config: &self.vip_config,
+            tournament: self.tournament.as_ref(),
+            tournament_entry: self.tournament_entry.as_mut(),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct ClaimRewardParams {
+    game_session_id: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: ClaimRewardParams
+)]
+pub struct ClaimReward<'info> {
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    pub operator: Signer<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub player: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [&player.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump
+    )]
+    pub player_pool: Account<'info, PlayerPool>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub casino_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [&player.key().as_ref(), VAULT_AUTHORITY_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub game_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [JACKPOT_SEED.as_bytes()],
+        bump,
+    )]
+    pub jackpot: Box<Account<'info, Jackpot>>,
+
+    // Entry of a registered player, scored by the game
+    pub tournament: Option<Account<'info, Tournament>>,
+
+    #[account(mut)]
+    pub tournament_entry: Option<Account<'info, TournamentEntry>>,
+
+    // Limits of the player, the payout makes up for their losses
+    #[account(
+        mut,
+        seeds = [player.key().as_ref(), PLAYER_LIMITS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read and written once this program owns it
+    pub player_limits: AccountInfo<'info>,
+
+    // Wagered totals of the owner, ranked once they have opened them
+    #[account(
+        seeds = [player.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub player_stats: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: ClaimRewardParams
+)]
+pub struct ClaimRewardToBalance<'info> {
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    pub operator: Signer<'info>,
+
+    /// CHECK: Only used to derive the game addresses
+    pub player: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [&player.key().as_ref(), PLAYER_BALANCE_SEED.as_bytes()],
+        bump,
+    )]
+    pub player_balance: Account<'info, PlayerBalance>,
+
+    #[account(
+        mut,
+        seeds = [&player.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump
+    )]
+    pub player_pool: Account<'info, PlayerPool>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [&player.key().as_ref(), VAULT_AUTHORITY_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub game_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [JACKPOT_SEED.as_bytes()],
+        bump,
+    )]
+    pub jackpot: Box<Account<'info, Jackpot>>,
+
+    // Tournament the game was played in and the player's entry, scored by the win
+    pub tournament: Option<Account<'info, Tournament>>,
+
+    #[account(mut)]
+    pub tournament_entry: Option<Account<'info, TournamentEntry>>,
+
+    // Limits of the player, the payout makes up for their losses
+    #[account(
+        mut,
+        seeds = [player.key().as_ref(), PLAYER_LIMITS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read and written once this program owns it
+    pub player_limits: AccountInfo<'info>,
+
+    // Wagered totals of the owner, ranked once they have opened them
+    #[account(
+        seeds = [player.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub player_stats: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositBalance<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        space = 8 + PlayerBalance::DATA_SIZE,
+        seeds = [player.key().as_ref(), PLAYER_BALANCE_SEED.as_bytes()],
+        bump,
+        payer = player
+    )]
+    pub player_balance: Account<'info, PlayerBalance>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawBalance<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [player.key().as_ref(), PLAYER_BALANCE_SEED.as_bytes()],
+        bump,
+    )]
+    pub player_balance: Account<'info, PlayerBalance>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct CreateSessionKeyParams {
+    pub key: Pubkey,
+    pub spending_cap: u64,
+    pub expires_at: i64,
+    pub allowed: u8,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: CreateSessionKeyParams
+)]
+pub struct CreateSessionKey<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        space = 8 + SessionKey::DATA_SIZE,
+        seeds = [owner.key().as_ref(), SESSION_KEY_SEED.as_bytes(), params.key.as_ref()],
+        bump,
+        payer = owner
+    )]
+    pub session_key: Account<'info, SessionKey>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct RevokeSessionKeyParams {
+    pub key: Pubkey,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: RevokeSessionKeyParams
+)]
+pub struct RevokeSessionKey<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), SESSION_KEY_SEED.as_bytes(), params.key.as_ref()],
+        bump,
+    )]
+    pub session_key: Account<'info, SessionKey>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: InitPlayGameParams
+)]
+pub struct PlayGameWithSessionKey<'info> {
+    pub signer: Signer<'info>,
+
+    /// CHECK: The session key is derived from it
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), SESSION_KEY_SEED.as_bytes(), signer.key().as_ref()],
+        bump,
+    )]
+    pub session_key: Account<'info, SessionKey>,
+
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    pub operator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), PLAYER_BALANCE_SEED.as_bytes()],
+        bump,
+    )]
+    pub player_balance: Account<'info, PlayerBalance>,
+
+    #[account(
+        init,
+        space = 8 + PlayerPool::DATA_SIZE,
+        seeds = [&owner.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+        payer = operator
+    )]
+    pub player_pool: Account<'info, PlayerPool>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub casino_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), VAULT_AUTHORITY_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub game_vault: AccountInfo<'info>,
+
+    // Limits the owner set on themselves, enforced once opened
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_LIMITS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read and written once this program owns it
+    pub player_limits: AccountInfo<'info>,
+
+    // Allow or deny listing of the owner, checked against the access mode
+    #[account(
+        seeds = [owner.key().as_ref(), ACCESS_ENTRY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub access_entry: AccountInfo<'info>,
+
+    // Verification of the owner, checked when the table requires it
+    #[account(
+        seeds = [owner.key().as_ref(), ATTESTATION_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub attestation: AccountInfo<'info>,
+
+    // Wagered totals of the owner, counted once they have opened them
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub player_stats: AccountInfo<'info>,
+
+    // Rakeback tiers, applied once they have been set
+    #[account(
+        seeds = [VIP_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub vip_config: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [JACKPOT_SEED.as_bytes()],
+        bump,
+    )]
+    pub jackpot: Box<Account<'info, Jackpot>>,
+
+    // Entry of a registered player, scored by the game
+    pub tournament: Option<Account<'info, Tournament>>,
+
+    #[account(mut)]
+    pub tournament_entry: Option<Account<'info, TournamentEntry>>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> PlayGameWithSessionKey<'info> {
+    pub fn open_game_accounts(&mut self) -> OpenGameAccounts<'_, 'info> {
+        OpenGameAccounts {
+            owner: &self.owner,
+            operator: &self.operator,
+            player_pool: &mut self.player_pool,
+            global_authority: &mut self.global_authority,
+            casino_vault: &self.casino_vault,
+            game_vault: &self.game_vault,
+            system_program: &self.system_program,
+            rent: &self.rent,
+            player_balance: Some(&mut self.player_balance),
+            promo_credit: None,
+            player_limits: &self.player_limits,
+            access_entry: &self.access_entry,
+            attestation: &self.attestation,
+            player_stats: &self.player_stats,
+            vip_config: &self.vip_config,
+            jackpot: Some(&mut self.jackpot),
+            tournament: self.tournament.as_ref(),
+            tournament_entry: self.tournament_entry.as_mut(),
+        }
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: DoubleBetParams
+)]
+pub struct DoubleBetWithSessionKey<'info> {
+    pub signer: Signer<'info>,
+
+    /// CHECK: The session key is derived from it
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+
+    #[account(
+        seeds = [owner.key().as_ref(), SESSION_KEY_SEED.as_bytes(), signer.key().as_ref()],
+        bump,
+    )]
+    pub session_key: Account<'info, SessionKey>,
+
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    pub operator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+    )]
+    pub player_pool: Account<'info, PlayerPool>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub casino_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), VAULT_AUTHORITY_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub game_vault: AccountInfo<'info>,
+
+    // Limits the owner set on themselves, enforced once opened
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_LIMITS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read and written once this program owns it
+    pub player_limits: AccountInfo<'info>,
+
+    // Wagered totals of the owner, counted once they have opened them
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub player_stats: AccountInfo<'info>,
+
+    // Rakeback tiers, applied once they have been set
+    #[account(
+        seeds = [VIP_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub vip_config: AccountInfo<'info>,
+
+    // Entry of a registered player, scored by the game
+    pub tournament: Option<Account<'info, Tournament>>,
+
+    #[account(mut)]
+    pub tournament_entry: Option<Account<'info, TournamentEntry>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DoubleBetWithSessionKey<'info> {
+    pub fn double_bet_accounts(&mut self) -> DoubleBetAccounts<'_, 'info> {
+        DoubleBetAccounts {
+            owner: &self.owner,
+            operator: &self.operator,
+            player_pool: &mut self.player_pool,
+            global_authority: &mut self.global_authority,
+            casino_vault: &self.casino_vault,
+            game_vault: &self.game_vault,
+            system_program: &self.system_program,
+            player_limits: &self.player_limits,
+            player_stats: &self.player_stats,
+            vip_config: &self.vip_config,
+            tournament: self.tournament.as_ref(),
+            tournament_entry: self.tournament_entry.as_mut(),
+        }
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: ClaimRewardParams
+)]
+pub struct ClaimRewardWithSessionKey<'info> {
+    pub signer: Signer<'info>,
+
+    /// CHECK: The session key is derived from it, and it receives the reward
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+
+    #[account(
+        seeds = [owner.key().as_ref(), SESSION_KEY_SEED.as_bytes(), signer.key().as_ref()],
+        bump,
+    )]
+    pub session_key: Account<'info, SessionKey>,
+
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    /// CHECK: Only receives the player pool rent
+    pub operator: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump
+    )]
+    pub player_pool: Account<'info, PlayerPool>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), VAULT_AUTHORITY_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub game_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [JACKPOT_SEED.as_bytes()],
+        bump,
+    )]
+    pub jackpot: Box<Account<'info, Jackpot>>,
+
+    // Tournament the game was played in and the player's entry, scored by the win
+    pub tournament: Option<Account<'info, Tournament>>,
+
+    #[account(mut)]
+    pub tournament_entry: Option<Account<'info, TournamentEntry>>,
+
+    // Limits of the player, the payout makes up for their losses
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_LIMITS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read and written once this program owns it
+    pub player_limits: AccountInfo<'info>,
+
+    // Wagered totals of the owner, ranked once they have opened them
+    #[account(
+        seeds = [owner.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub player_stats: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PostBond<'info> {
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    pub operator: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        init_if_needed,
+        space = 8 + OperatorBond::DATA_SIZE,
+        seeds = [OPERATOR_BOND_SEED.as_bytes()],
+        bump,
+        payer = operator
+    )]
+    pub operator_bond: Account<'info, OperatorBond>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawBond<'info> {
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    pub operator: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [OPERATOR_BOND_SEED.as_bytes()],
+        bump,
+    )]
+    pub operator_bond: Account<'info, OperatorBond>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct SlashOperatorParams {
+    game_session_id: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: SlashOperatorParams
+)]
+pub struct SlashOperator<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    /// CHECK: Only receives the player pool rent
+    pub operator: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump
+    )]
+    pub player_pool: Account<'info, PlayerPool>,
+
+    #[account(
+        mut,
+        seeds = [&owner.key().as_ref(), VAULT_AUTHORITY_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub game_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [OPERATOR_BOND_SEED.as_bytes()],
+        bump,
+    )]
+    pub operator_bond: Account<'info, OperatorBond>,
+
+    // Limits of the owner, the payout makes up for their losses
+    #[account(
+        mut,
+        seeds = [owner.key().as_ref(), PLAYER_LIMITS_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read and written once this program owns it
+    pub player_limits: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct CreateChallengeParams {
+    pub challenge_id: u64,
+    pub is_head: bool,
+    pub stake: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: CreateChallengeParams
+)]
+pub struct CreateChallenge<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        init,
+        space = 8 + Challenge::DATA_SIZE,
+        seeds = [creator.key().as_ref(), CHALLENGE_SEED.as_bytes(), &params.challenge_id.to_be_bytes()[..]],
+        bump,
+        payer = creator
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct ChallengeParams {
+    pub challenge_id: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: ChallengeParams
+)]
+pub struct AcceptChallenge<'info> {
+    #[account(mut)]
+    pub opponent: Signer<'info>,
+
+    /// CHECK: Only used to derive the challenge address
+    pub creator: AccountInfo<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [creator.key().as_ref(), CHALLENGE_SEED.as_bytes(), &params.challenge_id.to_be_bytes()[..]],
+        bump,
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: ChallengeParams
+)]
+pub struct CancelChallenge<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(mut)]
+    /// CHECK: Receives the stake and rent back, the challenge seeds tie it to the challenge
+    pub creator: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [creator.key().as_ref(), CHALLENGE_SEED.as_bytes(), &params.challenge_id.to_be_bytes()[..]],
+        bump,
+    )]
+    pub challenge: Account<'info, Challenge>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: ChallengeParams
+)]
+pub struct RefundChallenge<'info> {
+    #[account(mut)]
+    /// CHECK: Receives the stake and rent back, the challenge seeds tie it to the challenge
+    pub creator: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        address = challenge.opponent @ GameError::ChallengeNotAccepted
+    )]
+    /// CHECK: Receives the stake back
+    pub opponent: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [creator.key().as_ref(), CHALLENGE_SEED.as_bytes(), &params.challenge_id.to_be_bytes()[..]],
+        bump,
+    )]
+    pub challenge: Account<'info, Challenge>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: ChallengeParams
+)]
+pub struct SettleChallenge<'info> {
+    #[account(
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    pub operator: Signer<'info>,
+
+    #[account(mut)]
+    /// CHECK: Paid if the creator wins and receives the rent, the challenge seeds tie it to the challenge
+    pub creator: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        address = challenge.opponent @ GameError::ChallengeNotAccepted
+    )]
+    /// CHECK: Paid if the opponent wins
+    pub opponent: AccountInfo<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [creator.key().as_ref(), CHALLENGE_SEED.as_bytes(), &params.challenge_id.to_be_bytes()[..]],
+        bump,
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub casino_vault: AccountInfo<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct CreateTournamentParams {
+    pub tournament_id: u64,
+    pub start_at: i64,
+    pub end_at: i64,
+    pub entry_fee: u64,
+    pub scoring: u8,
+    pub prize_split: Vec<u16>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: CreateTournamentParams
+)]
+pub struct CreateTournament<'info> {
+    #[account(
+        mut,
+        address = global_authority.update_authority @ GameError::UnauthorizedUpdateAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        init,
+        space = 8 + Tournament::DATA_SIZE,
+        seeds = [TOURNAMENT_SEED.as_bytes(), &params.tournament_id.to_be_bytes()[..]],
+        bump,
+        payer = admin
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct TournamentParams {
+    pub tournament_id: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: TournamentParams
+)]
+pub struct FundTournament<'info> {
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [TOURNAMENT_SEED.as_bytes(), &params.tournament_id.to_be_bytes()[..]],
+        bump,
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: TournamentParams
+)]
+pub struct EnterTournament<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [TOURNAMENT_SEED.as_bytes(), &params.tournament_id.to_be_bytes()[..]],
+        bump,
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    #[account(
+        init,
+        space = 8 + TournamentEntry::DATA_SIZE,
+        seeds = [tournament.key().as_ref(), TOURNAMENT_ENTRY_SEED.as_bytes(), player.key().as_ref()],
+        bump,
+        payer = player
+    )]
+    pub tournament_entry: Account<'info, TournamentEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: TournamentParams
+)]
+pub struct RankTournamentEntry<'info> {
+    /// CHECK: Only used to derive the entry address
+    pub player: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [TOURNAMENT_SEED.as_bytes(), &params.tournament_id.to_be_bytes()[..]],
+        bump,
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    #[account(
+        mut,
+        seeds = [tournament.key().as_ref(), TOURNAMENT_ENTRY_SEED.as_bytes(), player.key().as_ref()],
+        bump,
+    )]
+    pub tournament_entry: Account<'info, TournamentEntry>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: TournamentParams
+)]
+pub struct FinalizeTournament<'info> {
+    #[account(
+        mut,
+        seeds = [TOURNAMENT_SEED.as_bytes(), &params.tournament_id.to_be_bytes()[..]],
+        bump,
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Receives the prizes no ranked player takes
+    pub casino_vault: AccountInfo<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct OpenLeaderboardParams {
+    pub period: u8,
+    pub period_index: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: OpenLeaderboardParams
+)]
+pub struct OpenLeaderboard<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        space = 8 + Leaderboard::DATA_SIZE,
+        seeds = [LEADERBOARD_SEED.as_bytes(), &[params.period], &params.period_index.to_be_bytes()[..]],
+        bump,
+        payer = payer
+    )]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPlayerStats<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Only used to derive the stats address
+    pub player: AccountInfo<'info>,
+
+    #[account(
+        init,
+        space = 8 + PlayerStats::DATA_SIZE,
+        seeds = [player.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+        payer = payer
+    )]
+    pub player_stats: Account<'info, PlayerStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct IssuePromoCredit<'info> {
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    pub operator: Signer<'info>,
+
+    /// CHECK: Only used to derive the promo credit address
+    pub player: AccountInfo<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        init_if_needed,
+        space = 8 + PromoCredit::DATA_SIZE,
+        seeds = [player.key().as_ref(), PROMO_CREDIT_SEED.as_bytes()],
+        bump,
+        payer = operator
+    )]
+    pub promo_credit: Account<'info, PromoCredit>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct ClaimPromoRewardParams {
+    pub game_session_id: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: ClaimPromoRewardParams
+)]
+pub struct ClaimPromoReward<'info> {
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    pub operator: Signer<'info>,
+
+    /// CHECK:
+    pub player: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [&player.key().as_ref(), PLAYER_POOL_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump
+    )]
+    pub player_pool: Account<'info, PlayerPool>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub casino_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [&player.key().as_ref(), VAULT_AUTHORITY_SEED.as_bytes(), &params.game_session_id.to_be_bytes()[..]],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub game_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [&player.key().as_ref(), PROMO_CREDIT_SEED.as_bytes()],
+        bump,
+    )]
+    pub promo_credit: Account<'info, PromoCredit>,
+
+    // Tournament the game was played in and the player's entry, scored by the win
+    pub tournament: Option<Account<'info, Tournament>>,
+
+    #[account(mut)]
+    pub tournament_entry: Option<Account<'info, TournamentEntry>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawPromoWinnings<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [player.key().as_ref(), PROMO_CREDIT_SEED.as_bytes()],
+        bump,
+    )]
+    pub promo_credit: Account<'info, PromoCredit>,
+}
+
+#[derive(Accounts)]
+pub struct SetPlayerLimits<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        space = 8 + PlayerLimits::DATA_SIZE,
+        seeds = [player.key().as_ref(), PLAYER_LIMITS_SEED.as_bytes()],
+        bump,
+        payer = player
+    )]
+    pub player_limits: Account<'info, PlayerLimits>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAccessMerkleRoot<'info> {
+    #[account(
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    pub operator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+}
+
+#[derive(Accounts)]
+pub struct SetAccessEntry<'info> {
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    pub operator: Signer<'info>,
+
+    /// CHECK: Only used to derive the entry address
+    pub wallet: AccountInfo<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        init_if_needed,
+        space = 8 + AccessEntry::DATA_SIZE,
+        seeds = [wallet.key().as_ref(), ACCESS_ENTRY_SEED.as_bytes()],
+        bump,
+        payer = operator
+    )]
+    pub access_entry: Account<'info, AccessEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveAccessEntry<'info> {
+    #[account(
+        mut,
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    pub operator: Signer<'info>,
+
+    /// CHECK: Only used to derive the entry address
+    pub wallet: AccountInfo<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [wallet.key().as_ref(), ACCESS_ENTRY_SEED.as_bytes()],
+        bump,
+    )]
+    pub access_entry: Account<'info, AccessEntry>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAllowlistAccess<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    // Plain init, so a denied entry cannot be claimed over
+    #[account(
+        init,
+        space = 8 + AccessEntry::DATA_SIZE,
+        seeds = [player.key().as_ref(), ACCESS_ENTRY_SEED.as_bytes()],
+        bump,
+        payer = player
+    )]
+    pub access_entry: Account<'info, AccessEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct IssueAttestation<'info> {
+    #[account(
+        mut,
+        address = global_authority.compliance_authority @ GameError::UnauthorizedComplianceAuthority
+    )]
+    pub compliance_authority: Signer<'info>,
+
+    /// CHECK: Only used to derive the attestation address
+    pub player: AccountInfo<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        init_if_needed,
+        space = 8 + Attestation::DATA_SIZE,
+        seeds = [player.key().as_ref(), ATTESTATION_SEED.as_bytes()],
+        bump,
+        payer = compliance_authority
+    )]
+    pub attestation: Account<'info, Attestation>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeAttestation<'info> {
+    #[account(
+        address = global_authority.compliance_authority @ GameError::UnauthorizedComplianceAuthority
+    )]
+    pub compliance_authority: Signer<'info>,
+
+    /// CHECK: Only used to derive the attestation address
+    pub player: AccountInfo<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [player.key().as_ref(), ATTESTATION_SEED.as_bytes()],
+        bump,
+    )]
+    pub attestation: Account<'info, Attestation>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRakeback<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [player.key().as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        bump,
+    )]
+    pub player_stats: Account<'info, PlayerStats>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub casino_vault: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        address = global_authority.finance_authority @ GameError::UnauthorizedFinanceAdmin
+    )]
+    pub financial_admin: Signer<'info>,
+
+    /// CHECK:
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub casino_vault: AccountInfo<'info>,
+
+    // Once approvers are set, withdrawals have to go through a proposal
+    #[account(
+        seeds = [WITHDRAWAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: Only read once this program owns it
+    pub withdrawal_config: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetWithdrawalApprovers<'info> {
+    #[account(
+        mut,
+        address = global_authority.super_admin @ GameError::UnauthorizedSuperAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        init_if_needed,
+        space = 8 + WithdrawalConfig::DATA_SIZE,
+        seeds = [WITHDRAWAL_CONFIG_SEED.as_bytes()],
+        bump,
+        payer = admin
+    )]
+    pub withdrawal_config: Box<Account<'info, WithdrawalConfig>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeWithdrawal<'info> {
+    #[account(
+        mut,
+        address = global_authority.finance_authority @ GameError::UnauthorizedFinanceAdmin
+    )]
+    pub financial_admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [WITHDRAWAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub withdrawal_config: Box<Account<'info, WithdrawalConfig>>,
+
+    #[account(
+        init,
+        space = 8 + WithdrawalProposal::DATA_SIZE,
+        seeds = [WITHDRAWAL_PROPOSAL_SEED.as_bytes(), &withdrawal_config.next_proposal_id.to_be_bytes()[..]],
+        bump,
+        payer = financial_admin
+    )]
+    pub proposal: Box<Account<'info, WithdrawalProposal>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct WithdrawalProposalParams {
+    pub proposal_id: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: WithdrawalProposalParams
+)]
+pub struct ApproveWithdrawal<'info> {
+    pub approver: Signer<'info>,
+
+    #[account(
+        seeds = [WITHDRAWAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub withdrawal_config: Box<Account<'info, WithdrawalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [WITHDRAWAL_PROPOSAL_SEED.as_bytes(), &params.proposal_id.to_be_bytes()[..]],
+        bump,
+    )]
+    pub proposal: Box<Account<'info, WithdrawalProposal>>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: WithdrawalProposalParams
+)]
+pub struct ExecuteWithdrawal<'info> {
+    pub signer: Signer<'info>,
+
+    /// CHECK: Receives the proposal's rent
+    #[account(mut, address = proposal.proposer)]
+    pub proposer: AccountInfo<'info>,
+
+    /// CHECK: Checked against the proposal
+    #[account(mut, address = proposal.recipient)]
+    pub recipient: AccountInfo<'info>,
+
+    #[account(
+        seeds = [WITHDRAWAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub withdrawal_config: Box<Account<'info, WithdrawalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [WITHDRAWAL_PROPOSAL_SEED.as_bytes(), &params.proposal_id.to_be_bytes()[..]],
+        bump,
+    )]
+    pub proposal: Box<Account<'info, WithdrawalProposal>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub casino_vault: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: WithdrawalProposalParams
+)]
+pub struct CancelWithdrawal<'info> {
+    pub signer: Signer<'info>,
+
+    /// CHECK: Receives the proposal's rent
+    #[account(mut, address = proposal.proposer)]
+    pub proposer: AccountInfo<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        seeds = [WITHDRAWAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub withdrawal_config: Box<Account<'info, WithdrawalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [WITHDRAWAL_PROPOSAL_SEED.as_bytes(), &params.proposal_id.to_be_bytes()[..]],
+        bump,
+    )]
+    pub proposal: Box<Account<'info, WithdrawalProposal>>,
+}
+
+#[derive(Accounts)]
+pub struct SetGlobalPool<'info> {
+    #[account(address = global_pool.update_authority @ GameError::UnauthorizedUpdateAdmin)]
+    pub admin: Signer<'info>, // ADMIN_C must be this signer
+
+    #[account(mut)]
+    pub global_pool: Account<'info, GlobalPool>,
+}
+
+#[derive(Accounts)]
+pub struct SetJackpot<'info> {
+    #[account(
+        mut,
+        address = global_authority.update_authority @ GameError::UnauthorizedUpdateAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    // Opened here for programs initialized before the jackpot existed
+    #[account(
+        init_if_needed,
+        space = 8 + Jackpot::DATA_SIZE,
+        seeds = [JACKPOT_SEED.as_bytes()],
+        bump,
+        payer = admin
+    )]
+    pub jackpot: Box<Account<'info, Jackpot>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetVipConfig<'info> {
+    #[account(
+        mut,
+        address = global_authority.update_authority @ GameError::UnauthorizedUpdateAdmin
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+
+    // Opened here for programs initialized before VIP tiers existed
+    #[account(
+        init_if_needed,
+        space = 8 + VipConfig::DATA_SIZE,
+        seeds = [VIP_CONFIG_SEED.as_bytes()],
+        bump,
+        payer = admin
+    )]
+    pub vip_config: Box<Account<'info, VipConfig>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PublishHashChain<'info> {
+    #[account(
+        address = global_authority.operation_authority @ GameError::UnauthorizedOperator
+    )]
+    pub operator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: Box<Account<'info, GlobalPool>>,
+}
+
+#[derive(Accounts)]
+pub struct SetAuthority<'info> {
+    #[account(address = global_pool.super_admin)]
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub global_pool: Account<'info, GlobalPool>,
+}
+
+#[derive(Accounts)]
+pub struct ResizeGlobalPool<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+        owner = crate::ID,
+    )]
+    /// CHECK: Read raw, an outdated layout does not deserialize as GlobalPool
+    pub global_authority: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(Default)]
+pub struct PlayerPool {
+    // 104
+    pub first_bet: u64,                         // 8
+    pub status: GameStatus,                     // 5
+    pub round: u8,                              // 1
+    pub player: Pubkey,                         // 32
+    pub is_head: bool,                          // 1
+    pub game_session_id: u64,                   // 8
+    pub nonce: u64,                             // 8
+    pub randomness_provider: Pubkey,            // 32
+    pub target_slot: u64,                       // 8
+    pub player_seed: [u8; 32],                  // 32
+    pub chain_position: u64,                    // 8
+    pub settlement_deadline: i64,               // 8
+    pub dice_target: u8,                        // 1
+    pub coin_count: u8,                         // 1
+    pub heads_target: u8,                       // 1
+    pub exact_heads: bool,                      // 1
+    pub coin_faces: [bool; MAX_COINS as usize], // 10
+    pub batch_choices: u64,                     // 8
+    pub batch_outcomes: u64,                    // 8
+    pub jackpot_eligible: bool,                 // 1
+    pub tournament: Pubkey,                     // 32
+    pub promo_stake: u64,                       // 8
+    pub batch_amounts: Vec<u64>,                // 4 + 8 per flip
+}
+
+impl PlayerPool {
+    pub const DATA_SIZE: usize = 8
+        + 5
+        + 1
+        + 32
+        + 1
+        + 8
+        + 8
+        + 32
+        + 8
+        + 32
+        + 8
+        + 8
+        + 1
+        + 1
+        + 1
+        + 1
+        + 10
+        + 8
+        + 8
+        + 1
+        + 32
+        + 8
+        + 4; // 226
 
     pub fn update_round(&mut self, game_statue: GameStatus, round: u8) {
         self.status = game_statue;
         self.round = round;
     }
+
+    // Odds the round wins: a multi-coin flip from its payout table, a dice
+    // round when the roll is under its target, anything else is a coin flip
+    pub fn win_odds(&self) -> (u64, u64) {
+        match (self.coin_count, self.dice_target) {
+            (0, 0) => (COIN_WIN_CHANCE, PERCENT),
+            (0, target) => (target as u64, PERCENT),
+            (coins, _) => multi_coin_odds(coins, self.heads_target, self.exact_heads),
+        }
+    }
+
+    // A batch game places several coin flips in its only round
+    pub fn is_batch(&self) -> bool {
+        !self.batch_amounts.is_empty()
+    }
+
+    // Rounds drawn by a provider, a slot hash or the hash chain cannot take a
+    // result from the operator
+    pub fn require_operator_settled(&self) -> Result<()> {
+        require!(
+            self.randomness_provider == Pubkey::default(),
+            GameError::SettledByProvider
+        );
+        require!(self.target_slot == 0, GameError::SettledBySlotHash);
+        require!(self.chain_position == 0, GameError::SettledByHashChain);
+        Ok(())
+    }
+    // A game staked with promo credit only pays out into that credit
+    pub fn require_not_promo(&self) -> Result<()> {
+        require!(self.promo_stake == 0, GameError::PromoGame);
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct OperatorBond {
+    pub pending_withdrawal: u64,    // 8
+    pub withdrawal_unlocks_at: i64, // 8
+    pub slashed: bool,              // 1
+    pub slash_count: u64,           // 8
+    pub total_slashed: u64,         // 8
+}
+
+impl OperatorBond {
+    pub const DATA_SIZE: usize = 8 + 8 + 1 + 8 + 8; // 33
+}
+
+// Coin flip between two players, escrowing both stakes until it is settled
+#[account]
+#[derive(Default)]
+pub struct Challenge {
+    pub creator: Pubkey,       // 32
+    pub opponent: Pubkey,      // 32
+    pub challenge_id: u64,     // 8
+    pub stake: u64,            // 8
+    pub creator_is_head: bool, // 1
+    pub expires_at: i64,       // 8
+}
+
+impl Challenge {
+    pub const DATA_SIZE: usize = 32 + 32 + 8 + 8 + 1 + 8; // 89
+
+    pub fn is_open(&self) -> bool {
+        self.opponent == Pubkey::default()
+    }
+}
+
+// Prepaid SOL a player bets from without signing a transfer per game. The
+// balance is whatever the account holds above its rent
+#[account]
+#[derive(Default)]
+pub struct PlayerBalance {
+    pub player: Pubkey,    // 32
+    pub intent_nonce: u64, // 8
+}
+
+impl PlayerBalance {
+    pub const DATA_SIZE: usize = 32 + 8; // 40
+}
+
+// Ephemeral key a player lets sign games for them, up to a total stake and
+// until it expires. `allowed` holds the SESSION_ALLOW_* flags
+#[account]
+#[derive(Default)]
+pub struct SessionKey {
+    pub owner: Pubkey,     // 32
+    pub key: Pubkey,       // 32
+    pub spending_cap: u64, // 8
+    pub spent: u64,        // 8
+    pub expires_at: i64,   // 8
+    pub allowed: u8,       // 1
+}
+
+impl SessionKey {
+    pub const DATA_SIZE: usize = 32 + 32 + 8 + 8 + 8 + 1; // 89
+
+    // Fail unless the key is still live and may sign `instruction`
+    pub fn authorize(&self, instruction: u8) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp < self.expires_at,
+            GameError::SessionKeyExpired
+        );
+        require!(
+            self.allowed & instruction != 0,
+            GameError::SessionKeyNotAllowed
+        );
+        Ok(())
+    }
+
+    // Count `amount` against the spending cap
+    pub fn spend(&mut self, amount: u64) -> Result<()> {
+        let spent = self
+            .spent
+            .checked_add(amount)
+            .ok_or(GameError::MathOverflow)?;
+        require!(
+            spent <= self.spending_cap,
+            GameError::SessionSpendingCapExceeded
+        );
+        self.spent = spent;
+        Ok(())
+    }
+}
+
+// Progressive jackpot fed out of the stake of every bet the player makes and
+// won by claiming a coin flip doubled into a long enough streak. The jackpot is whatever the account
+// holds above its rent
+#[account]
+#[derive(Default)]
+pub struct Jackpot {
+    pub contribution_rate: u64, // 8
+    pub trigger_streak: u8,     // 1
+    pub total_contributed: u64, // 8
+    pub total_paid: u64,        // 8
+    pub last_winner: Pubkey,    // 32
+}
+
+impl Jackpot {
+    pub const DATA_SIZE: usize = 8 + 1 + 8 + 8 + 32; // 57
+
+    // Whether claiming `player_pool` wins the jackpot: a coin flip the player
+    // staked, won on a round at or past the trigger, 0 disables payouts
+    pub fn is_won_by(&self, player_pool: &PlayerPool) -> bool {
+        player_pool.jackpot_eligible
+            && self.trigger_streak != 0
+            && player_pool.round >= self.trigger_streak
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone)]
+pub struct TournamentLeader {
+    pub player: Pubkey, // 32
+    pub score: i64,     // 8
+}
+
+// Competition between the players entered in it, scored from `start_at` to
+// `end_at`. The prize pool is whatever the account holds above its rent, and
+// `leaders` holds the entries ranked after the end, best first
+#[account]
+#[derive(Default)]
+pub struct Tournament {
+    pub tournament_id: u64,             // 8
+    pub start_at: i64,                  // 8
+    pub end_at: i64,                    // 8
+    pub entry_fee: u64,                 // 8
+    pub scoring: u8,                    // 1
+    pub entrants: u64,                  // 8
+    pub finalized: bool,                // 1
+    pub prize_split: Vec<u16>,          // 4 + 2 per place
+    pub leaders: Vec<TournamentLeader>, // 4 + 40 per place
+}
+
+impl Tournament {
+    pub const DATA_SIZE: usize =
+        8 + 8 + 8 + 8 + 1 + 8 + 1 + 4 + 2 * MAX_PRIZE_PLACES + 4 + 40 * MAX_PRIZE_PLACES; // 470
+
+    pub fn is_live(&self, now: i64) -> bool {
+        self.start_at <= now && now < self.end_at
+    }
+
+    // Score of `entry` under the tournament's TOURNAMENT_SCORE_* rule
+    pub fn score(&self, entry: &TournamentEntry) -> i64 {
+        match self.scoring {
+            TOURNAMENT_SCORE_VOLUME => i64::try_from(entry.volume).unwrap_or(i64::MAX),
+            TOURNAMENT_SCORE_STREAK => entry.best_streak as i64,
+            _ => entry.net_profit,
+        }
+    }
+
+    // Place `player` among the leaders, behind those ranked earlier with the
+    // same score, and drop whoever falls past the paid places
+    pub fn rank(&mut self, player: Pubkey, score: i64) {
+        let place = self
+            .leaders
+            .iter()
+            .position(|leader| leader.score < score)
+            .unwrap_or(self.leaders.len());
+        if place < self.prize_split.len() {
+            self.leaders
+                .insert(place, TournamentLeader { player, score });
+            self.leaders.truncate(self.prize_split.len());
+        }
+    }
+}
+
+// A player's registration in a tournament and what their games scored in it
+#[account]
+#[derive(Default)]
+pub struct TournamentEntry {
+    pub tournament: Pubkey, // 32
+    pub player: Pubkey,     // 32
+    pub volume: u64,        // 8
+    pub net_profit: i64,    // 8
+    pub best_streak: u8,    // 1
+    pub ranked: bool,       // 1
+}
+
+impl TournamentEntry {
+    pub const DATA_SIZE: usize = 32 + 32 + 8 + 8 + 1 + 1; // 82
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone)]
+pub struct LeaderboardRow {
+    pub player: Pubkey, // 32
+    pub amount: u64,    // 8
+}
+
+// Top players of one period by wagered volume and by biggest win, best first,
+// so the frontend shows the rankings from a single account
+#[account]
+#[derive(Default)]
+pub struct Leaderboard {
+    pub period: u8,                     // 1
+    pub period_index: u64,              // 8
+    pub by_volume: Vec<LeaderboardRow>, // 4 + 40 per row
+    pub by_win: Vec<LeaderboardRow>,    // 4 + 40 per row
+}
+
+impl Leaderboard {
+    pub const DATA_SIZE: usize = 1 + 8 + 4 + 40 * LEADERBOARD_SIZE + 4 + 40 * LEADERBOARD_SIZE; // 817
+
+    // Index of the LEADERBOARD_* period running at `now`, all-time has only 0
+    pub fn period_index(period: u8, now: i64) -> u64 {
+        match period {
+            LEADERBOARD_DAILY => (now / DAY) as u64,
+            LEADERBOARD_WEEKLY => (now / WEEK) as u64,
+            _ => 0,
+        }
+    }
+
+    pub fn is_current(&self, now: i64) -> bool {
+        self.period_index == Self::period_index(self.period, now)
+    }
+
+    // Both rankings only ever see a player's amount grow within a period, so
+    // keeping the top rows is enough to rank everyone exactly
+    pub fn record_volume(&mut self, player: Pubkey, volume: u64) {
+        Self::place(&mut self.by_volume, player, volume);
+    }
+
+    pub fn record_win(&mut self, player: Pubkey, amount: u64) {
+        Self::place(&mut self.by_win, player, amount);
+    }
+
+    // Move `player` to `amount` unless they already rank higher, behind the
+    // rows with the same amount
+    fn place(rows: &mut Vec<LeaderboardRow>, player: Pubkey, amount: u64) {
+        if let Some(current) = rows.iter().position(|row| row.player == player) {
+            if rows[current].amount >= amount {
+                return;
+            }
+            rows.remove(current);
+        }
+        let place = rows
+            .iter()
+            .position(|row| row.amount < amount)
+            .unwrap_or(rows.len());
+        if place < LEADERBOARD_SIZE {
+            rows.insert(place, LeaderboardRow { player, amount });
+            rows.truncate(LEADERBOARD_SIZE);
+        }
+    }
+}
+
+// What a player wagered today, this week and ever, ranked on the leaderboards,
+// and the rakeback their VIP tier earned them
+#[account]
+#[derive(Default)]
+pub struct PlayerStats {
+    pub player: Pubkey,     // 32
+    pub day: u64,           // 8
+    pub daily_volume: u64,  // 8
+    pub week: u64,          // 8
+    pub weekly_volume: u64, // 8
+    pub total_volume: u64,  // 8
+    pub vip_tier: u8,       // 1
+    pub rakeback: u64,      // 8
+    pub rakeback_paid: u64, // 8
+}
+
+impl PlayerStats {
+    pub const DATA_SIZE: usize = 32 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8; // 89
+
+    // Add a settled stake, starting the daily and weekly totals over when
+    // their period has changed
+    pub fn record(&mut self, stake: u64, now: i64) -> Result<()> {
+        let day = Leaderboard::period_index(LEADERBOARD_DAILY, now);
+        if self.day != day {
+            self.day = day;
+            self.daily_volume = 0;
+        }
+        let week = Leaderboard::period_index(LEADERBOARD_WEEKLY, now);
+        if self.week != week {
+            self.week = week;
+            self.weekly_volume = 0;
+        }
+
+        self.daily_volume = self
+            .daily_volume
+            .checked_add(stake)
+            .ok_or(GameError::MathOverflow)?;
+        self.weekly_volume = self
+            .weekly_volume
+            .checked_add(stake)
+            .ok_or(GameError::MathOverflow)?;
+        self.total_volume = self
+            .total_volume
+            .checked_add(stake)
+            .ok_or(GameError::MathOverflow)?;
+        Ok(())
+    }
+
+    // Volume of the LEADERBOARD_* period running at `now`, nothing yet when
+    // the last stake was in an earlier one
+    pub fn volume(&self, period: u8, now: i64) -> u64 {
+        let index = Leaderboard::period_index(period, now);
+        match period {
+            LEADERBOARD_DAILY if self.day == index => self.daily_volume,
+            LEADERBOARD_WEEKLY if self.week == index => self.weekly_volume,
+            LEADERBOARD_DAILY | LEADERBOARD_WEEKLY => 0,
+            _ => self.total_volume,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct VipTier {
+    pub threshold: u64, // 8
+    pub rakeback: u16,  // 2
+}
+
+// VIP tiers by lifetime wagered amount, lowest first. A player in tier `n`
+// (1-based, 0 below the first threshold) gets its rakeback in permille of the
+// house edge on every settled stake of their own
+#[account]
+#[derive(Default)]
+pub struct VipConfig {
+    pub tiers: Vec<VipTier>, // 4 + 10 per tier
+}
+
+impl VipConfig {
+    pub const DATA_SIZE: usize = 4 + 10 * MAX_VIP_TIERS; // 84
+
+    pub fn is_valid(tiers: &[VipTier]) -> bool {
+        tiers.len() <= MAX_VIP_TIERS
+            && tiers
+                .windows(2)
+                .all(|pair| pair[0].threshold < pair[1].threshold)
+            && tiers.iter().all(|tier| tier.rakeback as u64 <= PERMILLE)
+    }
+
+    // Tier reached with `volume` wagered, and its rakeback rate
+    pub fn tier(&self, volume: u64) -> (u8, u16) {
+        let reached = self
+            .tiers
+            .iter()
+            .take_while(|tier| tier.threshold <= volume)
+            .count();
+        match reached {
+            0 => (0, 0),
+            n => (n as u8, self.tiers[n - 1].rakeback),
+        }
+    }
+}
+
+// Free-bet credit the operator hands a player. The house stakes the credit,
+// and net winnings stay locked here until the player has wagered
+// `wagering_requirement` in promo games
+#[account]
+#[derive(Default)]
+pub struct PromoCredit {
+    pub player: Pubkey,            // 32
+    pub credit: u64,               // 8
+    pub wagering_requirement: u64, // 8
+    pub wagered: u64,              // 8
+    pub locked_winnings: u64,      // 8
+}
+
+impl PromoCredit {
+    pub const DATA_SIZE: usize = 32 + 8 + 8 + 8 + 8; // 64
+
+    // Take a bet out of the credit, counting it towards the requirement
+    pub fn stake(&mut self, amount: u64) -> Result<()> {
+        self.credit = self
+            .credit
+            .checked_sub(amount)
+            .ok_or(GameError::InsufficientPromoCredit)?;
+        self.wagered = self
+            .wagered
+            .checked_add(amount)
+            .ok_or(GameError::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.wagered >= self.wagering_requirement
+    }
+}
+
+// Responsible gambling limits a player sets on themselves. Wager and loss
+// limits are per LIMIT_* period, 0 for no limit. Losses count every play_game
+// stake as lost until claim_reward pays it back. A looser setting only applies
+// from `pending_at`, LIMITS_COOLING_OFF after it was asked for
+#[account]
+#[derive(Default)]
+pub struct PlayerLimits {
+    pub player: Pubkey,                   // 32
+    pub self_excluded_until: i64,         // 8
+    pub wager_limits: [u64; 3],           // 24
+    pub loss_limits: [u64; 3],            // 24
+    pub pending_self_excluded_until: i64, // 8
+    pub pending_wager_limits: [u64; 3],   // 24
+    pub pending_loss_limits: [u64; 3],    // 24
+    pub pending_at: i64,                  // 8
+    pub periods: [u64; 3],                // 24
+    pub wagered: [u64; 3],                // 24
+    pub lost: [u64; 3],                   // 24
+}
+
+impl PlayerLimits {
+    pub const DATA_SIZE: usize = 32 + 8 + 24 + 24 + 8 + 24 + 24 + 8 + 24 + 24 + 24; // 224
+
+    // Tighter settings apply at once, looser ones wait out the cooling-off
+    pub fn update(
+        &mut self,
+        wager_limits: [u64; 3],
+        loss_limits: [u64; 3],
+        self_excluded_until: i64,
+        now: i64,
+    ) {
+        self.apply_pending(now);
+
+        let mut loosened = false;
+        for (current, new) in self
+            .wager_limits
+            .iter_mut()
+            .chain(self.loss_limits.iter_mut())
+            .zip(wager_limits.iter().chain(loss_limits.iter()))
+        {
+            if limit_or_max(*new) > limit_or_max(*current) {
+                loosened = true;
+            } else {
+                *current = *new;
+            }
+        }
+        if self_excluded_until < self.self_excluded_until {
+            loosened = true;
+        } else {
+            self.self_excluded_until = self_excluded_until;
+        }
+
+        self.pending_wager_limits = wager_limits;
+        self.pending_loss_limits = loss_limits;
+        self.pending_self_excluded_until = self_excluded_until;
+        self.pending_at = if loosened {
+            now + LIMITS_COOLING_OFF
+        } else {
+            0
+        };
+    }
+
+    fn apply_pending(&mut self, now: i64) {
+        if self.pending_at != 0 && now >= self.pending_at {
+            self.wager_limits = self.pending_wager_limits;
+            self.loss_limits = self.pending_loss_limits;
+            self.self_excluded_until = self.pending_self_excluded_until;
+            self.pending_at = 0;
+        }
+    }
+
+    // Start the totals of every period that has changed over
+    fn roll_periods(&mut self, now: i64) {
+        for (i, length) in LIMIT_PERIODS.iter().enumerate() {
+            let period = (now / length) as u64;
+            if self.periods[i] != period {
+                self.periods[i] = period;
+                self.wagered[i] = 0;
+                self.lost[i] = 0;
+            }
+        }
+    }
+
+    // Count a bet of `stake` that can lose `loss` of the player's own funds,
+    // rejecting it while self-excluded or when it would breach a limit
+    pub fn place_bet(&mut self, stake: u64, loss: u64, now: i64) -> Result<()> {
+        self.apply_pending(now);
+        require!(now >= self.self_excluded_until, GameError::SelfExcluded);
+        self.roll_periods(now);
+
+        for i in 0..LIMIT_PERIODS.len() {
+            let wagered = self.wagered[i]
+                .checked_add(stake)
+                .ok_or(GameError::MathOverflow)?;
+            require!(
+                wagered <= limit_or_max(self.wager_limits[i]),
+                GameError::WagerLimitExceeded
+            );
+            let lost = self.lost[i]
+                .checked_add(loss)
+                .ok_or(GameError::MathOverflow)?;
+            require!(
+                lost <= limit_or_max(self.loss_limits[i]),
+                GameError::LossLimitExceeded
+            );
+            self.wagered[i] = wagered;
+            self.lost[i] = lost;
+        }
+        Ok(())
+    }
+
+    // Take a claimed payout off the losses of the current periods
+    pub fn record_payout(&mut self, payout: u64, now: i64) {
+        self.roll_periods(now);
+        for lost in self.lost.iter_mut() {
+            *lost = lost.saturating_sub(payout);
+        }
+    }
+}
+
+fn limit_or_max(limit: u64) -> u64 {
+    if limit == 0 {
+        u64::MAX
+    } else {
+        limit
+    }
+}
+
+// Listing of one wallet set by the operation authority, or claimed by the
+// wallet itself with a proof against the allowlist Merkle root
+#[account]
+#[derive(Default)]
+pub struct AccessEntry {
+    pub wallet: Pubkey, // 32
+    pub allowed: bool,  // 1
+}
+
+impl AccessEntry {
+    pub const DATA_SIZE: usize = 32 + 1; // 33
+}
+
+// Verified status of one player as stated by the compliance authority. A
+// revoked attestation is kept, marked unverified, until it is issued again
+#[account]
+#[derive(Default)]
+pub struct Attestation {
+    pub player: Pubkey,  // 32
+    pub verified: bool,  // 1
+    pub expires_at: i64, // 8
+    pub issued_at: i64,  // 8
+}
+
+impl Attestation {
+    pub const DATA_SIZE: usize = 32 + 1 + 8 + 8; // 49
+
+    pub fn is_valid(&self, now: i64) -> bool {
+        self.verified && now < self.expires_at
+    }
+}
+
+// Approvers that sign off casino vault withdrawals. With a threshold above 0,
+// `withdraw` is disabled and every withdrawal needs that many approvals
+#[account]
+#[derive(Default)]
+pub struct WithdrawalConfig {
+    pub approvers: Vec<Pubkey>, // 4 + 32 per approver
+    pub threshold: u8,          // 1
+    pub proposal_timeout: i64,  // 8
+    pub next_proposal_id: u64,  // 8
+}
+
+impl WithdrawalConfig {
+    pub const DATA_SIZE: usize = 4 + 32 * MAX_WITHDRAWAL_APPROVERS + 1 + 8 + 8; // 341
+
+    pub fn is_valid(approvers: &[Pubkey], threshold: u8, proposal_timeout: i64) -> bool {
+        approvers.len() <= MAX_WITHDRAWAL_APPROVERS
+            && approvers
+                .iter()
+                .enumerate()
+                .all(|(i, approver)| !approvers[..i].contains(approver))
+            && threshold as usize <= approvers.len()
+            && (threshold > 0 || approvers.is_empty())
+            && proposal_timeout > 0
+    }
+
+    pub fn is_approver(&self, key: &Pubkey) -> bool {
+        self.approvers.contains(key)
+    }
+
+    // Approvals of `proposal` from keys that are still approvers
+    pub fn approvals(&self, proposal: &WithdrawalProposal) -> usize {
+        proposal
+            .approvals
+            .iter()
+            .filter(|approver| self.is_approver(approver))
+            .count()
+    }
+}
+
+// Casino vault withdrawal waiting for approvals, closed to the proposer once
+// executed or cancelled
+#[account]
+#[derive(Default)]
+pub struct WithdrawalProposal {
+    pub proposal_id: u64,       // 8
+    pub proposer: Pubkey,       // 32
+    pub recipient: Pubkey,      // 32
+    pub amount: u64,            // 8
+    pub expires_at: i64,        // 8
+    pub approvals: Vec<Pubkey>, // 4 + 32 per approver
+}
+
+impl WithdrawalProposal {
+    pub const DATA_SIZE: usize = 8 + 32 + 32 + 8 + 8 + 4 + 32 * MAX_WITHDRAWAL_APPROVERS; // 412
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.expires_at
+    }
 }
@@ -3,11 +3,92 @@ pub const CF_VERSION: &str = "0.3"; // hack proof
 pub const GLOBAL_AUTHORITY_SEED: &str = "global-authority";
 pub const VAULT_AUTHORITY_SEED: &str = "vault-authority";
 pub const PLAYER_POOL_SEED: &str = "player-pool";
+pub const RANDOMNESS_REQUESTER_SEED: &str = "randomness-requester";
+pub const PROVIDER_AUTHORITY_SEED: &str = "provider-authority";
+pub const OPERATOR_BOND_SEED: &str = "operator-bond";
+pub const CHALLENGE_SEED: &str = "challenge";
+pub const PLAYER_BALANCE_SEED: &str = "player-balance";
+pub const SESSION_KEY_SEED: &str = "session-key";
+pub const JACKPOT_SEED: &str = "jackpot";
+pub const TOURNAMENT_SEED: &str = "tournament";
+pub const TOURNAMENT_ENTRY_SEED: &str = "tournament-entry";
+pub const LEADERBOARD_SEED: &str = "leaderboard";
+pub const PLAYER_STATS_SEED: &str = "player-stats";
+pub const VIP_CONFIG_SEED: &str = "vip-config";
+pub const PROMO_CREDIT_SEED: &str = "promo-credit";
+pub const PLAYER_LIMITS_SEED: &str = "player-limits";
+pub const ACCESS_ENTRY_SEED: &str = "access-entry";
+pub const ATTESTATION_SEED: &str = "attestation";
+pub const WITHDRAWAL_CONFIG_SEED: &str = "withdrawal-config";
+pub const WITHDRAWAL_PROPOSAL_SEED: &str = "withdrawal-proposal";
 
 pub const MAX_NAME_LENGTH: usize = 24;
 
 pub const PERMILLE: u64 = 1000;
+pub const PERCENT: u64 = 100;
+
+// Percent chance a coin flip wins, and the targets a dice roll can be under
+pub const COIN_WIN_CHANCE: u64 = PERCENT / 2;
+pub const DICE_MIN_TARGET: u8 = 1;
+pub const DICE_MAX_TARGET: u8 = 99;
+
+// Coins a multi-coin flip can throw at once
+pub const MAX_COINS: u8 = 10;
+
+// Flips a batch can place at once, each gets a bit of the outcome bitmask
+pub const MAX_BATCH_FLIPS: usize = 50;
+
+// Instructions a session key can be allowed to sign, as bit flags
+pub const SESSION_ALLOW_PLAY: u8 = 1;
+pub const SESSION_ALLOW_DOUBLE_BET: u8 = 2;
+pub const SESSION_ALLOW_CLAIM: u8 = 4;
+
+// What a tournament ranks its entries by
+pub const TOURNAMENT_SCORE_NET_PROFIT: u8 = 0;
+pub const TOURNAMENT_SCORE_VOLUME: u8 = 1;
+pub const TOURNAMENT_SCORE_STREAK: u8 = 2;
+
+// Places a tournament can pay a prize to
+pub const MAX_PRIZE_PLACES: usize = 10;
+
+// Periods a leaderboard ranks players over, and the rows it keeps per ranking
+pub const LEADERBOARD_DAILY: u8 = 0;
+pub const LEADERBOARD_WEEKLY: u8 = 1;
+pub const LEADERBOARD_ALL_TIME: u8 = 2;
+pub const LEADERBOARD_SIZE: usize = 10;
+pub const DAY: i64 = 86400; // seconds
+pub const WEEK: i64 = 7 * DAY;
+
+// VIP tiers the config can hold, each a wagered threshold and its rakeback
+pub const MAX_VIP_TIERS: usize = 8;
+
+// Periods a player's wager and loss limits run over
+pub const LIMIT_DAILY: usize = 0;
+pub const LIMIT_WEEKLY: usize = 1;
+pub const LIMIT_MONTHLY: usize = 2;
+pub const MONTH: i64 = 30 * DAY;
+pub const LIMIT_PERIODS: [i64; 3] = [DAY, WEEK, MONTH];
+
+// Who play_game lets in: anyone, allowed wallets only, or anyone not denied
+pub const ACCESS_OPEN: u8 = 0;
+pub const ACCESS_ALLOWLIST: u8 = 1;
+pub const ACCESS_DENYLIST: u8 = 2;
+
+// Approvers that can sign off casino vault withdrawals
+pub const MAX_WITHDRAWAL_APPROVERS: usize = 10;
+
+// Slots the SlotHashes sysvar keeps a hash for
+pub const SLOT_HASHES_MAX_ENTRIES: u64 = 512;
 
 pub const RTP: u64 = 95; // 95%
 pub const MAX_WIN_AMOUNT: u64 = 10000000000;
 pub const MIN_BET_AMOUNT: u64 = 100000000;
+pub const SETTLEMENT_TIMEOUT: i64 = 3600; // seconds
+pub const MIN_BOND_WITHDRAWAL_DELAY: i64 = 2 * SETTLEMENT_TIMEOUT; // seconds
+pub const CHALLENGE_RAKE: u64 = 25; // 2.5%
+pub const MAX_CHALLENGE_RAKE: u64 = 100; // 10%
+pub const CHALLENGE_TIMEOUT: i64 = 86400; // seconds
+pub const JACKPOT_STREAK: u8 = 10; // wins in a row
+pub const MAX_JACKPOT_CONTRIBUTION: u64 = 50; // 5%
+pub const TOURNAMENT_RANKING_PERIOD: i64 = 86400; // seconds
+pub const LIMITS_COOLING_OFF: i64 = 86400; // seconds
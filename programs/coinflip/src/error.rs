@@ -26,4 +26,164 @@ pub enum GameError {
     UnauthorizedFinanceAdmin,
     #[msg("Only Update Admin can call this")] // 6011
     UnauthorizedUpdateAdmin,
+    #[msg("Arithmetic overflow")] // 6012
+    MathOverflow,
+    #[msg("Invalid operator signature")] // 6013
+    InvalidSignature,
+    #[msg("Invalid settlement nonce")] // 6014
+    InvalidNonce,
+    #[msg("Only Super Admin can call this")] // 6015
+    UnauthorizedSuperAdmin,
+    #[msg("Invalid randomness provider accounts")] // 6016
+    InvalidRandomnessProvider,
+    #[msg("Only the randomness provider can settle this game")] // 6017
+    UnauthorizedRandomnessProvider,
+    #[msg("Game is settled by the randomness provider")] // 6018
+    SettledByProvider,
+    #[msg("Slot hash games are disabled")] // 6019
+    SlotHashDisabled,
+    #[msg("Bet exceeds the slot hash game ceiling")] // 6020
+    SlotHashBetTooLarge,
+    #[msg("Not a slot hash game")] // 6021
+    NotSlotHashGame,
+    #[msg("Target slot hash is not available yet")] // 6022
+    SlotHashNotReady,
+    #[msg("Game is settled by slot hash")] // 6023
+    SettledBySlotHash,
+    #[msg("Invalid slot hash delay")] // 6024
+    InvalidSlotHashDelay,
+    #[msg("Game is settled by the hash chain")] // 6025
+    SettledByHashChain,
+    #[msg("Not a hash chain game")] // 6026
+    NotHashChainGame,
+    #[msg("Reveal does not hash to the chain head")] // 6027
+    InvalidReveal,
+    #[msg("Earlier hash chain positions are not settled yet")] // 6028
+    HashChainOutOfOrder,
+    #[msg("Hash chain has unrevealed positions")] // 6029
+    HashChainPending,
+    #[msg("Game has no settlement deadline")] // 6030
+    NoSettlementDeadline,
+    #[msg("Settlement deadline has not passed")] // 6031
+    SettlementNotOverdue,
+    #[msg("Bond withdrawal is still locked")] // 6032
+    BondWithdrawalLocked,
+    #[msg("Bond is too small")] // 6033
+    InsufficientBond,
+    #[msg("Invalid settlement timeout")] // 6034
+    InvalidSettlementTimeout,
+    #[msg("Dice target must be between 1 and 99")] // 6035
+    InvalidDiceTarget,
+    #[msg("A multi-coin flip throws 1 to 10 coins")] // 6036
+    InvalidCoinCount,
+    #[msg("Heads target must be at most the coin count, and above 0 for an at-least bet")] // 6037
+    InvalidHeadsTarget,
+    #[msg("Player versus player challenges are disabled")] // 6038
+    ChallengesDisabled,
+    #[msg("Challenge was already accepted")] // 6039
+    ChallengeNotOpen,
+    #[msg("Challenge has expired")] // 6040
+    ChallengeExpired,
+    #[msg("Cannot accept your own challenge")] // 6041
+    OwnChallenge,
+    #[msg("Only the creator can cancel a challenge before it expires")] // 6042
+    ChallengeNotCancellable,
+    #[msg("Challenge has no opponent yet")] // 6043
+    ChallengeNotAccepted,
+    #[msg("Accepted challenge is still within its settlement deadline")] // 6044
+    ChallengeNotOverdue,
+    #[msg("Challenge rake is above the maximum")] // 6045
+    InvalidChallengeRake,
+    #[msg("A batch places 1 to 50 flips with one amount per choice")] // 6046
+    InvalidBatch,
+    #[msg("Batch games are settled with set_batch_result")] // 6047
+    SettledAsBatch,
+    #[msg("Not a batch game")] // 6048
+    NotBatchGame,
+    #[msg("Player balance is too small")] // 6049
+    InsufficientPlayerBalance,
+    #[msg("Session key has expired")] // 6050
+    SessionKeyExpired,
+    #[msg("Session key is not allowed to sign this instruction")] // 6051
+    SessionKeyNotAllowed,
+    #[msg("Session key spending cap exceeded")] // 6052
+    SessionSpendingCapExceeded,
+    #[msg("Bet intent has expired")] // 6053
+    IntentExpired,
+    #[msg("Bet intent is not signed by the player")] // 6054
+    InvalidIntentSignature,
+    #[msg("Bet intent nonce was already used or skips ahead")] // 6055
+    InvalidIntentNonce,
+    #[msg("Jackpot contribution is above the maximum")] // 6056
+    InvalidJackpotContribution,
+    #[msg("Invalid tournament times, scoring rule or prize split")] // 6057
+    InvalidTournament,
+    #[msg("Tournament is not running")] // 6058
+    TournamentNotLive,
+    #[msg("Tournament entry does not belong to this player and tournament")] // 6059
+    InvalidTournamentEntry,
+    #[msg("Tournament has not ended yet")] // 6060
+    TournamentNotOver,
+    #[msg("Tournament is still being ranked")] // 6061
+    TournamentRanking,
+    #[msg("Tournament is already finalized")] // 6062
+    TournamentFinalized,
+    #[msg("Tournament entry is already ranked")] // 6063
+    EntryAlreadyRanked,
+    #[msg("Prize recipients must be the ranked players in order")] // 6064
+    InvalidPrizeRecipient,
+    #[msg("Leaderboard period is unknown or already over")] // 6065
+    InvalidLeaderboard,
+    #[msg("VIP tiers must rise in threshold with rakeback up to the house edge")] // 6066
+    InvalidVipTiers,
+    #[msg("Player stats need the VIP config to accrue rakeback")] // 6067
+    MissingVipConfig,
+    #[msg("No rakeback to claim")] // 6068
+    NoRakeback,
+    #[msg("Not enough promo credit for the bet")] // 6069
+    InsufficientPromoCredit,
+    #[msg("Promo game winnings only go to the promo credit")] // 6070
+    PromoGame,
+    #[msg("Not a promo game")] // 6071
+    NotPromoGame,
+    #[msg("Promo winnings are locked until the wagering requirement is met")] // 6072
+    WageringRequirementNotMet,
+    #[msg("No promo winnings to withdraw")] // 6073
+    NoPromoWinnings,
+    #[msg("Player is self-excluded")] // 6074
+    SelfExcluded,
+    #[msg("Bet would exceed the player's wager limit")] // 6075
+    WagerLimitExceeded,
+    #[msg("Bet would exceed the player's loss limit")] // 6076
+    LossLimitExceeded,
+    #[msg("Invalid access mode")] // 6077
+    InvalidAccessMode,
+    #[msg("Player is not on the allowlist")] // 6078
+    PlayerNotAllowed,
+    #[msg("Player is on the denylist")] // 6079
+    PlayerDenied,
+    #[msg("Invalid Merkle proof")] // 6080
+    InvalidMerkleProof,
+    #[msg("Player has no valid attestation")] // 6081
+    AttestationRequired,
+    #[msg("Attestation must expire in the future")] // 6082
+    InvalidAttestationExpiry,
+    #[msg("Unauthorized compliance authority")] // 6083
+    UnauthorizedComplianceAuthority,
+    #[msg("Invalid withdrawal approvers, threshold or timeout")] // 6084
+    InvalidWithdrawalApprovers,
+    #[msg("Signer cannot approve or cancel this withdrawal")] // 6085
+    UnauthorizedWithdrawalSigner,
+    #[msg("Withdrawal already approved by this signer")] // 6086
+    WithdrawalAlreadyApproved,
+    #[msg("Withdrawal proposal has expired")] // 6087
+    WithdrawalProposalExpired,
+    #[msg("Withdrawal has not reached the approval threshold")] // 6088
+    WithdrawalNotApproved,
+    #[msg("Casino vault withdrawals need approval through a proposal")] // 6089
+    WithdrawalApprovalRequired,
+    #[msg("Game was played in a tournament, claim it with the tournament entry")] // 6090
+    TournamentEntryRequired,
+    #[msg("Bet would pay no more than its stake")] // 6091
+    PayoutBelowStake,
 }
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct JackpotContributed {
+    pub player: Pubkey,
+    pub game_session_id: u64,
+    pub amount: u64,
+    pub balance: u64,
+}
+
+#[event]
+pub struct JackpotWon {
+    pub player: Pubkey,
+    pub game_session_id: u64,
+    pub streak: u8,
+    pub amount: u64,
+}
+
+#[event]
+pub struct JackpotConfigUpdated {
+    pub contribution_rate: u64,
+    pub trigger_streak: u8,
+}
+
+#[event]
+pub struct JackpotFunded {
+    pub amount: u64,
+    pub balance: u64,
+}
+
+#[event]
+pub struct RakebackClaimed {
+    pub player: Pubkey,
+    pub vip_tier: u8,
+    pub amount: u64,
+}
+
+#[event]
+pub struct WithdrawalProposed {
+    pub proposal_id: u64,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct WithdrawalApproved {
+    pub proposal_id: u64,
+    pub approver: Pubkey,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct WithdrawalExecuted {
+    pub proposal_id: u64,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
@@ -5,11 +5,17 @@ use solana_program::pubkey::Pubkey;
 pub mod account;
 pub mod constants;
 pub mod error;
+pub mod events;
+pub mod math;
+pub mod randomness;
 pub mod utils;
 
 use account::*;
 use constants::*;
 use error::*;
+use events::*;
+use math::*;
+use randomness::*;
 use utils::*;
 
 declare_id!("AF8kyU67aGDW42CZMz3tpuJL1hu7e3Xoxv614t1FsSzq");
@@ -17,7 +23,6 @@ declare_id!("AF8kyU67aGDW42CZMz3tpuJL1hu7e3Xoxv614t1FsSzq");
 #[program]
 pub mod coinflip {
     use super::*;
-    use solana_program::native_token::LAMPORTS_PER_SOL;
     pub fn initialize(
         ctx: Context<Initialize>,
         operate_admin: Pubkey,
@@ -33,6 +38,10 @@ pub mod coinflip {
         global_authority.rtp = RTP;
         global_authority.max_win_amount = MAX_WIN_AMOUNT;
         global_authority.min_bet_amount = MIN_BET_AMOUNT;
+        global_authority.settlement_timeout = SETTLEMENT_TIMEOUT;
+        global_authority.challenge_rake = CHALLENGE_RAKE;
+        global_authority.challenge_timeout = CHALLENGE_TIMEOUT;
+        ctx.accounts.jackpot.trigger_streak = JACKPOT_STREAK;
 
         Ok(())
     }
@@ -42,83 +51,257 @@ pub mod coinflip {
             head_or_tail: indicate whether the player bet on head or tail       0: Tail, 1: Head
             bet_amount:    The SOL amount to deposit
     */
-    pub fn play_game(
-        ctx: Context<PlayGame>,
+    pub fn play_game<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlayGame<'info>>,
         is_head: bool,
         bet_amount: u64,
-        _game_session_id: u64,
+        game_session_id: u64,
     ) -> Result<()> {
-        let player_pool = &mut ctx.accounts.player_pool;
-        let player = &ctx.accounts.owner;
-        let global_authority = &ctx.accounts.global_authority;
+        let mut accounts = ctx.accounts.open_game_accounts();
+        open_game(&mut accounts, is_head, bet_amount, game_session_id)?;
+        start_round(&mut accounts, ctx.remaining_accounts, game_session_id)
+    }
+
+    /**
+        @disc: Flip a coin with the bet taken from the player's prepaid balance.
+        @param:
+            is_head:        Side the player bets on
+            bet_amount:     The SOL amount to take from the balance
+    */
+    pub fn play_game_from_balance<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlayGameFromBalance<'info>>,
+        is_head: bool,
+        bet_amount: u64,
+        game_session_id: u64,
+    ) -> Result<()> {
+        let mut accounts = ctx.accounts.open_game_accounts();
+        open_game(&mut accounts, is_head, bet_amount, game_session_id)?;
+        start_round(&mut accounts, ctx.remaining_accounts, game_session_id)
+    }
+
+    /**
+        @disc: Flip a coin staked by the house out of the player's promo credit. Net winnings
+               are claimed into the credit with claim_promo_reward.
+        @param:
+            is_head:        Side the player bets on
+            bet_amount:     The credit to stake
+    */
+    pub fn play_game_with_promo_credit<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlayGameWithPromoCredit<'info>>,
+        is_head: bool,
+        bet_amount: u64,
+        game_session_id: u64,
+    ) -> Result<()> {
+        let mut accounts = ctx.accounts.open_game_accounts(ctx.bumps.casino_vault);
+        open_game(&mut accounts, is_head, bet_amount, game_session_id)?;
+        start_round(&mut accounts, ctx.remaining_accounts, game_session_id)
+    }
+
+    /**
+        @disc: Flip a coin for a player who only signed a bet intent off-chain. The operator
+               sends the transaction and the bet comes from the player's prepaid balance.
+        @param:
+            expires_at:     Unix timestamp after which the intent can no longer be submitted
+            nonce:          The player balance's next intent nonce, so every intent is used once
+    */
+    pub fn play_game_with_intent<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlayGameWithIntent<'info>>,
+        is_head: bool,
+        bet_amount: u64,
+        game_session_id: u64,
+        expires_at: i64,
+        nonce: u64,
+    ) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
 
         require!(
-            global_authority.min_bet_amount <= bet_amount,
-            GameError::InvalidBetAmount
+            Clock::get()?.unix_timestamp <= expires_at,
+            GameError::IntentExpired
+        );
+
+        let player_balance = &mut ctx.accounts.player_balance;
+        require!(
+            nonce == player_balance.intent_nonce,
+            GameError::InvalidIntentNonce
+        );
+        player_balance.intent_nonce += 1;
+
+        // A session key signs within its own limits, the owner within the balance
+        let signer = match ctx.accounts.session_key.as_mut() {
+            Some(session_key) => {
+                require_keys_eq!(session_key.owner, owner, GameError::SessionKeyNotAllowed);
+                session_key.authorize(SESSION_ALLOW_PLAY)?;
+                session_key.spend(bet_amount)?;
+                session_key.key
+            }
+            None => owner,
+        };
+
+        let message = bet_intent_message(
+            &owner,
+            is_head,
+            bet_amount,
+            game_session_id,
+            expires_at,
+            nonce,
+        );
+        verify_ed25519_signature(&ctx.accounts.instructions, &signer, &message)
+            .map_err(|_| GameError::InvalidIntentSignature)?;
+
+        let mut accounts = ctx.accounts.open_game_accounts();
+        open_game(&mut accounts, is_head, bet_amount, game_session_id)?;
+        start_round(&mut accounts, ctx.remaining_accounts, game_session_id)
+    }
+
+    /**
+        @disc: Roll a dice that wins when it comes up under the target, paying out the RTP over the win chance.
+        @param:
+            target:         Roll to stay under, 1 to 99, which is also the percent chance of winning
+            bet_amount:     The SOL amount to deposit
+    */
+    pub fn play_dice<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlayDice<'info>>,
+        target: u8,
+        bet_amount: u64,
+        game_session_id: u64,
+    ) -> Result<()> {
+        require!(
+            (DICE_MIN_TARGET..=DICE_MAX_TARGET).contains(&target),
+            GameError::InvalidDiceTarget
+        );
+        require!(
+            pays_above_stake(
+                bet_amount,
+                ctx.accounts.global_authority.rtp,
+                (target as u64, PERCENT)
+            ),
+            GameError::PayoutBelowStake
         );
 
-        let bet_amount_f64 = bet_amount as f64;
-        let rtp_f64 = global_authority.rtp as f64;
-        let rtp_ratio = rtp_f64 / 100.0;
-        let double_bet = bet_amount_f64 * 2.0;
-        let potential_win = double_bet * rtp_ratio;
-        let net_gain = potential_win - bet_amount_f64;
-        let net_gain_u64 = net_gain as u64;
-        let max_win_amount_u64 = global_authority.max_win_amount;
+        let mut accounts = ctx.accounts.open_game_accounts();
+        accounts.player_pool.dice_target = target;
+        open_game(&mut accounts, false, bet_amount, game_session_id)?;
+        start_round(&mut accounts, ctx.remaining_accounts, game_session_id)
+    }
 
+    /**
+        @disc: Flip several coins at once on a heads count, paying out the RTP over the binomial probability of that count.
+        @param:
+            coins:          Number of coins to flip, 1 to 10
+            heads:          Heads to bet on
+            exact:          Whether exactly `heads` heads win, otherwise at least `heads`
+            bet_amount:     The SOL amount to deposit
+    */
+    pub fn play_multi_coin<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlayMultiCoin<'info>>,
+        coins: u8,
+        heads: u8,
+        exact: bool,
+        bet_amount: u64,
+        game_session_id: u64,
+    ) -> Result<()> {
+        require!(
+            (1..=MAX_COINS).contains(&coins),
+            GameError::InvalidCoinCount
+        );
         require!(
-            net_gain_u64 < max_win_amount_u64,
-            GameError::InvalidBetAmountMaxWinAmountViolation
+            heads <= coins && (exact || heads > 0),
+            GameError::InvalidHeadsTarget
         );
+        require!(
+            pays_above_stake(
+                bet_amount,
+                ctx.accounts.global_authority.rtp,
+                multi_coin_odds(coins, heads, exact)
+            ),
+            GameError::PayoutBelowStake
+        );
+
+        let mut accounts = ctx.accounts.open_game_accounts();
+        accounts.player_pool.coin_count = coins;
+        accounts.player_pool.heads_target = heads;
+        accounts.player_pool.exact_heads = exact;
+        open_game(&mut accounts, false, bet_amount, game_session_id)?;
+        start_round(&mut accounts, ctx.remaining_accounts, game_session_id)
+    }
 
-        // require!(
-        //     (bet_amount * 2 * (global_authority.rtp / 100) - bet_amount)
-        //         < global_authority.max_win_amount,
-        //     GameError::InvalidBetAmountMaxWinAmountViolation
-        // );
+    /**
+        @disc: Place a batch of coin flips in one game, settled all at once.
+        @param:
+            choices:        Side of every flip      false: Tail, true: Head
+            amounts:        The SOL amount of every flip, the game vault escrows their total
+    */
+    pub fn play_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlayBatch<'info>>,
+        choices: Vec<bool>,
+        amounts: Vec<u64>,
+        game_session_id: u64,
+    ) -> Result<()> {
+        require!(
+            !amounts.is_empty()
+                && amounts.len() <= MAX_BATCH_FLIPS
+                && choices.len() == amounts.len(),
+            GameError::InvalidBatch
+        );
 
+        // Every flip is held to the limits of a single flip
+        let min_bet_amount = ctx.accounts.global_authority.min_bet_amount;
         require!(
-            ctx.accounts.owner.to_account_info().lamports() > bet_amount,
-            GameError::InsufficientUserBalance
+            amounts.iter().all(|amount| *amount >= min_bet_amount),
+            GameError::InvalidBetAmount
         );
+        let total = amounts
+            .iter()
+            .try_fold(0u64, |total, amount| total.checked_add(*amount))
+            .ok_or(GameError::MathOverflow)?;
+
+        let mut accounts = ctx.accounts.open_game_accounts();
+        accounts.player_pool.batch_choices = choices
+            .iter()
+            .enumerate()
+            .fold(0, |heads, (i, is_head)| heads | (*is_head as u64) << i);
+        accounts.player_pool.batch_amounts = amounts;
+        open_game(&mut accounts, false, total, game_session_id)?;
+        start_round(&mut accounts, ctx.remaining_accounts, game_session_id)
+    }
+
+    /**
+    Start a game settled by the SlotHashes sysvar instead of the operator.
+    The outcome mixes the hash of a slot `slot_hash_delay` slots ahead with
+    `player_seed`, so only low stakes are accepted
+    */
+    pub fn play_game_with_slot_hash<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlayGame<'info>>,
+        is_head: bool,
+        bet_amount: u64,
+        game_session_id: u64,
+        player_seed: [u8; 32],
+    ) -> Result<()> {
+        let global_authority = &ctx.accounts.global_authority;
 
         require!(
-            ctx.accounts.casino_vault.to_account_info().lamports() > bet_amount,
-            GameError::InsufficientCasinoVault
+            global_authority.slot_hash_delay > 0,
+            GameError::SlotHashDisabled
+        );
+        require!(
+            bet_amount <= global_authority.slot_hash_max_bet,
+            GameError::SlotHashBetTooLarge
         );
 
-        // Transfer rent fee for PDA of player pool
-        sol_transfer_user(
-            ctx.accounts.operator.to_account_info().clone(),
-            player_pool.to_account_info().clone(),
-            ctx.accounts.system_program.to_account_info().clone(),
-            ctx.accounts.rent.minimum_balance(0),
-        )?;
+        let target_slot = Clock::get()?.slot + global_authority.slot_hash_delay;
 
-        // Transfer bet_amount Sol to this PDA from User Wallet
-        sol_transfer_user(
-            ctx.accounts.owner.to_account_info(),
-            ctx.accounts.game_vault.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
+        open_game(
+            &mut ctx.accounts.open_game_accounts(),
+            is_head,
             bet_amount,
+            game_session_id,
         )?;
 
-        player_pool.status = GameStatus::Processing;
-        player_pool.round = 1;
-        player_pool.first_bet = bet_amount;
-        player_pool.player = player.key();
+        let player_pool = &mut ctx.accounts.player_pool;
+        player_pool.target_slot = target_slot;
+        player_pool.player_seed = player_seed;
 
-        if is_head == true {
-            msg!(
-                "User's choice is Head, bet amount is {}SOL",
-                bet_amount as f64 / LAMPORTS_PER_SOL as f64
-            );
-        } else {
-            msg!(
-                "User's choice is Tail, bet amount is {}SOL",
-                bet_amount as f64 / LAMPORTS_PER_SOL as f64
-            );
-        }
+        msg!("Settles with the hash of slot {}", target_slot);
 
         Ok(())
     }
@@ -126,153 +309,1337 @@ pub mod coinflip {
     /**
     The setting result function to determine whether player Win or Lose
     */
-    pub fn set_result(
-        ctx: Context<SetResult>,
+    pub fn set_result<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetResult<'info>>,
+        round_id: u8,
+        is_win: bool,
+        game_session_id: u64,
+    ) -> Result<()> {
+        let player_pool = &ctx.accounts.player_pool;
+        player_pool.require_operator_settled()?;
+        require!(!player_pool.is_batch(), GameError::SettledAsBatch);
+        msg!("Settling session {}", game_session_id);
+
+        let accounts = SettleAccounts {
+            owner: ctx.accounts.owner.to_account_info(),
+            operator: ctx.accounts.operator.to_account_info(),
+            game_vault: ctx.accounts.game_vault.to_account_info(),
+            casino_vault: ctx.accounts.casino_vault.to_account_info(),
+            player_stats: ctx.accounts.player_stats.to_account_info(),
+            vip_config: ctx.accounts.vip_config.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            game_bump: ctx.bumps.game_vault,
+            casino_bump: ctx.bumps.casino_vault,
+        };
+
+        settle_round(
+            &mut ctx.accounts.player_pool,
+            &accounts,
+            round_id,
+            is_win,
+            ctx.accounts.global_authority.rtp,
+        )?;
+        record_leaderboard_volume(ctx.remaining_accounts, &ctx.accounts.player_stats)
+    }
+
+    /**
+    The batch counterpart of set_result, settling every flip of a batch game.
+    Bit `i` of `outcomes` is set when flip `i` won
+    */
+    pub fn set_batch_result<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetBatchResult<'info>>,
+        round_id: u8,
+        outcomes: u64,
+        game_session_id: u64,
+    ) -> Result<()> {
+        let player_pool = &ctx.accounts.player_pool;
+        player_pool.require_operator_settled()?;
+        require!(player_pool.is_batch(), GameError::NotBatchGame);
+        msg!("Settling batch of session {}", game_session_id);
+
+        let accounts = SettleAccounts {
+            owner: ctx.accounts.owner.to_account_info(),
+            operator: ctx.accounts.operator.to_account_info(),
+            game_vault: ctx.accounts.game_vault.to_account_info(),
+            casino_vault: ctx.accounts.casino_vault.to_account_info(),
+            player_stats: ctx.accounts.player_stats.to_account_info(),
+            vip_config: ctx.accounts.vip_config.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            game_bump: ctx.bumps.game_vault,
+            casino_bump: ctx.bumps.casino_vault,
+        };
+
+        let flips = (1u64 << ctx.accounts.player_pool.batch_amounts.len()) - 1;
+        settle_batch(
+            &mut ctx.accounts.player_pool,
+            &accounts,
+            round_id,
+            outcomes & flips,
+            ctx.accounts.global_authority.rtp,
+        )?;
+        record_leaderboard_volume(ctx.remaining_accounts, &ctx.accounts.player_stats)
+    }
+
+    /**
+    Settle a round with a result the operation authority signed off-chain, so
+    anyone can submit it. The Ed25519 precompile instruction verifying the
+    signature must come right before this one
+    */
+    pub fn settle_with_signature(
+        ctx: Context<SettleWithSignature>,
         round_id: u8,
         is_win: bool,
         game_session_id: u64,
+        nonce: u64,
+    ) -> Result<()> {
+        let player_pool = &ctx.accounts.player_pool;
+
+        player_pool.require_operator_settled()?;
+        require!(!player_pool.is_batch(), GameError::SettledAsBatch);
+        require!(nonce == player_pool.nonce, GameError::InvalidNonce);
+
+        let message = settlement_message(
+            &player_pool.player,
+            game_session_id,
+            round_id,
+            is_win,
+            nonce,
+        );
+        verify_ed25519_signature(
+            &ctx.accounts.instructions,
+            &ctx.accounts.global_authority.operation_authority,
+            &message,
+        )?;
+
+        let accounts = SettleAccounts {
+            owner: ctx.accounts.owner.to_account_info(),
+            operator: ctx.accounts.operator.to_account_info(),
+            game_vault: ctx.accounts.game_vault.to_account_info(),
+            casino_vault: ctx.accounts.casino_vault.to_account_info(),
+            player_stats: ctx.accounts.player_stats.to_account_info(),
+            vip_config: ctx.accounts.vip_config.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            game_bump: ctx.bumps.game_vault,
+            casino_bump: ctx.bumps.casino_vault,
+        };
+
+        settle_round(
+            &mut ctx.accounts.player_pool,
+            &accounts,
+            round_id,
+            is_win,
+            ctx.accounts.global_authority.rtp,
+        )
+    }
+
+    /**
+    Callback of the randomness provider the round was requested from,
+    settling it with the provider's randomness instead of an operator result
+    */
+    pub fn consume_randomness(
+        ctx: Context<ConsumeRandomness>,
+        game_session_id: u64,
+        randomness: [u8; 32],
+    ) -> Result<()> {
+        let player_pool = &ctx.accounts.player_pool;
+
+        require!(
+            player_pool.randomness_provider != Pubkey::default()
+                && ctx.accounts.provider_authority.key()
+                    == provider_authority(&player_pool.randomness_provider),
+            GameError::UnauthorizedRandomnessProvider
+        );
+
+        let round = player_pool.round;
+        msg!(
+            "Session {} round {} settled by provider",
+            game_session_id,
+            round
+        );
+
+        let accounts = SettleAccounts {
+            owner: ctx.accounts.owner.to_account_info(),
+            operator: ctx.accounts.operator.to_account_info(),
+            game_vault: ctx.accounts.game_vault.to_account_info(),
+            casino_vault: ctx.accounts.casino_vault.to_account_info(),
+            player_stats: ctx.accounts.player_stats.to_account_info(),
+            vip_config: ctx.accounts.vip_config.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            game_bump: ctx.bumps.game_vault,
+            casino_bump: ctx.bumps.casino_vault,
+        };
+
+        settle_with_randomness(
+            &mut ctx.accounts.player_pool,
+            &accounts,
+            round,
+            &randomness,
+            ctx.accounts.global_authority.rtp,
+        )
+    }
+
+    /**
+    Settle a slot hash game once its target slot has passed. A game whose slot
+    hash has left the sysvar is lost. Anyone can call this
+    */
+    pub fn settle_with_slot_hash(
+        ctx: Context<SettleWithSlotHash>,
+        game_session_id: u64,
+    ) -> Result<()> {
+        let player_pool = &ctx.accounts.player_pool;
+        let global_authority = &ctx.accounts.global_authority;
+
+        require!(player_pool.target_slot != 0, GameError::NotSlotHashGame);
+        require!(
+            player_pool.status == GameStatus::Processing,
+            GameError::NotAllowedStatus
+        );
+        require!(
+            Clock::get()?.slot > player_pool.target_slot,
+            GameError::SlotHashNotReady
+        );
+
+        let accounts = SettleAccounts {
+            owner: ctx.accounts.owner.to_account_info(),
+            operator: ctx.accounts.operator.to_account_info(),
+            game_vault: ctx.accounts.game_vault.to_account_info(),
+            casino_vault: ctx.accounts.casino_vault.to_account_info(),
+            player_stats: ctx.accounts.player_stats.to_account_info(),
+            vip_config: ctx.accounts.vip_config.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            game_bump: ctx.bumps.game_vault,
+            casino_bump: ctx.bumps.casino_vault,
+        };
+
+        let round = player_pool.round;
+        let Some(slot_hash) = slot_hash_from(&ctx.accounts.slot_hashes, player_pool.target_slot)?
+        else {
+            // Nobody settled while the hash was public, so a player who saw
+            // the round lost gains nothing by letting it expire
+            msg!(
+                "Slot hash of slot {} expired, the round is lost",
+                player_pool.target_slot
+            );
+            return settle_round(
+                &mut ctx.accounts.player_pool,
+                &accounts,
+                round,
+                false,
+                global_authority.rtp,
+            );
+        };
+
+        let randomness =
+            slot_game_randomness(&slot_hash, &player_pool.player_seed, game_session_id, round);
+
+        settle_with_randomness(
+            &mut ctx.accounts.player_pool,
+            &accounts,
+            round,
+            &randomness,
+            global_authority.rtp,
+        )
+    }
+
+    /**
+    Settle the oldest unsettled hash chain round with the next preimage of the
+    chain head. Anyone can call this once the operator has published the reveal
+    */
+    pub fn settle_with_reveal(
+        ctx: Context<SettleWithReveal>,
+        game_session_id: u64,
+        reveal: [u8; 32],
+    ) -> Result<()> {
+        let player_pool = &ctx.accounts.player_pool;
+        let global_authority = &mut ctx.accounts.global_authority;
+
+        require!(player_pool.chain_position != 0, GameError::NotHashChainGame);
+        require!(
+            player_pool.status == GameStatus::Processing,
+            GameError::NotAllowedStatus
+        );
+        require!(
+            player_pool.chain_position == global_authority.hash_chain_revealed + 1,
+            GameError::HashChainOutOfOrder
+        );
+        global_authority.reveal_chain_position(reveal)?;
+
+        let round = player_pool.round;
+        let randomness = chain_game_randomness(&reveal, player_pool, game_session_id);
+        msg!(
+            "Chain position {} settles session {} round {}",
+            player_pool.chain_position,
+            game_session_id,
+            round
+        );
+
+        let accounts = SettleAccounts {
+            owner: ctx.accounts.owner.to_account_info(),
+            operator: ctx.accounts.operator.to_account_info(),
+            game_vault: ctx.accounts.game_vault.to_account_info(),
+            casino_vault: ctx.accounts.casino_vault.to_account_info(),
+            player_stats: ctx.accounts.player_stats.to_account_info(),
+            vip_config: ctx.accounts.vip_config.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            game_bump: ctx.bumps.game_vault,
+            casino_bump: ctx.bumps.casino_vault,
+        };
+
+        settle_with_randomness(
+            &mut ctx.accounts.player_pool,
+            &accounts,
+            round,
+            &randomness,
+            ctx.accounts.global_authority.rtp,
+        )
+    }
+
+    /**
+    Double Bet function when the user want to do that after win the game
+    */
+    pub fn double_bet<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DoubleBet<'info>>,
+        is_head: bool,
+        game_session_id: u64,
+    ) -> Result<()> {
+        let mut accounts = ctx.accounts.double_bet_accounts();
+        double_round(
+            &mut accounts,
+            ctx.remaining_accounts,
+            is_head,
+            game_session_id,
+        )
+    }
+
+    /**
+    Claim the full potential win of a round the operator left unsettled past its
+    deadline. The stake comes back from the game vault, the casino's share of
+    the win from the operator bond, and the operator is marked as slashed. A
+    hash chain round has to be the next one to reveal, and its position is
+    passed over so later rounds can still be revealed
+    */
+    pub fn slash_operator(ctx: Context<SlashOperator>, game_session_id: u64) -> Result<()> {
+        let player_pool = &ctx.accounts.player_pool;
+        let operator_bond = &mut ctx.accounts.operator_bond;
+        let global_authority = &mut ctx.accounts.global_authority;
+
+        require!(
+            player_pool.status == GameStatus::Processing,
+            GameError::NotAllowedStatus
+        );
+        require!(
+            player_pool.settlement_deadline != 0,
+            GameError::NoSettlementDeadline
+        );
+        require!(
+            Clock::get()?.unix_timestamp > player_pool.settlement_deadline,
+            GameError::SettlementNotOverdue
+        );
+        player_pool.require_not_promo()?;
+
+        if player_pool.chain_position != 0 {
+            require!(
+                player_pool.chain_position == global_authority.hash_chain_revealed + 1,
+                GameError::HashChainOutOfOrder
+            );
+            global_authority.skip_chain_position();
+        }
+
+        let stake = ctx.accounts.game_vault.lamports();
+        let top_up = casino_top_up_at_odds(stake, global_authority.rtp, player_pool.win_odds())
+            .ok_or(GameError::MathOverflow)?;
+        // Whatever the bond holds above its rent, if it cannot cover the whole win
+        let bond_info = operator_bond.to_account_info();
+        let available = balance_above_rent(&bond_info)?;
+        let slashed = top_up.min(available);
+
+        msg!(
+            "Slashing operator for session {} round {}: {} of {}",
+            game_session_id,
+            player_pool.round,
+            slashed,
+            top_up
+        );
+
+        sol_transfer_with_signer(
+            ctx.accounts.game_vault.to_account_info(),
+            ctx.accounts.owner.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            &[&[
+                ctx.accounts.owner.key().as_ref(),
+                VAULT_AUTHORITY_SEED.as_bytes(),
+                &game_session_id.to_be_bytes()[..],
+                &[ctx.bumps.game_vault],
+            ]],
+            stake,
+        )?;
+
+        **bond_info.try_borrow_mut_lamports()? -= slashed;
+        let owner_info = ctx.accounts.owner.to_account_info();
+        **owner_info.try_borrow_mut_lamports()? = owner_info
+            .lamports()
+            .checked_add(slashed)
+            .ok_or(GameError::MathOverflow)?;
+
+        // The stake and the win from the bond reach the owner like a claimed payout
+        record_limits_payout(
+            &ctx.accounts.player_limits,
+            stake.checked_add(slashed).ok_or(GameError::MathOverflow)?,
+        )?;
+
+        operator_bond.slashed = true;
+        operator_bond.slash_count += 1;
+        operator_bond.total_slashed = operator_bond
+            .total_slashed
+            .checked_add(slashed)
+            .ok_or(GameError::MathOverflow)?;
+
+        close_program_account(
+            &ctx.accounts.player_pool.to_account_info(),
+            &ctx.accounts.operator.to_account_info(),
+        )
+    }
+
+    /**
+    The claim Reward function for User after playing and Win
+    */
+    pub fn claim_reward<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimReward<'info>>,
+        game_session_id: u64,
     ) -> Result<()> {
         let player_pool = &mut ctx.accounts.player_pool;
+        let player = &ctx.accounts.player;
         let game_bump = ctx.bumps.game_vault;
-        let casino_bump = ctx.bumps.casino_vault;
-        let global_authority = &ctx.accounts.global_authority;
         let game_vault = &mut ctx.accounts.game_vault;
-        let casino_vault = &mut ctx.accounts.casino_vault;
         let vault_balance = game_vault.lamports();
-        let win_balance = vault_balance * 2 * global_authority.rtp / 100;
 
-        msg!(
-            "RoundId: {}, PlayerPoolRoundId: {}",
-            round_id,
-            player_pool.round
+        require!(
+            player_pool.status == GameStatus::Win,
+            GameError::NotAllowedStatus
         );
-        require!(round_id == player_pool.round, GameError::RoundNumMismatch);
+        player_pool.require_not_promo()?;
 
-        if is_win == true {
-            // Transfer bet_amount Sol to this PDA from casino bank
-            sol_transfer_with_signer(
-                casino_vault.to_account_info(),
-                game_vault.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-                &[&[VAULT_AUTHORITY_SEED.as_bytes(), &[casino_bump]]],
-                win_balance - vault_balance,
-            )?;
+        msg!(
+            "Withdrawer: {}
+            Amount: {}",
+            player.key(),
+            vault_balance,
+        );
 
-            player_pool.status = GameStatus::Win
-        } else {
-            player_pool.status = GameStatus::Lose;
+        sol_transfer_with_signer(
+            game_vault.to_account_info(),
+            player.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            &[&[
+                ctx.accounts.player.key().as_ref(),
+                VAULT_AUTHORITY_SEED.as_bytes(),
+                &game_session_id.to_be_bytes()[..],
+                &[game_bump],
+            ]],
+            vault_balance,
+        )?;
+
+        record_tournament_win(
+            ctx.accounts.tournament.as_ref(),
+            ctx.accounts.tournament_entry.as_mut(),
+            player_pool,
+            vault_balance,
+        )?;
+        record_leaderboard_win(ctx.remaining_accounts, player.key(), vault_balance)?;
+        record_leaderboard_volume(ctx.remaining_accounts, &ctx.accounts.player_stats)?;
+        record_limits_payout(&ctx.accounts.player_limits, vault_balance)?;
+        pay_jackpot(&mut ctx.accounts.jackpot, player_pool, player)?;
+
+        close_program_account(
+            &player_pool.to_account_info(),
+            &ctx.accounts.operator.to_account_info(),
+        )
+    }
+
+    /**
+    Claim a won game into the player's prepaid balance instead of their wallet
+    */
+    pub fn claim_reward_to_balance<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimRewardToBalance<'info>>,
+        game_session_id: u64,
+    ) -> Result<()> {
+        let player_pool = &ctx.accounts.player_pool;
+        let vault_balance = ctx.accounts.game_vault.lamports();
+
+        require!(
+            player_pool.status == GameStatus::Win,
+            GameError::NotAllowedStatus
+        );
+        player_pool.require_not_promo()?;
+
+        msg!(
+            "Crediting {} to the balance of {}",
+            vault_balance,
+            ctx.accounts.player.key()
+        );
+
+        sol_transfer_with_signer(
+            ctx.accounts.game_vault.to_account_info(),
+            ctx.accounts.player_balance.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            &[&[
+                ctx.accounts.player.key().as_ref(),
+                VAULT_AUTHORITY_SEED.as_bytes(),
+                &game_session_id.to_be_bytes()[..],
+                &[ctx.bumps.game_vault],
+            ]],
+            vault_balance,
+        )?;
+
+        record_tournament_win(
+            ctx.accounts.tournament.as_ref(),
+            ctx.accounts.tournament_entry.as_mut(),
+            &ctx.accounts.player_pool,
+            vault_balance,
+        )?;
+        record_leaderboard_win(
+            ctx.remaining_accounts,
+            ctx.accounts.player.key(),
+            vault_balance,
+        )?;
+        record_leaderboard_volume(ctx.remaining_accounts, &ctx.accounts.player_stats)?;
+        record_limits_payout(&ctx.accounts.player_limits, vault_balance)?;
+        pay_jackpot(
+            &mut ctx.accounts.jackpot,
+            &ctx.accounts.player_pool,
+            &ctx.accounts.player_balance.to_account_info(),
+        )?;
+
+        close_program_account(
+            &ctx.accounts.player_pool.to_account_info(),
+            &ctx.accounts.operator.to_account_info(),
+        )
+    }
+
+    /**
+    Add `amount` lamports to the player's prepaid balance, opening it on the first deposit
+    */
+    pub fn deposit_balance(ctx: Context<DepositBalance>, amount: u64) -> Result<()> {
+        ctx.accounts.player_balance.player = ctx.accounts.player.key();
+
+        sol_transfer_user(
+            ctx.accounts.player.to_account_info(),
+            ctx.accounts.player_balance.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            amount,
+        )
+    }
+
+    pub fn withdraw_balance(ctx: Context<WithdrawBalance>, amount: u64) -> Result<()> {
+        let balance_info = ctx.accounts.player_balance.to_account_info();
+
+        require!(
+            amount <= balance_above_rent(&balance_info)?,
+            GameError::InsufficientPlayerBalance
+        );
+
+        debit_program_account(
+            &balance_info,
+            &ctx.accounts.player.to_account_info(),
+            amount,
+        )
+    }
+
+    /**
+        @disc: Let an ephemeral key sign games for the owner.
+        @param:
+            key:            Public key of the session key
+            spending_cap:   Total stake the key can take from the owner's prepaid balance
+            expires_at:     Unix timestamp the key stops working at
+            allowed:        SESSION_ALLOW_* flags of the instructions the key can sign
+    */
+    pub fn create_session_key(
+        ctx: Context<CreateSessionKey>,
+        key: Pubkey,
+        spending_cap: u64,
+        expires_at: i64,
+        allowed: u8,
+    ) -> Result<()> {
+        require!(
+            expires_at > Clock::get()?.unix_timestamp,
+            GameError::SessionKeyExpired
+        );
+
+        let session_key = &mut ctx.accounts.session_key;
+        session_key.owner = ctx.accounts.owner.key();
+        session_key.key = key;
+        session_key.spending_cap = spending_cap;
+        session_key.expires_at = expires_at;
+        session_key.allowed = allowed;
+
+        msg!(
+            "Session key {} can spend {} until {}",
+            key,
+            spending_cap,
+            expires_at
+        );
+        Ok(())
+    }
+
+    pub fn revoke_session_key(ctx: Context<RevokeSessionKey>, key: Pubkey) -> Result<()> {
+        msg!("Revoking session key {}", key);
+        close_program_account(
+            &ctx.accounts.session_key.to_account_info(),
+            &ctx.accounts.owner.to_account_info(),
+        )
+    }
+
+    /**
+    `play_game` signed by a session key, the bet coming from the owner's prepaid balance
+    */
+    pub fn play_game_with_session_key<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlayGameWithSessionKey<'info>>,
+        is_head: bool,
+        bet_amount: u64,
+        game_session_id: u64,
+    ) -> Result<()> {
+        let session_key = &mut ctx.accounts.session_key;
+        session_key.authorize(SESSION_ALLOW_PLAY)?;
+        session_key.spend(bet_amount)?;
+
+        let mut accounts = ctx.accounts.open_game_accounts();
+        open_game(&mut accounts, is_head, bet_amount, game_session_id)?;
+        start_round(&mut accounts, ctx.remaining_accounts, game_session_id)
+    }
+
+    /**
+    `double_bet` signed by a session key. It only moves the winnings already in the
+    game vault, so it does not count against the spending cap
+    */
+    pub fn double_bet_with_session_key<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DoubleBetWithSessionKey<'info>>,
+        is_head: bool,
+        game_session_id: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .session_key
+            .authorize(SESSION_ALLOW_DOUBLE_BET)?;
+
+        let mut accounts = ctx.accounts.double_bet_accounts();
+        double_round(
+            &mut accounts,
+            ctx.remaining_accounts,
+            is_head,
+            game_session_id,
+        )
+    }
+
+    /**
+    `claim_reward` signed by a session key, paying the owner's wallet
+    */
+    pub fn claim_reward_with_session_key<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimRewardWithSessionKey<'info>>,
+        game_session_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.session_key.authorize(SESSION_ALLOW_CLAIM)?;
+
+        let player_pool = &ctx.accounts.player_pool;
+        let vault_balance = ctx.accounts.game_vault.lamports();
+
+        require!(
+            player_pool.status == GameStatus::Win,
+            GameError::NotAllowedStatus
+        );
+        player_pool.require_not_promo()?;
+
+        msg!(
+            "Withdrawer: {} Amount: {}",
+            ctx.accounts.owner.key(),
+            vault_balance
+        );
+
+        sol_transfer_with_signer(
+            ctx.accounts.game_vault.to_account_info(),
+            ctx.accounts.owner.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            &[&[
+                ctx.accounts.owner.key().as_ref(),
+                VAULT_AUTHORITY_SEED.as_bytes(),
+                &game_session_id.to_be_bytes()[..],
+                &[ctx.bumps.game_vault],
+            ]],
+            vault_balance,
+        )?;
+
+        record_tournament_win(
+            ctx.accounts.tournament.as_ref(),
+            ctx.accounts.tournament_entry.as_mut(),
+            &ctx.accounts.player_pool,
+            vault_balance,
+        )?;
+        record_leaderboard_win(
+            ctx.remaining_accounts,
+            ctx.accounts.owner.key(),
+            vault_balance,
+        )?;
+        record_leaderboard_volume(ctx.remaining_accounts, &ctx.accounts.player_stats)?;
+        record_limits_payout(&ctx.accounts.player_limits, vault_balance)?;
+        pay_jackpot(
+            &mut ctx.accounts.jackpot,
+            &ctx.accounts.player_pool,
+            &ctx.accounts.owner,
+        )?;
+
+        close_program_account(
+            &ctx.accounts.player_pool.to_account_info(),
+            &ctx.accounts.operator.to_account_info(),
+        )
+    }
+
+    /**
+        @disc: Open a coin flip against another player, escrowing the stake in the challenge account.
+        @param:
+            challenge_id:   Id of the challenge among the creator's challenges
+            is_head:        Side the creator bets on, the opponent takes the other one
+            stake:          The SOL amount each player puts in
+    */
+    pub fn create_challenge(
+        ctx: Context<CreateChallenge>,
+        challenge_id: u64,
+        is_head: bool,
+        stake: u64,
+    ) -> Result<()> {
+        let global_authority = &ctx.accounts.global_authority;
+
+        require!(
+            global_authority.challenge_timeout > 0,
+            GameError::ChallengesDisabled
+        );
+        require!(
+            global_authority.min_bet_amount <= stake,
+            GameError::InvalidBetAmount
+        );
+
+        sol_transfer_user(
+            ctx.accounts.creator.to_account_info(),
+            ctx.accounts.challenge.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            stake,
+        )?;
+
+        let challenge = &mut ctx.accounts.challenge;
+        challenge.creator = ctx.accounts.creator.key();
+        challenge.challenge_id = challenge_id;
+        challenge.stake = stake;
+        challenge.creator_is_head = is_head;
+        challenge.expires_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(global_authority.challenge_timeout)
+            .ok_or(GameError::MathOverflow)?;
+
+        Ok(())
+    }
+
+    /**
+    Match an open challenge's stake on the other side. The operator then has
+    until a new deadline to settle it
+    */
+    pub fn accept_challenge(ctx: Context<AcceptChallenge>, challenge_id: u64) -> Result<()> {
+        let challenge = &mut ctx.accounts.challenge;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(challenge.is_open(), GameError::ChallengeNotOpen);
+        require!(now <= challenge.expires_at, GameError::ChallengeExpired);
+        require_keys_neq!(
+            ctx.accounts.opponent.key(),
+            challenge.creator,
+            GameError::OwnChallenge
+        );
+
+        challenge.opponent = ctx.accounts.opponent.key();
+        challenge.expires_at = now
+            .checked_add(ctx.accounts.global_authority.challenge_timeout)
+            .ok_or(GameError::MathOverflow)?;
+
+        sol_transfer_user(
+            ctx.accounts.opponent.to_account_info(),
+            challenge.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            challenge.stake,
+        )?;
+
+        msg!(
+            "Challenge {} accepted by {}",
+            challenge_id,
+            challenge.opponent
+        );
+        Ok(())
+    }
+
+    /**
+    Close an unmatched challenge and return the stake to its creator. Anyone
+    can do it once the challenge has expired
+    */
+    pub fn cancel_challenge(ctx: Context<CancelChallenge>, challenge_id: u64) -> Result<()> {
+        let challenge = &ctx.accounts.challenge;
+
+        require!(challenge.is_open(), GameError::ChallengeNotOpen);
+        require!(
+            ctx.accounts.signer.key() == challenge.creator
+                || Clock::get()?.unix_timestamp > challenge.expires_at,
+            GameError::ChallengeNotCancellable
+        );
+
+        msg!("Challenge {} cancelled", challenge_id);
+        close_program_account(
+            &challenge.to_account_info(),
+            &ctx.accounts.creator.to_account_info(),
+        )
+    }
+
+    /**
+    Return both stakes of an accepted challenge the operator failed to settle
+    before its deadline
+    */
+    pub fn refund_challenge(ctx: Context<RefundChallenge>, challenge_id: u64) -> Result<()> {
+        let challenge = &ctx.accounts.challenge;
+
+        require!(!challenge.is_open(), GameError::ChallengeNotAccepted);
+        require!(
+            Clock::get()?.unix_timestamp > challenge.expires_at,
+            GameError::ChallengeNotOverdue
+        );
+
+        msg!("Challenge {} refunded", challenge_id);
+        let challenge_info = challenge.to_account_info();
+        debit_program_account(
+            &challenge_info,
+            &ctx.accounts.opponent.to_account_info(),
+            challenge.stake,
+        )?;
+        close_program_account(&challenge_info, &ctx.accounts.creator.to_account_info())
+    }
+
+    /**
+        @disc: Settle an accepted challenge, paying the winner both stakes minus the rake, which goes to the casino vault.
+        @param:
+            is_head:    The side the coin landed on
+    */
+    pub fn settle_challenge(
+        ctx: Context<SettleChallenge>,
+        challenge_id: u64,
+        is_head: bool,
+    ) -> Result<()> {
+        let challenge = &ctx.accounts.challenge;
+
+        require!(!challenge.is_open(), GameError::ChallengeNotAccepted);
+
+        let pot = challenge
+            .stake
+            .checked_mul(2)
+            .ok_or(GameError::MathOverflow)?;
+        let rake = pot_rake(pot, ctx.accounts.global_authority.challenge_rake)
+            .ok_or(GameError::MathOverflow)?;
+        let winner = match is_head == challenge.creator_is_head {
+            true => ctx.accounts.creator.to_account_info(),
+            false => ctx.accounts.opponent.to_account_info(),
+        };
+
+        msg!(
+            "Challenge {} won by {}, rake is {}",
+            challenge_id,
+            winner.key(),
+            rake
+        );
+
+        let challenge_info = challenge.to_account_info();
+        debit_program_account(&challenge_info, &winner, pot - rake)?;
+        debit_program_account(
+            &challenge_info,
+            &ctx.accounts.casino_vault.to_account_info(),
+            rake,
+        )?;
+        close_program_account(&challenge_info, &ctx.accounts.creator.to_account_info())
+    }
+
+    /**
+        @disc: Open a tournament, its prize pool starts with the entry fees and whatever sponsors add.
+        @param:
+            tournament_id:  Id of the tournament
+            start_at:       Time from which the entrants' play_game bets and claims are scored
+            end_at:         Time scoring stops and ranking starts
+            entry_fee:      Lamports a player pays into the prize pool to enter, 0 for free entry
+            scoring:        TOURNAMENT_SCORE_NET_PROFIT, TOURNAMENT_SCORE_VOLUME or TOURNAMENT_SCORE_STREAK
+            prize_split:    Permille of the prize pool paid to each place, best first
+    */
+    pub fn create_tournament(
+        ctx: Context<CreateTournament>,
+        tournament_id: u64,
+        start_at: i64,
+        end_at: i64,
+        entry_fee: u64,
+        scoring: u8,
+        prize_split: Vec<u16>,
+    ) -> Result<()> {
+        let split_total: u64 = prize_split.iter().map(|share| *share as u64).sum();
+        require!(
+            start_at < end_at
+                && Clock::get()?.unix_timestamp < end_at
+                && scoring <= TOURNAMENT_SCORE_STREAK
+                && !prize_split.is_empty()
+                && prize_split.len() <= MAX_PRIZE_PLACES
+                && split_total <= PERMILLE,
+            GameError::InvalidTournament
+        );
+
+        let tournament = &mut ctx.accounts.tournament;
+        tournament.tournament_id = tournament_id;
+        tournament.start_at = start_at;
+        tournament.end_at = end_at;
+        tournament.entry_fee = entry_fee;
+        tournament.scoring = scoring;
+        tournament.prize_split = prize_split;
+        Ok(())
+    }
+
+    /**
+    Add `amount` lamports to the prize pool of a tournament
+    */
+    pub fn fund_tournament(
+        ctx: Context<FundTournament>,
+        tournament_id: u64,
+        amount: u64,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.tournament.finalized,
+            GameError::TournamentFinalized
+        );
+
+        msg!("Tournament {} funded with {}", tournament_id, amount);
+        sol_transfer_user(
+            ctx.accounts.sponsor.to_account_info(),
+            ctx.accounts.tournament.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            amount,
+        )
+    }
+
+    /**
+    Register the player for a tournament that has not ended, paying its entry fee
+    */
+    pub fn enter_tournament(ctx: Context<EnterTournament>, tournament_id: u64) -> Result<()> {
+        let tournament = &mut ctx.accounts.tournament;
+
+        require!(
+            Clock::get()?.unix_timestamp < tournament.end_at,
+            GameError::TournamentNotLive
+        );
+
+        if tournament.entry_fee > 0 {
+            sol_transfer_user(
+                ctx.accounts.player.to_account_info(),
+                tournament.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                tournament.entry_fee,
+            )?;
+        }
+        tournament.entrants = tournament
+            .entrants
+            .checked_add(1)
+            .ok_or(GameError::MathOverflow)?;
+
+        let entry = &mut ctx.accounts.tournament_entry;
+        entry.tournament = tournament.key();
+        entry.player = ctx.accounts.player.key();
+
+        msg!(
+            "{} entered tournament {}",
+            ctx.accounts.player.key(),
+            tournament_id
+        );
+        Ok(())
+    }
+
+    /**
+    Rank an entry of a tournament that has ended by its final score. Anyone can
+    do it, until the ranking period is over and the tournament is finalized
+    */
+    pub fn rank_tournament_entry(
+        ctx: Context<RankTournamentEntry>,
+        tournament_id: u64,
+    ) -> Result<()> {
+        let tournament = &mut ctx.accounts.tournament;
+        let entry = &mut ctx.accounts.tournament_entry;
+
+        require!(
+            Clock::get()?.unix_timestamp >= tournament.end_at,
+            GameError::TournamentNotOver
+        );
+        require!(!tournament.finalized, GameError::TournamentFinalized);
+        require!(!entry.ranked, GameError::EntryAlreadyRanked);
+
+        let score = tournament.score(entry);
+        tournament.rank(entry.player, score);
+        entry.ranked = true;
+
+        msg!(
+            "Ranked {} in tournament {} with a score of {}",
+            entry.player,
+            tournament_id,
+            score
+        );
+        Ok(())
+    }
+
+    /**
+    Pay the prize pool out to the ranked players once the ranking period is
+    over. Their wallets are passed as remaining accounts, best first, and the
+    share of places nobody took goes to the casino vault
+    */
+    pub fn finalize_tournament(ctx: Context<FinalizeTournament>, tournament_id: u64) -> Result<()> {
+        let tournament = &ctx.accounts.tournament;
+        let ranking_end = tournament
+            .end_at
+            .checked_add(TOURNAMENT_RANKING_PERIOD)
+            .ok_or(GameError::MathOverflow)?;
+
+        require!(
+            Clock::get()?.unix_timestamp >= ranking_end,
+            GameError::TournamentRanking
+        );
+        require!(!tournament.finalized, GameError::TournamentFinalized);
+        require!(
+            ctx.remaining_accounts.len() == tournament.leaders.len(),
+            GameError::InvalidPrizeRecipient
+        );
+
+        let tournament_info = tournament.to_account_info();
+        let pool = balance_above_rent(&tournament_info)?;
+        let mut paid = 0u64;
+        for ((leader, share), recipient) in tournament
+            .leaders
+            .iter()
+            .zip(&tournament.prize_split)
+            .zip(ctx.remaining_accounts)
+        {
+            require_keys_eq!(
+                recipient.key(),
+                leader.player,
+                GameError::InvalidPrizeRecipient
+            );
+            let prize = prize_amount(pool, *share).ok_or(GameError::MathOverflow)?;
+            debit_program_account(&tournament_info, recipient, prize)?;
+            paid += prize;
+            msg!(
+                "Tournament {} pays {} to {}",
+                tournament_id,
+                prize,
+                leader.player
+            );
+        }
+        debit_program_account(
+            &tournament_info,
+            &ctx.accounts.casino_vault.to_account_info(),
+            pool - paid,
+        )?;
+
+        ctx.accounts.tournament.finalized = true;
+        Ok(())
+    }
+
+    /**
+        @disc: Open the leaderboard of a period, anyone can pay for it ahead of time.
+        @param:
+            period:         LEADERBOARD_DAILY, LEADERBOARD_WEEKLY or LEADERBOARD_ALL_TIME
+            period_index:   Days or weeks since the unix epoch, 0 for all-time
+    */
+    pub fn open_leaderboard(
+        ctx: Context<OpenLeaderboard>,
+        period: u8,
+        period_index: u64,
+    ) -> Result<()> {
+        require!(
+            period <= LEADERBOARD_ALL_TIME
+                && period_index >= Leaderboard::period_index(period, Clock::get()?.unix_timestamp),
+            GameError::InvalidLeaderboard
+        );
+        if period == LEADERBOARD_ALL_TIME {
+            require!(period_index == 0, GameError::InvalidLeaderboard);
+        }
+
+        let leaderboard = &mut ctx.accounts.leaderboard;
+        leaderboard.period = period;
+        leaderboard.period_index = period_index;
+        Ok(())
+    }
+
+    /**
+    Open the wagered totals a player is ranked by on the leaderboards
+    */
+    pub fn open_player_stats(ctx: Context<OpenPlayerStats>) -> Result<()> {
+        ctx.accounts.player_stats.player = ctx.accounts.player.key();
+        Ok(())
+    }
+
+    /**
+        @disc: Hand a player free-bet credit, opening their promo credit on the first issue.
+        @param:
+            amount:                 Credit the player can stake in play_game_with_promo_credit
+            wagering_requirement:   Promo stakes the player must add before winnings unlock
+    */
+    pub fn issue_promo_credit(
+        ctx: Context<IssuePromoCredit>,
+        amount: u64,
+        wagering_requirement: u64,
+    ) -> Result<()> {
+        let promo_credit = &mut ctx.accounts.promo_credit;
+        promo_credit.player = ctx.accounts.player.key();
+        promo_credit.credit = promo_credit
+            .credit
+            .checked_add(amount)
+            .ok_or(GameError::MathOverflow)?;
+        promo_credit.wagering_requirement = promo_credit
+            .wagering_requirement
+            .checked_add(wagering_requirement)
+            .ok_or(GameError::MathOverflow)?;
+        Ok(())
+    }
+
+    /**
+    Claim a won promo game: the staked credit goes back to the casino vault and
+    the net winnings are locked in the player's promo credit
+    */
+    pub fn claim_promo_reward(ctx: Context<ClaimPromoReward>, game_session_id: u64) -> Result<()> {
+        let player_pool = &ctx.accounts.player_pool;
+        require!(
+            player_pool.status == GameStatus::Win,
+            GameError::NotAllowedStatus
+        );
+        require!(player_pool.promo_stake != 0, GameError::NotPromoGame);
+
+        let payout = ctx.accounts.game_vault.lamports();
+        record_tournament_win(
+            ctx.accounts.tournament.as_ref(),
+            ctx.accounts.tournament_entry.as_mut(),
+            player_pool,
+            payout,
+        )?;
+        let winnings = payout
+            .checked_sub(player_pool.promo_stake)
+            .ok_or(GameError::MathOverflow)?;
+        let game_seeds: &[&[u8]] = &[
+            ctx.accounts.player.key.as_ref(),
+            VAULT_AUTHORITY_SEED.as_bytes(),
+            &game_session_id.to_be_bytes(),
+            &[ctx.bumps.game_vault],
+        ];
+        sol_transfer_with_signer(
+            ctx.accounts.game_vault.to_account_info(),
+            ctx.accounts.casino_vault.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            &[game_seeds],
+            player_pool.promo_stake,
+        )?;
+        sol_transfer_with_signer(
+            ctx.accounts.game_vault.to_account_info(),
+            ctx.accounts.promo_credit.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            &[game_seeds],
+            winnings,
+        )?;
+
+        let promo_credit = &mut ctx.accounts.promo_credit;
+        promo_credit.locked_winnings = promo_credit
+            .locked_winnings
+            .checked_add(winnings)
+            .ok_or(GameError::MathOverflow)?;
+
+        close_program_account(
+            &ctx.accounts.player_pool.to_account_info(),
+            &ctx.accounts.operator.to_account_info(),
+        )
+    }
 
-            let player_pool_lamports = player_pool.to_account_info().lamports();
+    /**
+    Send the winnings locked in a promo credit to the player once its wagering
+    requirement is met
+    */
+    pub fn withdraw_promo_winnings(ctx: Context<WithdrawPromoWinnings>) -> Result<()> {
+        let promo_credit = &mut ctx.accounts.promo_credit;
+        require!(
+            promo_credit.is_unlocked(),
+            GameError::WageringRequirementNotMet
+        );
+        let amount = promo_credit.locked_winnings;
+        require!(amount > 0, GameError::NoPromoWinnings);
 
-            sol_transfer_with_signer(
-                game_vault.to_account_info(),
-                casino_vault.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-                &[&[
-                    ctx.accounts.owner.key().as_ref(),
-                    VAULT_AUTHORITY_SEED.as_bytes(),
-                    &game_session_id.to_be_bytes()[..],
-                    &[game_bump],
-                ]],
-                vault_balance,
-            )?;
+        promo_credit.locked_winnings = 0;
+        debit_program_account(
+            &promo_credit.to_account_info(),
+            &ctx.accounts.player.to_account_info(),
+            amount,
+        )
+    }
 
-            let dest_starting_lamports = ctx.accounts.operator.lamports();
-            **ctx.accounts.operator.lamports.borrow_mut() = dest_starting_lamports
-                .checked_add(player_pool.to_account_info().lamports())
-                .unwrap();
-            **player_pool.to_account_info().lamports.borrow_mut() = 0;
+    /**
+        @disc: Set the player's own responsible gambling limits, opening them on the first call.
+               Tighter settings apply at once, looser ones after LIMITS_COOLING_OFF.
+        @param:
+            wager_limits:           Daily, weekly and monthly stake limits, 0 for no limit
+            loss_limits:            Daily, weekly and monthly net loss limits, 0 for no limit
+            self_excluded_until:    Time before which the player cannot bet
+    */
+    pub fn set_player_limits(
+        ctx: Context<SetPlayerLimits>,
+        wager_limits: [u64; 3],
+        loss_limits: [u64; 3],
+        self_excluded_until: i64,
+    ) -> Result<()> {
+        let player_limits = &mut ctx.accounts.player_limits;
+        player_limits.player = ctx.accounts.player.key();
+        player_limits.update(
+            wager_limits,
+            loss_limits,
+            self_excluded_until,
+            Clock::get()?.unix_timestamp,
+        );
+        Ok(())
+    }
 
-            let player_pool_account_info = player_pool.to_account_info();
-            let mut player_pool_data = player_pool_account_info.try_borrow_mut_data()?;
-            player_pool_data.fill(0);
-        }
+    /**
+        @disc: Set who play_game lets in.
+        @param:
+            mode:   ACCESS_OPEN, ACCESS_ALLOWLIST or ACCESS_DENYLIST
+    */
+    pub fn set_access_mode(ctx: Context<SetGlobalPool>, mode: u8) -> Result<()> {
+        require!(mode <= ACCESS_DENYLIST, GameError::InvalidAccessMode);
+        ctx.accounts.global_pool.access_mode = mode;
+        Ok(())
+    }
 
+    /**
+        @disc: Set the Merkle root of an allowlist too large for one entry per wallet. Listed
+               wallets open their own entry with claim_allowlist_access.
+        @param:
+            root:   Root over sha256(wallet) leaves, pairs hashed in sorted order
+    */
+    pub fn set_access_merkle_root(ctx: Context<SetAccessMerkleRoot>, root: [u8; 32]) -> Result<()> {
+        ctx.accounts.global_authority.access_merkle_root = root;
         Ok(())
     }
 
     /**
-    Double Bet function when the user want to do that after win the game
+        @disc: Allow or deny a wallet, opening its access entry on the first call.
+        @param:
+            allowed:    Whether the wallet is allowed (allowlist) or denied (denylist)
     */
-    pub fn double_bet(ctx: Context<DoubleBet>, is_head: bool, game_session_id: u64) -> Result<()> {
-        let player_pool = &mut ctx.accounts.player_pool;
-        let round = player_pool.round;
-        let player = &ctx.accounts.owner;
-        let game_vault = &mut ctx.accounts.game_vault;
-        let global_authority = &ctx.accounts.global_authority;
-        let game_balance = game_vault.lamports();
+    pub fn set_access_entry(ctx: Context<SetAccessEntry>, allowed: bool) -> Result<()> {
+        let access_entry = &mut ctx.accounts.access_entry;
+        access_entry.wallet = ctx.accounts.wallet.key();
+        access_entry.allowed = allowed;
+        Ok(())
+    }
 
-        require!(
-            player_pool.status == GameStatus::Win,
-            GameError::NotAllowedDoubleBet
-        );
+    /**
+    Close a wallet's access entry, returning its rent to the operation authority
+    */
+    pub fn remove_access_entry(ctx: Context<RemoveAccessEntry>) -> Result<()> {
+        close_program_account(
+            &ctx.accounts.access_entry.to_account_info(),
+            &ctx.accounts.operator.to_account_info(),
+        )
+    }
 
+    /**
+        @disc: Open an allowed access entry for the player from a proof against the allowlist
+               Merkle root. Fails when the player already has an entry, so a denial stands.
+        @param:
+            proof:  Sibling hashes from the player's leaf up to the root
+    */
+    pub fn claim_allowlist_access(
+        ctx: Context<ClaimAllowlistAccess>,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let player = ctx.accounts.player.key();
+        let root = &ctx.accounts.global_authority.access_merkle_root;
         require!(
-            (game_balance * 2 * global_authority.rtp / 100 - player_pool.first_bet)
-                < global_authority.max_win_amount,
-            GameError::InvalidBetAmountMaxWinAmountViolation
+            *root != [0; 32] && verify_merkle_proof(root, access_merkle_leaf(&player), &proof),
+            GameError::InvalidMerkleProof
         );
 
-        require!(
-            player.key() == player_pool.player,
-            GameError::NotOriginalPlayer
-        );
+        let access_entry = &mut ctx.accounts.access_entry;
+        access_entry.wallet = player;
+        access_entry.allowed = true;
+        Ok(())
+    }
 
-        player_pool.update_round(GameStatus::Processing, round + 1);
+    /**
+    Require players of play_game to hold a valid attestation from the compliance authority
+    */
+    pub fn set_attestation_required(ctx: Context<SetGlobalPool>, required: bool) -> Result<()> {
+        ctx.accounts.global_pool.attestation_required = required;
+        Ok(())
+    }
 
-        //  = GameStatus::Processing;
-        // msg!("Initial round num: {}", player_pool.round);
-        // player_pool.round += 1;
-        // msg!("Double Bet round num: {}", player_pool.round);
+    /**
+        @disc: State that the player is verified, opening their attestation on the first call.
+               Issuing again renews a revoked or expiring attestation.
+        @param:
+            expires_at:     Time from which the attestation no longer lets the player in
+    */
+    pub fn issue_attestation(ctx: Context<IssueAttestation>, expires_at: i64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(expires_at > now, GameError::InvalidAttestationExpiry);
 
+        let attestation = &mut ctx.accounts.attestation;
+        attestation.player = ctx.accounts.player.key();
+        attestation.verified = true;
+        attestation.expires_at = expires_at;
+        attestation.issued_at = now;
         Ok(())
     }
 
     /**
-    The claim Reward function for User after playing and Win
+    Mark the player's attestation unverified, effective for their next play_game
     */
-    pub fn claim_reward(ctx: Context<ClaimReward>, game_session_id: u64) -> Result<()> {
-        let player_pool = &mut ctx.accounts.player_pool;
-        let player = &ctx.accounts.player;
-        let game_bump = ctx.bumps.game_vault;
-        let game_vault = &mut ctx.accounts.game_vault;
-        let casino_vault = &mut ctx.accounts.casino_vault;
-        let vault_balance = game_vault.lamports();
+    pub fn revoke_attestation(ctx: Context<RevokeAttestation>) -> Result<()> {
+        ctx.accounts.attestation.verified = false;
+        Ok(())
+    }
 
-        require!(
-            player_pool.status == GameStatus::Win,
-            GameError::NotAllowedStatus
-        );
+    /**
+        @disc: Set the VIP tiers players reach by lifetime wagered amount.
+        @param:
+            tiers:          Up to 8 tiers by rising threshold, each with its rakeback in permille
+                            of the house edge on settled stakes
+    */
+    pub fn set_vip_config(ctx: Context<SetVipConfig>, tiers: Vec<VipTier>) -> Result<()> {
+        require!(VipConfig::is_valid(&tiers), GameError::InvalidVipTiers);
+        ctx.accounts.vip_config.tiers = tiers;
+        Ok(())
+    }
 
-        msg!(
-            "Withdrawer: {}
-            Amount: {}",
-            player.key(),
-            vault_balance,
-        );
+    /**
+    Pay the rakeback a player has accrued out of the casino vault
+    */
+    pub fn claim_rakeback(ctx: Context<ClaimRakeback>) -> Result<()> {
+        let player_stats = &mut ctx.accounts.player_stats;
+        let amount = player_stats.rakeback;
+        require!(amount > 0, GameError::NoRakeback);
 
         sol_transfer_with_signer(
-            game_vault.to_account_info(),
-            player.to_account_info(),
+            ctx.accounts.casino_vault.to_account_info(),
+            ctx.accounts.player.to_account_info(),
             ctx.accounts.system_program.to_account_info(),
-            &[&[
-                ctx.accounts.player.key().as_ref(),
-                VAULT_AUTHORITY_SEED.as_bytes(),
-                &game_session_id.to_be_bytes()[..],
-                &[game_bump],
-            ]],
-            vault_balance,
+            &[&[VAULT_AUTHORITY_SEED.as_bytes(), &[ctx.bumps.casino_vault]]],
+            amount,
         )?;
+        player_stats.rakeback = 0;
+        player_stats.rakeback_paid = player_stats
+            .rakeback_paid
+            .checked_add(amount)
+            .ok_or(GameError::MathOverflow)?;
 
-        let dest_starting_lamports = ctx.accounts.operator.lamports();
-        **ctx.accounts.operator.lamports.borrow_mut() = dest_starting_lamports
-            .checked_add(player_pool.to_account_info().lamports())
-            .unwrap();
-        **player_pool.to_account_info().lamports.borrow_mut() = 0;
-
-        let player_pool_account_info = player_pool.to_account_info();
-        let mut player_pool_data = player_pool_account_info.try_borrow_mut_data()?;
-        player_pool_data.fill(0);
+        emit!(RakebackClaimed {
+            player: player_stats.player,
+            vip_tier: player_stats.vip_tier,
+            amount,
+        });
         Ok(())
     }
 
@@ -292,6 +1659,7 @@ pub mod coinflip {
             financial_authority.key() == global_authority.finance_authority,
             GameError::UnauthorizedFinanceAdmin
         );
+        require_no_withdrawal_approvers(&ctx.accounts.withdrawal_config)?;
 
         require!(
             casino_vault.lamports() > amount,
@@ -313,6 +1681,164 @@ pub mod coinflip {
         Ok(())
     }
 
+    /**
+        @disc: Set who approves casino vault withdrawals. With a threshold above 0 withdraw is
+               disabled and withdrawals go through propose, approve and execute_withdrawal.
+        @param:
+            approvers:          Up to 10 distinct approver keys
+            threshold:          Approvals a withdrawal needs, 0 with no approvers to go back to withdraw
+            proposal_timeout:   Seconds a proposal can be approved and executed for
+    */
+    pub fn set_withdrawal_approvers(
+        ctx: Context<SetWithdrawalApprovers>,
+        approvers: Vec<Pubkey>,
+        threshold: u8,
+        proposal_timeout: i64,
+    ) -> Result<()> {
+        require!(
+            WithdrawalConfig::is_valid(&approvers, threshold, proposal_timeout),
+            GameError::InvalidWithdrawalApprovers
+        );
+
+        let withdrawal_config = &mut ctx.accounts.withdrawal_config;
+        withdrawal_config.approvers = approvers;
+        withdrawal_config.threshold = threshold;
+        withdrawal_config.proposal_timeout = proposal_timeout;
+        Ok(())
+    }
+
+    /**
+        @disc: Propose sending `amount` from the casino vault to `recipient`. The proposal takes
+               the config's next proposal id.
+        @param:
+            recipient:  Account the withdrawal goes to
+            amount:     The SOL amount to withdraw
+    */
+    pub fn propose_withdrawal(
+        ctx: Context<ProposeWithdrawal>,
+        recipient: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        let withdrawal_config = &mut ctx.accounts.withdrawal_config;
+        let proposal_id = withdrawal_config.next_proposal_id;
+        withdrawal_config.next_proposal_id += 1;
+
+        let expires_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(withdrawal_config.proposal_timeout)
+            .ok_or(GameError::MathOverflow)?;
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.proposal_id = proposal_id;
+        proposal.proposer = ctx.accounts.financial_admin.key();
+        proposal.recipient = recipient;
+        proposal.amount = amount;
+        proposal.expires_at = expires_at;
+
+        emit!(WithdrawalProposed {
+            proposal_id,
+            recipient,
+            amount,
+            expires_at,
+        });
+        Ok(())
+    }
+
+    pub fn approve_withdrawal(ctx: Context<ApproveWithdrawal>, proposal_id: u64) -> Result<()> {
+        let withdrawal_config = &ctx.accounts.withdrawal_config;
+        let proposal = &mut ctx.accounts.proposal;
+        let approver = ctx.accounts.approver.key();
+
+        require!(
+            withdrawal_config.is_approver(&approver),
+            GameError::UnauthorizedWithdrawalSigner
+        );
+        require!(
+            !proposal.is_expired(Clock::get()?.unix_timestamp),
+            GameError::WithdrawalProposalExpired
+        );
+        require!(
+            !proposal.approvals.contains(&approver),
+            GameError::WithdrawalAlreadyApproved
+        );
+
+        // Approvals of removed approvers no longer count, so they give up their slot
+        proposal
+            .approvals
+            .retain(|key| withdrawal_config.is_approver(key));
+        proposal.approvals.push(approver);
+
+        emit!(WithdrawalApproved {
+            proposal_id,
+            approver,
+            approvals: proposal.approvals.len() as u8,
+        });
+        Ok(())
+    }
+
+    /**
+    Send an approved proposal's amount to its recipient, signed by the proposer or an approver
+    */
+    pub fn execute_withdrawal(ctx: Context<ExecuteWithdrawal>, proposal_id: u64) -> Result<()> {
+        let withdrawal_config = &ctx.accounts.withdrawal_config;
+        let proposal = &ctx.accounts.proposal;
+        let signer = ctx.accounts.signer.key();
+
+        require!(
+            signer == proposal.proposer || withdrawal_config.is_approver(&signer),
+            GameError::UnauthorizedWithdrawalSigner
+        );
+        require!(
+            !proposal.is_expired(Clock::get()?.unix_timestamp),
+            GameError::WithdrawalProposalExpired
+        );
+        require!(
+            withdrawal_config.approvals(proposal) >= withdrawal_config.threshold.max(1) as usize,
+            GameError::WithdrawalNotApproved
+        );
+        require!(
+            ctx.accounts.casino_vault.lamports() > proposal.amount,
+            GameError::InsufficientCasinoVault
+        );
+
+        sol_transfer_with_signer(
+            ctx.accounts.casino_vault.to_account_info(),
+            ctx.accounts.recipient.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            &[&[VAULT_AUTHORITY_SEED.as_bytes(), &[ctx.bumps.casino_vault]]],
+            proposal.amount,
+        )?;
+
+        emit!(WithdrawalExecuted {
+            proposal_id,
+            recipient: proposal.recipient,
+            amount: proposal.amount,
+        });
+        close_program_account(
+            &ctx.accounts.proposal.to_account_info(),
+            &ctx.accounts.proposer,
+        )
+    }
+
+    /**
+    Drop a proposal, signed by the finance authority, its proposer or an approver
+    */
+    pub fn cancel_withdrawal(ctx: Context<CancelWithdrawal>, proposal_id: u64) -> Result<()> {
+        let signer = ctx.accounts.signer.key();
+        require!(
+            signer == ctx.accounts.global_authority.finance_authority
+                || signer == ctx.accounts.proposal.proposer
+                || ctx.accounts.withdrawal_config.is_approver(&signer),
+            GameError::UnauthorizedWithdrawalSigner
+        );
+
+        msg!("Cancelling withdrawal proposal {}", proposal_id);
+        close_program_account(
+            &ctx.accounts.proposal.to_account_info(),
+            &ctx.accounts.proposer,
+        )
+    }
+
     pub fn set_rtp(ctx: Context<SetGlobalPool>, new_rtp: u64) -> Result<()> {
         require!(new_rtp < 100, GameError::InvalidRtp);
 
@@ -331,6 +1857,184 @@ pub mod coinflip {
         Ok(())
     }
 
+    /**
+        @disc: Configure slot hash games
+        @param:
+            delay:      Slots between the bet and the slot whose hash settles it, 0 disables slot hash games
+            max_bet:    Largest stake a slot hash game can have at risk
+    */
+    pub fn set_slot_hash_mode(ctx: Context<SetGlobalPool>, delay: u64, max_bet: u64) -> Result<()> {
+        require!(
+            delay < SLOT_HASHES_MAX_ENTRIES,
+            GameError::InvalidSlotHashDelay
+        );
+
+        ctx.accounts.global_pool.slot_hash_delay = delay;
+        ctx.accounts.global_pool.slot_hash_max_bet = max_bet;
+        Ok(())
+    }
+
+    /**
+        @disc: Publish the tail of a new SHA-256 hash chain, new games are settled by revealing it backwards
+        @param:
+            tail:   Last element of the chain
+            length: Number of preimages behind the tail, new games go back to the operator once they are handed out
+    */
+    pub fn publish_hash_chain(
+        ctx: Context<PublishHashChain>,
+        tail: [u8; 32],
+        length: u64,
+    ) -> Result<()> {
+        let global_authority = &mut ctx.accounts.global_authority;
+
+        // Swapping the chain under a drawn round would let the operator pick its outcome
+        require!(
+            global_authority.hash_chain_assigned == global_authority.hash_chain_revealed,
+            GameError::HashChainPending
+        );
+
+        global_authority.hash_chain_head = tail;
+        global_authority.hash_chain_skipped = 0;
+        global_authority.hash_chain_end = global_authority
+            .hash_chain_revealed
+            .checked_add(length)
+            .ok_or(GameError::MathOverflow)?;
+        Ok(())
+    }
+
+    /**
+        @disc: Set how long the operator has to settle a round before the player can slash the bond
+        @param:
+            timeout:    Seconds, 0 disables deadlines for new rounds
+    */
+    pub fn set_settlement_timeout(ctx: Context<SetGlobalPool>, timeout: i64) -> Result<()> {
+        require!(timeout >= 0, GameError::InvalidSettlementTimeout);
+
+        ctx.accounts.global_pool.settlement_timeout = timeout;
+        Ok(())
+    }
+
+    /**
+        @disc: Configure player versus player challenges
+        @param:
+            rake:       Permille of the pot a settled challenge pays the casino vault
+            timeout:    Seconds a challenge stays open, and then has to be settled in, 0 disables new challenges
+    */
+    pub fn set_challenge_config(
+        ctx: Context<SetGlobalPool>,
+        rake: u64,
+        timeout: i64,
+    ) -> Result<()> {
+        require!(rake <= MAX_CHALLENGE_RAKE, GameError::InvalidChallengeRake);
+        require!(timeout >= 0, GameError::InvalidSettlementTimeout);
+
+        ctx.accounts.global_pool.challenge_rake = rake;
+        ctx.accounts.global_pool.challenge_timeout = timeout;
+        Ok(())
+    }
+
+    /**
+        @disc: Configure the progressive jackpot
+        @param:
+            contribution_rate:  Permille of every bet paid into the jackpot out of its stake
+            trigger_streak:     Rounds a coin flip has to win in a row to take the jackpot when claimed, 0 disables payouts
+    */
+    pub fn set_jackpot_config(
+        ctx: Context<SetJackpot>,
+        contribution_rate: u64,
+        trigger_streak: u8,
+    ) -> Result<()> {
+        require!(
+            contribution_rate <= MAX_JACKPOT_CONTRIBUTION,
+            GameError::InvalidJackpotContribution
+        );
+
+        ctx.accounts.jackpot.contribution_rate = contribution_rate;
+        ctx.accounts.jackpot.trigger_streak = trigger_streak;
+
+        emit!(JackpotConfigUpdated {
+            contribution_rate,
+            trigger_streak,
+        });
+        Ok(())
+    }
+
+    /**
+    Add `amount` lamports from the update authority to the jackpot
+    */
+    pub fn fund_jackpot(ctx: Context<SetJackpot>, amount: u64) -> Result<()> {
+        sol_transfer_user(
+            ctx.accounts.admin.to_account_info(),
+            ctx.accounts.jackpot.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            amount,
+        )?;
+
+        emit!(JackpotFunded {
+            amount,
+            balance: balance_above_rent(&ctx.accounts.jackpot.to_account_info())?,
+        });
+        Ok(())
+    }
+
+    /**
+    Add `amount` lamports to the operator bond that slashed rounds are paid from
+    */
+    pub fn post_bond(ctx: Context<PostBond>, amount: u64) -> Result<()> {
+        sol_transfer_user(
+            ctx.accounts.operator.to_account_info(),
+            ctx.accounts.operator_bond.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            amount,
+        )
+    }
+
+    /**
+    Announce a bond withdrawal. It unlocks after two settlement timeouts, so
+    every round started before the announcement is overdue by then, and never
+    sooner than MIN_BOND_WITHDRAWAL_DELAY, so turning deadlines off or down
+    first cannot free the bond under rounds that are still open
+    */
+    pub fn request_bond_withdrawal(ctx: Context<WithdrawBond>, amount: u64) -> Result<()> {
+        let timeout = ctx.accounts.global_authority.settlement_timeout;
+        let now = Clock::get()?.unix_timestamp;
+        let operator_bond = &mut ctx.accounts.operator_bond;
+
+        operator_bond.pending_withdrawal = amount;
+        operator_bond.withdrawal_unlocks_at = timeout
+            .checked_mul(2)
+            .map(|delay| delay.max(MIN_BOND_WITHDRAWAL_DELAY))
+            .and_then(|delay| now.checked_add(delay))
+            .ok_or(GameError::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn withdraw_bond(ctx: Context<WithdrawBond>) -> Result<()> {
+        let operator_bond = &mut ctx.accounts.operator_bond;
+        let amount = operator_bond.pending_withdrawal;
+
+        require!(
+            amount > 0 && Clock::get()?.unix_timestamp >= operator_bond.withdrawal_unlocks_at,
+            GameError::BondWithdrawalLocked
+        );
+
+        let bond_info = operator_bond.to_account_info();
+        require!(
+            amount <= balance_above_rent(&bond_info)?,
+            GameError::InsufficientBond
+        );
+
+        operator_bond.pending_withdrawal = 0;
+        **bond_info.try_borrow_mut_lamports()? -= amount;
+        let operator_info = ctx.accounts.operator.to_account_info();
+        **operator_info.try_borrow_mut_lamports()? = operator_info
+            .lamports()
+            .checked_add(amount)
+            .ok_or(GameError::MathOverflow)?;
+
+        Ok(())
+    }
+
     pub fn set_operation_authority(
         ctx: Context<SetAuthority>,
         new_operation_authority: Pubkey,
@@ -347,6 +2051,29 @@ pub mod coinflip {
         Ok(())
     }
 
+    /**
+    Set the randomness provider program new games request their outcome from,
+    `Pubkey::default()` returns outcome generation to the operator
+    */
+    pub fn set_randomness_provider(
+        ctx: Context<SetAuthority>,
+        new_randomness_provider: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.global_pool.randomness_provider = new_randomness_provider;
+        Ok(())
+    }
+
+    /**
+    Set the key that issues and revokes player attestations
+    */
+    pub fn set_compliance_authority(
+        ctx: Context<SetAuthority>,
+        new_compliance_authority: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.global_pool.compliance_authority = new_compliance_authority;
+        Ok(())
+    }
+
     pub fn set_update_authority(
         ctx: Context<SetAuthority>,
         new_update_authority: Pubkey,
@@ -354,4 +2081,29 @@ pub mod coinflip {
         ctx.accounts.global_pool.update_authority = new_update_authority;
         Ok(())
     }
+
+    /**
+    Grow the global pool to the current layout after a program upgrade added fields
+    */
+    pub fn resize_global_pool(ctx: Context<ResizeGlobalPool>) -> Result<()> {
+        let global_authority = &ctx.accounts.global_authority;
+
+        // super_admin is the first field and never moves between layouts
+        let super_admin = {
+            let data = global_authority.try_borrow_data()?;
+            Pubkey::try_from(&data[8..40]).map_err(|_| GameError::UnauthorizedSuperAdmin)?
+        };
+        require_keys_eq!(
+            super_admin,
+            ctx.accounts.admin.key(),
+            GameError::UnauthorizedSuperAdmin
+        );
+
+        resize_account(
+            global_authority.to_account_info(),
+            8 + GlobalPool::DATA_SIZE,
+            ctx.accounts.admin.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        )
+    }
 }
@@ -0,0 +1,144 @@
+use crate::constants::{COIN_WIN_CHANCE, PERCENT, PERMILLE};
+
+// Payout and limit math shared by every instruction that moves a stake.
+// Everything is integer and checked so no input can panic the program.
+// Rounds win with odds of `(winning outcomes, total outcomes)`, a coin flip
+// being 50 in 100.
+
+// Amount the game vault holds after winning a round at `stake`: the stake
+// scaled by the table's RTP percentage over the win probability
+pub fn win_amount_at_odds(stake: u64, rtp: u64, odds: (u64, u64)) -> Option<u64> {
+    let (wins, outcomes) = odds;
+    let amount = (stake as u128)
+        .checked_mul(rtp as u128)?
+        .checked_mul(outcomes as u128)?
+        .checked_div((PERCENT as u128).checked_mul(wins as u128)?)?;
+    u64::try_from(amount).ok()
+}
+
+// Whether winning at these odds pays more than the stake. Bets that would not
+// are rejected, so a win never pays less than its odds promise
+pub fn pays_above_stake(stake: u64, rtp: u64, odds: (u64, u64)) -> bool {
+    win_amount_at_odds(stake, rtp, odds).is_some_and(|amount| amount > stake)
+}
+
+// Lamports the casino vault adds to a game vault holding `stake` on a win
+pub fn casino_top_up_at_odds(stake: u64, rtp: u64, odds: (u64, u64)) -> Option<u64> {
+    Some(win_amount_at_odds(stake, rtp, odds)?.saturating_sub(stake))
+}
+
+// Player's profit over `first_bet` if the round at `stake` is won
+pub fn potential_profit_at_odds(
+    stake: u64,
+    first_bet: u64,
+    rtp: u64,
+    odds: (u64, u64),
+) -> Option<u64> {
+    Some(win_amount_at_odds(stake, rtp, odds)?.saturating_sub(first_bet))
+}
+
+// Whether winning the round at `stake` keeps the profit below `max_win_amount`
+pub fn within_max_win_at_odds(
+    stake: u64,
+    first_bet: u64,
+    rtp: u64,
+    odds: (u64, u64),
+    max_win_amount: u64,
+) -> bool {
+    potential_profit_at_odds(stake, first_bet, rtp, odds)
+        .is_some_and(|profit| profit < max_win_amount)
+}
+
+// Win amount of a round won `win_chance` percent of the time, like a dice roll
+pub fn win_amount_at_chance(stake: u64, rtp: u64, win_chance: u64) -> Option<u64> {
+    win_amount_at_odds(stake, rtp, (win_chance, PERCENT))
+}
+
+// Number of ways `k` of `n` coins can show heads, `n` being at most MAX_COINS
+pub fn binomial(n: u64, k: u64) -> u64 {
+    if k > n {
+        return 0;
+    }
+    (0..k.min(n - k)).fold(1, |ways, i| ways * (n - i) / (i + 1))
+}
+
+// Odds of `coins` fair coins showing exactly `heads` heads, or at least
+// `heads` heads unless `exact`. The payout table of a multi-coin flip
+pub fn multi_coin_odds(coins: u8, heads: u8, exact: bool) -> (u64, u64) {
+    let wins = match exact {
+        true => binomial(coins as u64, heads as u64),
+        false => (heads..=coins)
+            .map(|k| binomial(coins as u64, k as u64))
+            .sum(),
+    };
+    (wins, 1 << coins)
+}
+
+// Amount the game vault holds after a batch settles: every flip whose bit is
+// set in `outcomes` pays like a coin flip won at its amount
+pub fn batch_win_amount(amounts: &[u64], outcomes: u64, rtp: u64) -> Option<u64> {
+    amounts
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| outcomes >> i & 1 == 1)
+        .try_fold(0u64, |total, (_, amount)| {
+            total.checked_add(win_amount(*amount, rtp)?)
+        })
+}
+
+// Casino's cut of a player versus player pot, `rake` being in permille
+pub fn pot_rake(pot: u64, rake: u64) -> Option<u64> {
+    let amount = (pot as u128)
+        .checked_mul(rake as u128)?
+        .checked_div(PERMILLE as u128)?;
+    u64::try_from(amount).ok()
+}
+
+// Slice of a bet paid into the jackpot, `rate` being in permille
+pub fn jackpot_contribution(bet: u64, rate: u64) -> Option<u64> {
+    permille_of(bet, rate)
+}
+
+// Prize of a tournament place taking `share` permille of the pool
+pub fn prize_amount(pool: u64, share: u16) -> Option<u64> {
+    permille_of(pool, share as u64)
+}
+
+// Rakeback on a settled `stake`: `rate` permille of the house edge the table
+// keeps at `rtp` percent
+pub fn rakeback_amount(stake: u64, rtp: u64, rate: u16) -> Option<u64> {
+    let edge = (stake as u128)
+        .checked_mul(PERCENT.checked_sub(rtp)? as u128)?
+        .checked_div(PERCENT as u128)?;
+    permille_of(u64::try_from(edge).ok()?, rate as u64)
+}
+
+fn permille_of(amount: u64, permille: u64) -> Option<u64> {
+    let part = (amount as u128)
+        .checked_mul(permille as u128)?
+        .checked_div(PERMILLE as u128)?;
+    u64::try_from(part).ok()
+}
+
+// Coin flip amounts: the stake doubled and scaled by the RTP
+pub fn win_amount(stake: u64, rtp: u64) -> Option<u64> {
+    win_amount_at_odds(stake, rtp, (COIN_WIN_CHANCE, PERCENT))
+}
+
+pub fn casino_top_up(stake: u64, rtp: u64) -> Option<u64> {
+    casino_top_up_at_odds(stake, rtp, (COIN_WIN_CHANCE, PERCENT))
+}
+
+pub fn potential_profit(stake: u64, first_bet: u64, rtp: u64) -> Option<u64> {
+    potential_profit_at_odds(stake, first_bet, rtp, (COIN_WIN_CHANCE, PERCENT))
+}
+
+pub fn within_max_win(stake: u64, first_bet: u64, rtp: u64, max_win_amount: u64) -> bool {
+    within_max_win_at_odds(
+        stake,
+        first_bet,
+        rtp,
+        (COIN_WIN_CHANCE, PERCENT),
+        max_win_amount,
+    )
+}
@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    hash::hashv,
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
+use anchor_lang::InstructionData;
+
+use crate::constants::{PROVIDER_AUTHORITY_SEED, RANDOMNESS_REQUESTER_SEED};
+use crate::error::GameError;
+
+// Interface between the game and a randomness provider (VRF oracle).
+//
+// When a round starts the game invokes the provider's `request_randomness`
+// instruction with `seed: [u8; 32]`, `callback_program: Pubkey` and
+// `callback_data: Vec<u8>` as Anchor arguments, and the accounts
+//   0. requester        the game's RANDOMNESS_REQUESTER_SEED PDA, signer
+//   1. request          provider account tracking the request, writable
+//   2. payer            funds the request account, signer, writable
+//   3. system_program
+//   4.. callback accounts, to be handed back unchanged
+//
+// Once the randomness is known the provider invokes `callback_program` with
+// its own PROVIDER_AUTHORITY_SEED PDA as first account and signer, followed
+// by the callback accounts, and `callback_data ‖ randomness` as data.
+// Providers with another interface (Switchboard, ORAO) plug in through a
+// small adapter program implementing this one.
+
+// sha256("global:request_randomness")[..8]
+pub const REQUEST_RANDOMNESS_DISCRIMINATOR: [u8; 8] = [213, 5, 173, 166, 37, 236, 31, 18];
+
+// Seed the provider is asked to randomize for the round holding `nonce`
+pub fn randomness_seed(player_pool: &Pubkey, nonce: u64) -> [u8; 32] {
+    hashv(&[player_pool.as_ref(), &nonce.to_be_bytes()[..]]).to_bytes()
+}
+
+// The signer a provider program uses when it calls back into the game
+pub fn provider_authority(provider: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[PROVIDER_AUTHORITY_SEED.as_bytes()], provider).0
+}
+
+// Ask `provider` for randomness on `seed`. `provider_accounts` are the
+// provider program, the game's requester PDA and the request account, as
+// passed in the remaining accounts of the instruction starting the round
+pub fn request_randomness<'info>(
+    provider: &Pubkey,
+    provider_accounts: &[AccountInfo<'info>],
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    seed: [u8; 32],
+    callback_accounts: &[AccountInfo<'info>],
+    callback_data: Vec<u8>,
+) -> Result<()> {
+    let [provider_program, requester, request, ..] = provider_accounts else {
+        return err!(GameError::InvalidRandomnessProvider);
+    };
+    require_keys_eq!(
+        provider_program.key(),
+        *provider,
+        GameError::InvalidRandomnessProvider
+    );
+
+    let (requester_key, requester_bump) =
+        Pubkey::find_program_address(&[RANDOMNESS_REQUESTER_SEED.as_bytes()], &crate::ID);
+    require_keys_eq!(
+        requester.key(),
+        requester_key,
+        GameError::InvalidRandomnessProvider
+    );
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(requester_key, true),
+        AccountMeta::new(request.key(), false),
+        AccountMeta::new(payer.key(), true),
+        AccountMeta::new_readonly(system_program.key(), false),
+    ];
+    accounts.extend(callback_accounts.iter().map(|account| AccountMeta {
+        pubkey: account.key(),
+        is_signer: false,
+        is_writable: account.is_writable,
+    }));
+
+    let mut data = REQUEST_RANDOMNESS_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&seed);
+    data.extend_from_slice(crate::ID.as_ref());
+    data.extend_from_slice(&(callback_data.len() as u32).to_le_bytes());
+    data.extend_from_slice(&callback_data);
+
+    let mut infos = vec![
+        requester.clone(),
+        request.clone(),
+        payer,
+        system_program,
+        provider_program.clone(),
+    ];
+    infos.extend_from_slice(callback_accounts);
+
+    invoke_signed(
+        &Instruction {
+            program_id: *provider,
+            accounts,
+            data,
+        },
+        &infos,
+        &[&[RANDOMNESS_REQUESTER_SEED.as_bytes(), &[requester_bump]]],
+    )?;
+
+    Ok(())
+}
+
+// `consume_randomness` instruction data without the trailing randomness,
+// which the provider appends
+pub fn consume_randomness_data(game_session_id: u64) -> Vec<u8> {
+    let mut data = crate::instruction::ConsumeRandomness {
+        game_session_id,
+        randomness: [0; 32],
+    }
+    .data();
+    data.truncate(data.len() - 32);
+    data
+}
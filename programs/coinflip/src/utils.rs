@@ -1,10 +1,31 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::{program::invoke, system_instruction::transfer};
+use anchor_lang::solana_program::{
+    ed25519_program,
+    hash::hashv,
+    native_token::LAMPORTS_PER_SOL,
+    program::invoke,
+    system_instruction::transfer,
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+};
 // use sha2::Digest;
 // use sha2::Sha256;
 use solana_program::program::invoke_signed;
 use std::cmp::Ordering;
 
+use crate::account::{
+    AccessEntry, Attestation, GameStatus, GlobalPool, Jackpot, Leaderboard, PlayerBalance,
+    PlayerLimits, PlayerPool, PlayerStats, PromoCredit, Tournament, TournamentEntry, VipConfig,
+    WithdrawalConfig,
+};
+use crate::constants::{MAX_COINS, PERCENT, VAULT_AUTHORITY_SEED};
+use crate::error::GameError;
+use crate::events::{JackpotContributed, JackpotWon};
+use crate::math::{
+    batch_win_amount, casino_top_up_at_odds, jackpot_contribution, rakeback_amount,
+    within_max_win_at_odds,
+};
+use crate::randomness::{consume_randomness_data, randomness_seed, request_randomness};
+
 // transfer sol
 pub fn sol_transfer_with_signer<'a>(
     source: AccountInfo<'a>,
@@ -29,14 +50,14 @@ pub fn sol_transfer_user<'a>(
     Ok(())
 }
 
-pub fn puffed_out_string(s: &String, size: usize) -> String {
+pub fn puffed_out_string(s: &str, size: usize) -> String {
     let mut array_of_zeroes = vec![];
 
     let puff_amount = size - s.len();
     while array_of_zeroes.len() < puff_amount {
         array_of_zeroes.push(0u8);
     }
-    s.clone() + std::str::from_utf8(&array_of_zeroes).unwrap()
+    s.to_owned() + std::str::from_utf8(&array_of_zeroes).unwrap()
 }
 
 //  Generate pseudo random number
@@ -68,6 +89,1149 @@ pub struct HashStruct {
 //     return hashed_randoms[2] % 1000000;
 // }
 
+// Derive the randomness of one game round from the operator's secret server seed
+pub fn derive_game_randomness(
+    server_seed: &[u8],
+    player: &Pubkey,
+    game_session_id: u64,
+    round: u8,
+) -> [u8; 32] {
+    hashv(&[
+        server_seed,
+        player.as_ref(),
+        &game_session_id.to_be_bytes()[..],
+        &[round],
+    ])
+    .to_bytes()
+}
+
+// Commitment to the bet of a hash chain round, taken in the slot its position
+// is handed out so the operator's chain value only meets it once revealed
+pub fn chain_bet_commitment(player_pool: &PlayerPool, slot: u64) -> [u8; 32] {
+    hashv(&[
+        player_pool.player.as_ref(),
+        &player_pool.game_session_id.to_be_bytes()[..],
+        &player_pool.nonce.to_be_bytes()[..],
+        &[
+            player_pool.round,
+            player_pool.is_head as u8,
+            player_pool.dice_target,
+            player_pool.coin_count,
+            player_pool.heads_target,
+            player_pool.exact_heads as u8,
+        ],
+        &player_pool.batch_choices.to_be_bytes()[..],
+        &slot.to_be_bytes()[..],
+    ])
+    .to_bytes()
+}
+
+// Randomness of a hash chain round, mixing the revealed chain value with the
+// commitment to its bet
+pub fn chain_game_randomness(
+    reveal: &[u8; 32],
+    player_pool: &PlayerPool,
+    game_session_id: u64,
+) -> [u8; 32] {
+    let server_seed = hashv(&[reveal, &player_pool.player_seed]).to_bytes();
+    derive_game_randomness(
+        &server_seed,
+        &player_pool.player,
+        game_session_id,
+        player_pool.round,
+    )
+}
+
+// The coin lands on head when the lowest bit of the randomness is set
+pub fn is_head_from_randomness(randomness: &[u8; 32]) -> bool {
+    randomness[0] & 1 == 1
+}
+
+// Dice roll between 0 and 99 from the first 8 bytes of the randomness
+pub fn dice_roll_from_randomness(randomness: &[u8; 32]) -> u8 {
+    let value = u64::from_le_bytes(randomness[..8].try_into().unwrap());
+    (value % PERCENT) as u8
+}
+
+// Faces of a multi-coin flip, coin `i` landing on head when bit `i` of the
+// randomness is set. The first coin lands like a single coin flip
+pub fn coin_faces_from_randomness(randomness: &[u8; 32], coins: u8) -> [bool; MAX_COINS as usize] {
+    let mut faces = [false; MAX_COINS as usize];
+    for (i, face) in faces.iter_mut().enumerate().take(coins as usize) {
+        *face = randomness[i / 8] >> (i % 8) & 1 == 1;
+    }
+    faces
+}
+
+// Whether the round in progress wins under `randomness`: a multi-coin flip
+// when the heads hit its target, a dice round when the roll is under its
+// target, a coin flip when the coin shows the player's side
+pub fn is_win_from_randomness(randomness: &[u8; 32], player_pool: &PlayerPool) -> bool {
+    if player_pool.coin_count != 0 {
+        let heads = coin_faces_from_randomness(randomness, player_pool.coin_count)
+            .iter()
+            .filter(|head| **head)
+            .count() as u8;
+        return match player_pool.exact_heads {
+            true => heads == player_pool.heads_target,
+            false => heads >= player_pool.heads_target,
+        };
+    }
+
+    match player_pool.dice_target {
+        0 => is_head_from_randomness(randomness) == player_pool.is_head,
+        target => dice_roll_from_randomness(randomness) < target,
+    }
+}
+
+// Flips of a batch won under `randomness`: flip `i` wins when bit `i` of the
+// randomness matches its choice, the same bit a multi-coin flip's coin `i` uses
+pub fn batch_outcomes_from_randomness(randomness: &[u8; 32], player_pool: &PlayerPool) -> u64 {
+    let heads = u64::from_le_bytes(randomness[..8].try_into().unwrap());
+    let flips = (1u64 << player_pool.batch_amounts.len()) - 1;
+    !(heads ^ player_pool.batch_choices) & flips
+}
+
+// Keep the faces a multi-coin round landed on for the UI to show. Only
+// settlements that see the randomness can record them
+pub fn record_coin_faces(randomness: &[u8; 32], player_pool: &mut PlayerPool) {
+    if player_pool.coin_count == 0 {
+        return;
+    }
+
+    player_pool.coin_faces = coin_faces_from_randomness(randomness, player_pool.coin_count);
+    let faces: String = player_pool.coin_faces[..player_pool.coin_count as usize]
+        .iter()
+        .map(|head| if *head { 'H' } else { 'T' })
+        .collect();
+    msg!("Coins landed {}", faces);
+}
+
+// Randomness of a slot hash game round, mixing the hash of its target slot
+// with the seed the player committed to
+pub fn slot_game_randomness(
+    slot_hash: &[u8; 32],
+    player_seed: &[u8; 32],
+    game_session_id: u64,
+    round: u8,
+) -> [u8; 32] {
+    hashv(&[
+        slot_hash,
+        player_seed,
+        &game_session_id.to_be_bytes()[..],
+        &[round],
+    ])
+    .to_bytes()
+}
+
+// SlotHashes sysvar layout: entry count (u64) followed by (slot, hash) pairs,
+// newest first
+const SLOT_HASH_ENTRY_SIZE: usize = 8 + 32;
+
+// Hash of `target`, or of the first slot after it when `target` was skipped.
+// `None` once the sysvar no longer reaches back to `target`
+pub fn slot_hash_from(slot_hashes: &AccountInfo, target: u64) -> Result<Option<[u8; 32]>> {
+    let data = slot_hashes.try_borrow_data()?;
+    let count = data
+        .get(..8)
+        .map(|len| u64::from_le_bytes(len.try_into().unwrap()) as usize)
+        .unwrap_or_default();
+
+    let mut found = None;
+    for entry in data
+        .get(8..)
+        .unwrap_or_default()
+        .chunks_exact(SLOT_HASH_ENTRY_SIZE)
+        .take(count)
+    {
+        let slot = u64::from_le_bytes(entry[..8].try_into().unwrap());
+        if slot < target {
+            return Ok(found.map(|(_, hash)| hash));
+        }
+        found = Some((slot, entry[8..].try_into().unwrap()));
+    }
+
+    // Every entry left is newer than `target`, which may have dropped out
+    Ok(found
+        .filter(|(slot, _)| *slot == target)
+        .map(|(_, hash)| hash))
+}
+
+// Message the operation authority signs off-chain to settle one round
+pub fn settlement_message(
+    player: &Pubkey,
+    game_session_id: u64,
+    round: u8,
+    is_win: bool,
+    nonce: u64,
+) -> Vec<u8> {
+    [
+        crate::ID.as_ref(),
+        player.as_ref(),
+        &game_session_id.to_be_bytes()[..],
+        &[round, is_win as u8],
+        &nonce.to_be_bytes()[..],
+    ]
+    .concat()
+}
+
+// Message a player, or one of their session keys, signs off-chain to bet
+// from their prepaid balance without sending the transaction
+pub fn bet_intent_message(
+    player: &Pubkey,
+    is_head: bool,
+    bet_amount: u64,
+    game_session_id: u64,
+    expires_at: i64,
+    nonce: u64,
+) -> Vec<u8> {
+    [
+        crate::ID.as_ref(),
+        player.as_ref(),
+        &[is_head as u8],
+        &bet_amount.to_be_bytes()[..],
+        &game_session_id.to_be_bytes()[..],
+        &expires_at.to_be_bytes()[..],
+        &nonce.to_be_bytes()[..],
+    ]
+    .concat()
+}
+
+// Offsets header of an Ed25519 precompile instruction, after the signature
+// count and padding bytes
+const ED25519_OFFSETS_START: usize = 2;
+const ED25519_OFFSETS_SIZE: usize = 14;
+
+// Make sure the instruction right before the current one is an Ed25519
+// precompile call over `message` by `signer`. The runtime rejects the whole
+// transaction if the signature itself is invalid, so only the signed data
+// needs checking here
+pub fn verify_ed25519_signature(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current = load_current_index_checked(instructions)?;
+    require!(current > 0, GameError::InvalidSignature);
+
+    let ix = load_instruction_at_checked(current as usize - 1, instructions)?;
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        GameError::InvalidSignature
+    );
+
+    let data = &ix.data;
+    require!(
+        data.len() >= ED25519_OFFSETS_START + ED25519_OFFSETS_SIZE && data[0] == 1,
+        GameError::InvalidSignature
+    );
+
+    let offset = |index: usize| {
+        let at = ED25519_OFFSETS_START + index * 2;
+        u16::from_le_bytes([data[at], data[at + 1]]) as usize
+    };
+    // Signature, public key and message must all live in the precompile
+    // instruction itself, not in some other instruction of the transaction
+    require!(
+        offset(1) == u16::MAX as usize
+            && offset(3) == u16::MAX as usize
+            && offset(6) == u16::MAX as usize,
+        GameError::InvalidSignature
+    );
+
+    let public_key = data.get(offset(2)..offset(2) + 32);
+    let signed = data.get(offset(4)..offset(4) + offset(5));
+    require!(
+        public_key == Some(signer.as_ref()) && signed == Some(message),
+        GameError::InvalidSignature
+    );
+
+    Ok(())
+}
+
+// Accounts of an instruction starting a game
+pub struct OpenGameAccounts<'a, 'info> {
+    pub owner: &'a AccountInfo<'info>,
+    pub operator: &'a Signer<'info>,
+    pub player_pool: &'a mut Account<'info, PlayerPool>,
+    pub global_authority: &'a mut Account<'info, GlobalPool>,
+    pub casino_vault: &'a AccountInfo<'info>,
+    pub game_vault: &'a AccountInfo<'info>,
+    pub system_program: &'a Program<'info, System>,
+    pub rent: &'a Sysvar<'info, Rent>,
+    // Prepaid balance the bet comes from instead of the owner's wallet
+    pub player_balance: Option<&'a mut Account<'info, PlayerBalance>>,
+    // Promo credit the house stakes the bet for, with the casino vault's bump
+    pub promo_credit: Option<(&'a mut Account<'info, PromoCredit>, u8)>,
+    // Limits the owner set on themselves, at their fixed address
+    pub player_limits: &'a AccountInfo<'info>,
+    // Allow or deny listing of the owner, at its fixed address
+    pub access_entry: &'a AccountInfo<'info>,
+    // Verification of the owner, at its fixed address
+    pub attestation: &'a AccountInfo<'info>,
+    // Wagered totals of the owner and the rakeback tiers, at their fixed
+    // addresses, counted when the round is settled
+    pub player_stats: &'a AccountInfo<'info>,
+    pub vip_config: &'a AccountInfo<'info>,
+    // Tournament and entry of a registered player, scored by the stake
+    pub tournament: Option<&'a Account<'info, Tournament>>,
+    pub tournament_entry: Option<&'a mut Account<'info, TournamentEntry>>,
+    // Jackpot fed from the stake, none when the house stakes the bet
+    pub jackpot: Option<&'a mut Account<'info, Jackpot>>,
+}
+
+// Escrow the bet and open the player pool of a new game, shared by every
+// instruction starting a game. Dice and multi-coin games set their target on
+// the player pool first, anything else is a coin flip
+pub fn open_game<'info>(
+    accounts: &mut OpenGameAccounts<'_, 'info>,
+    is_head: bool,
+    bet_amount: u64,
+    game_session_id: u64,
+) -> Result<()> {
+    check_player_access(accounts.global_authority, accounts.access_entry)?;
+    check_player_attestation(accounts.global_authority, accounts.attestation)?;
+
+    // A bet staked by the house counts towards the wager limits but risks
+    // nothing of the player's own
+    let loss = if accounts.promo_credit.is_some() {
+        0
+    } else {
+        bet_amount
+    };
+    enforce_player_limits(accounts.player_limits, bet_amount, loss)?;
+
+    let player_pool = &mut *accounts.player_pool;
+    let player = accounts.owner;
+    let global_authority = &mut *accounts.global_authority;
+
+    require!(
+        global_authority.min_bet_amount <= bet_amount,
+        GameError::InvalidBetAmount
+    );
+
+    require!(
+        within_max_win_at_odds(
+            bet_amount,
+            bet_amount,
+            global_authority.rtp,
+            player_pool.win_odds(),
+            global_authority.max_win_amount
+        ),
+        GameError::InvalidBetAmountMaxWinAmountViolation
+    );
+
+    require!(
+        accounts.casino_vault.to_account_info().lamports() > bet_amount,
+        GameError::InsufficientCasinoVault
+    );
+
+    // Transfer rent fee for PDA of player pool
+    sol_transfer_user(
+        accounts.operator.to_account_info().clone(),
+        player_pool.to_account_info().clone(),
+        accounts.system_program.to_account_info().clone(),
+        accounts.rent.minimum_balance(0),
+    )?;
+
+    // The jackpot's slice of the bet comes out of the stake the game vault escrows
+    let contribution = match accounts.jackpot.as_deref() {
+        Some(jackpot) => jackpot_contribution(bet_amount, jackpot.contribution_rate)
+            .ok_or(GameError::MathOverflow)?,
+        None => 0,
+    };
+    let stake = bet_amount - contribution;
+
+    match (
+        accounts.player_balance.as_deref(),
+        accounts.promo_credit.as_mut(),
+    ) {
+        (_, Some((promo_credit, casino_bump))) => {
+            promo_credit.stake(bet_amount)?;
+            sol_transfer_with_signer(
+                accounts.casino_vault.clone(),
+                accounts.game_vault.clone(),
+                accounts.system_program.to_account_info(),
+                &[&[VAULT_AUTHORITY_SEED.as_bytes(), &[*casino_bump]]],
+                bet_amount,
+            )?;
+            player_pool.promo_stake = bet_amount;
+        }
+        (Some(player_balance), None) => {
+            let balance_info = player_balance.to_account_info();
+            require!(
+                balance_above_rent(&balance_info)? >= bet_amount,
+                GameError::InsufficientPlayerBalance
+            );
+            debit_program_account(&balance_info, accounts.game_vault, stake)?;
+            if let Some(jackpot) = accounts.jackpot.as_deref() {
+                debit_program_account(&balance_info, &jackpot.to_account_info(), contribution)?;
+            }
+        }
+        (None, None) => {
+            require!(
+                accounts.owner.to_account_info().lamports() > bet_amount,
+                GameError::InsufficientUserBalance
+            );
+
+            // Transfer bet_amount Sol to this PDA from User Wallet
+            sol_transfer_user(
+                accounts.owner.to_account_info(),
+                accounts.game_vault.to_account_info(),
+                accounts.system_program.to_account_info(),
+                stake,
+            )?;
+            if let Some(jackpot) = accounts.jackpot.as_deref() {
+                if contribution > 0 {
+                    sol_transfer_user(
+                        accounts.owner.to_account_info(),
+                        jackpot.to_account_info(),
+                        accounts.system_program.to_account_info(),
+                        contribution,
+                    )?;
+                }
+            }
+        }
+    }
+
+    player_pool.status = GameStatus::Processing;
+    player_pool.round = 1;
+    player_pool.first_bet = bet_amount;
+    player_pool.player = player.key();
+    player_pool.is_head = is_head;
+    player_pool.game_session_id = game_session_id;
+    player_pool.nonce = global_authority.next_nonce();
+
+    record_tournament_bet(
+        accounts.tournament,
+        accounts.tournament_entry.as_deref_mut(),
+        player_pool,
+        bet_amount,
+    )?;
+
+    if let Some(jackpot) = accounts.jackpot.as_deref_mut() {
+        record_jackpot_contribution(jackpot, player_pool, contribution)?;
+    }
+
+    if player_pool.is_batch() {
+        msg!(
+            "User places a batch of {} flips, bet amount is {}SOL",
+            player_pool.batch_amounts.len(),
+            bet_amount as f64 / LAMPORTS_PER_SOL as f64
+        );
+    } else if player_pool.coin_count != 0 {
+        msg!(
+            "User flips {} coins for {} {} heads, bet amount is {}SOL",
+            player_pool.coin_count,
+            if player_pool.exact_heads {
+                "exactly"
+            } else {
+                "at least"
+            },
+            player_pool.heads_target,
+            bet_amount as f64 / LAMPORTS_PER_SOL as f64
+        );
+    } else if player_pool.dice_target != 0 {
+        msg!(
+            "User rolls under {}, bet amount is {}SOL",
+            player_pool.dice_target,
+            bet_amount as f64 / LAMPORTS_PER_SOL as f64
+        );
+    } else if is_head {
+        msg!(
+            "User's choice is Head, bet amount is {}SOL",
+            bet_amount as f64 / LAMPORTS_PER_SOL as f64
+        );
+    } else {
+        msg!(
+            "User's choice is Tail, bet amount is {}SOL",
+            bet_amount as f64 / LAMPORTS_PER_SOL as f64
+        );
+    }
+
+    Ok(())
+}
+
+// Decide how the first round of a game opened by `open_game` is drawn: by the
+// randomness provider, by the hash chain or by the operator before its deadline
+pub fn start_round<'info>(
+    accounts: &mut OpenGameAccounts<'_, 'info>,
+    provider_accounts: &[AccountInfo<'info>],
+    game_session_id: u64,
+) -> Result<()> {
+    let provider = accounts.global_authority.randomness_provider;
+    if provider != Pubkey::default() {
+        accounts.player_pool.randomness_provider = provider;
+
+        request_randomness(
+            &provider,
+            provider_accounts,
+            accounts.operator.to_account_info(),
+            accounts.system_program.to_account_info(),
+            randomness_seed(&accounts.player_pool.key(), accounts.player_pool.nonce),
+            &[
+                accounts.operator.to_account_info(),
+                accounts.owner.to_account_info(),
+                accounts.global_authority.to_account_info(),
+                accounts.player_pool.to_account_info(),
+                accounts.game_vault.to_account_info(),
+                accounts.casino_vault.to_account_info(),
+                accounts.player_stats.clone(),
+                accounts.vip_config.clone(),
+                accounts.system_program.to_account_info(),
+            ],
+            consume_randomness_data(game_session_id),
+        )?;
+    } else {
+        let global_authority = &mut *accounts.global_authority;
+        let player_pool = &mut *accounts.player_pool;
+        if global_authority.hash_chain_active() {
+            player_pool.chain_position = global_authority.next_chain_position();
+            player_pool.player_seed = chain_bet_commitment(player_pool, Clock::get()?.slot);
+        }
+        player_pool.settlement_deadline = global_authority.settlement_deadline()?;
+    }
+
+    Ok(())
+}
+
+// Accounts of an instruction doubling the bet of a won game
+pub struct DoubleBetAccounts<'a, 'info> {
+    pub owner: &'a AccountInfo<'info>,
+    pub operator: &'a Signer<'info>,
+    pub player_pool: &'a mut Account<'info, PlayerPool>,
+    pub global_authority: &'a mut Account<'info, GlobalPool>,
+    pub casino_vault: &'a AccountInfo<'info>,
+    pub game_vault: &'a AccountInfo<'info>,
+    pub system_program: &'a Program<'info, System>,
+    // Limits the owner set on themselves, at their fixed address
+    pub player_limits: &'a AccountInfo<'info>,
+    // Wagered totals of the owner and the rakeback tiers, at their fixed
+    // addresses, counted when the round is settled
+    pub player_stats: &'a AccountInfo<'info>,
+    pub vip_config: &'a AccountInfo<'info>,
+    // Tournament and entry of a registered player, scored by the stake
+    pub tournament: Option<&'a Account<'info, Tournament>>,
+    pub tournament_entry: Option<&'a mut Account<'info, TournamentEntry>>,
+}
+
+// Put the whole game vault of a won game at stake for another round, drawn
+// the same way as the round before, a provider's accounts being
+// `remaining_accounts`
+pub fn double_round<'info>(
+    accounts: &mut DoubleBetAccounts<'_, 'info>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    is_head: bool,
+    game_session_id: u64,
+) -> Result<()> {
+    let player_pool = &mut *accounts.player_pool;
+    let round = player_pool.round;
+    let player = accounts.owner;
+    let global_authority = &mut *accounts.global_authority;
+    let game_balance = accounts.game_vault.lamports();
+
+    // A double bet only risks the winnings already in the game vault
+    enforce_player_limits(accounts.player_limits, game_balance, 0)?;
+
+    require!(
+        player_pool.status == GameStatus::Win && !player_pool.is_batch(),
+        GameError::NotAllowedDoubleBet
+    );
+
+    require!(
+        within_max_win_at_odds(
+            game_balance,
+            player_pool.first_bet,
+            global_authority.rtp,
+            player_pool.win_odds(),
+            global_authority.max_win_amount
+        ),
+        GameError::InvalidBetAmountMaxWinAmountViolation
+    );
+
+    require!(
+        player.key() == player_pool.player,
+        GameError::NotOriginalPlayer
+    );
+
+    record_tournament_bet(
+        accounts.tournament,
+        accounts.tournament_entry.as_deref_mut(),
+        player_pool,
+        game_balance,
+    )?;
+
+    player_pool.update_round(GameStatus::Processing, round + 1);
+    player_pool.is_head = is_head;
+    player_pool.nonce = global_authority.next_nonce();
+
+    if player_pool.target_slot != 0 {
+        require!(
+            game_balance <= global_authority.slot_hash_max_bet,
+            GameError::SlotHashBetTooLarge
+        );
+        player_pool.target_slot = Clock::get()?.slot + global_authority.slot_hash_delay;
+    }
+
+    if player_pool.chain_position != 0 {
+        // A chain that ran out or was retired since the last round leaves it to the operator
+        player_pool.chain_position = if global_authority.hash_chain_active() {
+            global_authority.next_chain_position()
+        } else {
+            0
+        };
+        if player_pool.chain_position != 0 {
+            player_pool.player_seed = chain_bet_commitment(player_pool, Clock::get()?.slot);
+        }
+    }
+
+    // Rounds the operator has to settle get a fresh deadline
+    if player_pool.randomness_provider == Pubkey::default() && player_pool.target_slot == 0 {
+        player_pool.settlement_deadline = global_authority.settlement_deadline()?;
+    }
+
+    let provider = player_pool.randomness_provider;
+    if provider != Pubkey::default() {
+        request_randomness(
+            &provider,
+            remaining_accounts,
+            accounts.operator.to_account_info(),
+            accounts.system_program.to_account_info(),
+            randomness_seed(&accounts.player_pool.key(), accounts.player_pool.nonce),
+            &[
+                accounts.operator.to_account_info(),
+                accounts.owner.to_account_info(),
+                accounts.global_authority.to_account_info(),
+                accounts.player_pool.to_account_info(),
+                accounts.game_vault.to_account_info(),
+                accounts.casino_vault.to_account_info(),
+                accounts.player_stats.clone(),
+                accounts.vip_config.clone(),
+                accounts.system_program.to_account_info(),
+            ],
+            consume_randomness_data(game_session_id),
+        )?;
+    }
+
+    Ok(())
+}
+
+pub struct SettleAccounts<'info> {
+    pub owner: AccountInfo<'info>,
+    pub operator: AccountInfo<'info>,
+    pub game_vault: AccountInfo<'info>,
+    pub casino_vault: AccountInfo<'info>,
+    pub player_stats: AccountInfo<'info>,
+    pub vip_config: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub game_bump: u8,
+    pub casino_bump: u8,
+}
+
+// Settle the round in progress. A win tops the game vault up from the casino,
+// a loss sends the vault to the casino and closes the player pool to the operator
+pub fn settle_round<'info>(
+    player_pool: &mut Account<'info, PlayerPool>,
+    accounts: &SettleAccounts<'info>,
+    round_id: u8,
+    is_win: bool,
+    rtp: u64,
+) -> Result<()> {
+    msg!(
+        "RoundId: {}, PlayerPoolRoundId: {}",
+        round_id,
+        player_pool.round
+    );
+    require!(round_id == player_pool.round, GameError::RoundNumMismatch);
+
+    require!(
+        player_pool.status == GameStatus::Processing,
+        GameError::NotAllowedStatus
+    );
+
+    record_round_wager(player_pool, accounts, rtp)?;
+    let vault_balance = accounts.game_vault.lamports();
+
+    if is_win {
+        let top_up = casino_top_up_at_odds(vault_balance, rtp, player_pool.win_odds())
+            .ok_or(GameError::MathOverflow)?;
+
+        sol_transfer_with_signer(
+            accounts.casino_vault.clone(),
+            accounts.game_vault.clone(),
+            accounts.system_program.clone(),
+            &[&[VAULT_AUTHORITY_SEED.as_bytes(), &[accounts.casino_bump]]],
+            top_up,
+        )?;
+
+        player_pool.status = GameStatus::Win;
+    } else {
+        lose_round(player_pool, accounts)?;
+    }
+
+    Ok(())
+}
+
+// Count the stake of the round being settled towards the owner's wagered
+// totals and rakeback. The first round stakes the whole bet, a double bet the
+// game vault, and a game staked by the house counts for nothing
+fn record_round_wager<'info>(
+    player_pool: &Account<'info, PlayerPool>,
+    accounts: &SettleAccounts<'info>,
+    rtp: u64,
+) -> Result<()> {
+    if player_pool.promo_stake != 0 {
+        return Ok(());
+    }
+    let stake = if player_pool.round == 1 {
+        player_pool.first_bet
+    } else {
+        accounts.game_vault.lamports()
+    };
+    record_wager(&accounts.player_stats, &accounts.vip_config, stake, rtp)
+}
+
+// A lost game sends its vault to the casino and closes the player pool to the operator
+fn lose_round<'info>(
+    player_pool: &mut Account<'info, PlayerPool>,
+    accounts: &SettleAccounts<'info>,
+) -> Result<()> {
+    player_pool.status = GameStatus::Lose;
+
+    sol_transfer_with_signer(
+        accounts.game_vault.clone(),
+        accounts.casino_vault.clone(),
+        accounts.system_program.clone(),
+        &[&[
+            accounts.owner.key().as_ref(),
+            VAULT_AUTHORITY_SEED.as_bytes(),
+            &player_pool.game_session_id.to_be_bytes()[..],
+            &[accounts.game_bump],
+        ]],
+        accounts.game_vault.lamports(),
+    )?;
+
+    close_program_account(&player_pool.to_account_info(), &accounts.operator)
+}
+
+// Move lamports out of an account this program owns, no signature needed
+pub fn debit_program_account(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    let from_balance = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(GameError::MathOverflow)?;
+    let to_balance = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(GameError::MathOverflow)?;
+    **from.try_borrow_mut_lamports()? = from_balance;
+    **to.try_borrow_mut_lamports()? = to_balance;
+    Ok(())
+}
+
+// Lamports an account this program owns holds above its rent exemption
+pub fn balance_above_rent(account: &AccountInfo) -> Result<u64> {
+    let rent = Rent::get()?.minimum_balance(account.data_len());
+    Ok(account.lamports().saturating_sub(rent))
+}
+
+// Count the jackpot's slice of a bet already moved out of its stake. Coin
+// flips the player staked can go on to win the jackpot
+pub fn record_jackpot_contribution(
+    jackpot: &mut Account<Jackpot>,
+    player_pool: &mut PlayerPool,
+    amount: u64,
+) -> Result<()> {
+    player_pool.jackpot_eligible =
+        player_pool.dice_target == 0 && player_pool.coin_count == 0 && !player_pool.is_batch();
+
+    if amount == 0 {
+        return Ok(());
+    }
+    jackpot.total_contributed = jackpot
+        .total_contributed
+        .checked_add(amount)
+        .ok_or(GameError::MathOverflow)?;
+
+    emit!(JackpotContributed {
+        player: player_pool.player,
+        game_session_id: player_pool.game_session_id,
+        amount,
+        balance: balance_above_rent(&jackpot.to_account_info())?,
+    });
+    Ok(())
+}
+
+// Pay the whole jackpot to `recipient` when claiming `player_pool` wins it
+pub fn pay_jackpot(
+    jackpot: &mut Account<Jackpot>,
+    player_pool: &PlayerPool,
+    recipient: &AccountInfo,
+) -> Result<()> {
+    if !jackpot.is_won_by(player_pool) {
+        return Ok(());
+    }
+    let jackpot_info = jackpot.to_account_info();
+    let amount = balance_above_rent(&jackpot_info)?;
+    if amount == 0 {
+        return Ok(());
+    }
+
+    msg!(
+        "Jackpot of {} won by {} on a streak of {}",
+        amount,
+        player_pool.player,
+        player_pool.round
+    );
+
+    debit_program_account(&jackpot_info, recipient, amount)?;
+    jackpot.total_paid = jackpot
+        .total_paid
+        .checked_add(amount)
+        .ok_or(GameError::MathOverflow)?;
+    jackpot.last_winner = player_pool.player;
+
+    emit!(JackpotWon {
+        player: player_pool.player,
+        game_session_id: player_pool.game_session_id,
+        streak: player_pool.round,
+        amount,
+    });
+    Ok(())
+}
+
+// The tournament and entry a game instruction was given, which must come
+// together and belong to `player`
+fn tournament_accounts<'a, 'info>(
+    tournament: Option<&'a Account<'info, Tournament>>,
+    entry: Option<&'a mut Account<'info, TournamentEntry>>,
+    player: &Pubkey,
+) -> Result<
+    Option<(
+        &'a Account<'info, Tournament>,
+        &'a mut Account<'info, TournamentEntry>,
+    )>,
+> {
+    match (tournament, entry) {
+        (None, None) => Ok(None),
+        (Some(tournament), Some(entry)) => {
+            require!(
+                entry.tournament == tournament.key() && entry.player == *player,
+                GameError::InvalidTournamentEntry
+            );
+            Ok(Some((tournament, entry)))
+        }
+        _ => err!(GameError::InvalidTournamentEntry),
+    }
+}
+
+// Score a stake for the tournament entry it was given, and tie the game to
+// that tournament. Only the stake that brings a game into the tournament costs
+// the entry, a double bet stakes winnings it never scored
+pub fn record_tournament_bet<'info>(
+    tournament: Option<&Account<'info, Tournament>>,
+    entry: Option<&mut Account<'info, TournamentEntry>>,
+    player_pool: &mut PlayerPool,
+    stake: u64,
+) -> Result<()> {
+    let Some((tournament, entry)) = tournament_accounts(tournament, entry, &player_pool.player)?
+    else {
+        return Ok(());
+    };
+    require!(
+        tournament.is_live(Clock::get()?.unix_timestamp),
+        GameError::TournamentNotLive
+    );
+
+    entry.volume = entry
+        .volume
+        .checked_add(stake)
+        .ok_or(GameError::MathOverflow)?;
+    if player_pool.tournament == Pubkey::default() {
+        entry.net_profit = i64::try_from(stake)
+            .ok()
+            .and_then(|stake| entry.net_profit.checked_sub(stake))
+            .ok_or(GameError::MathOverflow)?;
+        player_pool.tournament = tournament.key();
+    }
+    require!(
+        player_pool.tournament == tournament.key(),
+        GameError::InvalidTournamentEntry
+    );
+    Ok(())
+}
+
+// Score a won game claimed for `payout` in the tournament it was played in,
+// which every claim of such a game has to pass along. Claims after the
+// tournament has ended no longer count
+pub fn record_tournament_win<'info>(
+    tournament: Option<&Account<'info, Tournament>>,
+    entry: Option<&mut Account<'info, TournamentEntry>>,
+    player_pool: &PlayerPool,
+    payout: u64,
+) -> Result<()> {
+    let Some((tournament, entry)) = tournament_accounts(tournament, entry, &player_pool.player)?
+    else {
+        require!(
+            player_pool.tournament == Pubkey::default(),
+            GameError::TournamentEntryRequired
+        );
+        return Ok(());
+    };
+    require!(
+        player_pool.tournament == tournament.key(),
+        GameError::InvalidTournamentEntry
+    );
+    if !tournament.is_live(Clock::get()?.unix_timestamp) {
+        return Ok(());
+    }
+
+    entry.net_profit = i64::try_from(payout)
+        .ok()
+        .and_then(|payout| entry.net_profit.checked_add(payout))
+        .ok_or(GameError::MathOverflow)?;
+    entry.best_streak = entry.best_streak.max(player_pool.round);
+    Ok(())
+}
+
+// Run `update` on each leaderboard passed along that ranks the current period.
+// Boards of a past period are skipped, so a stale board never fails a game.
+// Accounts of other programs, such as a randomness provider's, are passed over
+fn update_leaderboards<'info>(
+    leaderboards: &'info [AccountInfo<'info>],
+    mut update: impl FnMut(&mut Leaderboard),
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    for info in leaderboards.iter().filter(|info| info.owner == &crate::ID) {
+        let mut leaderboard = Account::<Leaderboard>::try_from(info)?;
+        if !leaderboard.is_current(now) {
+            continue;
+        }
+        update(&mut leaderboard);
+        leaderboard.exit(&crate::ID)?;
+    }
+    Ok(())
+}
+
+// Add a stake to the wagered totals at `player_stats` and accrue the rakeback
+// of the VIP tier they reach. Until the player opens their stats nothing is
+// counted, and until the tiers are set no rakeback accrues
+pub fn record_wager(
+    player_stats: &AccountInfo,
+    vip_config: &AccountInfo,
+    stake: u64,
+    rtp: u64,
+) -> Result<()> {
+    if player_stats.owner != &crate::ID {
+        return Ok(());
+    }
+    let mut stats = PlayerStats::try_deserialize(&mut &player_stats.try_borrow_data()?[..])?;
+    stats.record(stake, Clock::get()?.unix_timestamp)?;
+
+    if vip_config.owner == &crate::ID {
+        let vip_config = VipConfig::try_deserialize(&mut &vip_config.try_borrow_data()?[..])?;
+        let (vip_tier, rate) = vip_config.tier(stats.total_volume);
+        let rakeback = rakeback_amount(stake, rtp, rate).ok_or(GameError::MathOverflow)?;
+        stats.vip_tier = vip_tier;
+        stats.rakeback = stats
+            .rakeback
+            .checked_add(rakeback)
+            .ok_or(GameError::MathOverflow)?;
+    }
+    stats.try_serialize(&mut &mut player_stats.try_borrow_mut_data()?[..])
+}
+
+// Rank the wagered totals at `player_stats` on the leaderboards. Until the
+// player opens their stats the leaderboards are left as they are
+pub fn record_leaderboard_volume<'info>(
+    leaderboards: &'info [AccountInfo<'info>],
+    player_stats: &AccountInfo,
+) -> Result<()> {
+    if player_stats.owner != &crate::ID {
+        return Ok(());
+    }
+    let stats = PlayerStats::try_deserialize(&mut &player_stats.try_borrow_data()?[..])?;
+    let now = Clock::get()?.unix_timestamp;
+    update_leaderboards(leaderboards, |leaderboard| {
+        leaderboard.record_volume(stats.player, stats.volume(leaderboard.period, now))
+    })
+}
+
+// Rank a claimed payout on the leaderboards' biggest wins
+pub fn record_leaderboard_win<'info>(
+    leaderboards: &'info [AccountInfo<'info>],
+    player: Pubkey,
+    payout: u64,
+) -> Result<()> {
+    update_leaderboards(leaderboards, |leaderboard| {
+        leaderboard.record_win(player, payout)
+    })
+}
+
+// Run `update` on the player limits at `info` once the player has opened them.
+// The address is fixed by the instruction's seeds, so limits cannot be skipped
+fn update_player_limits(
+    info: &AccountInfo,
+    update: impl FnOnce(&mut PlayerLimits, i64) -> Result<()>,
+) -> Result<()> {
+    if info.owner != &crate::ID {
+        return Ok(());
+    }
+    let mut limits = PlayerLimits::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    update(&mut limits, Clock::get()?.unix_timestamp)?;
+    limits.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+}
+
+// Check a bet of `stake` risking `loss` of the player's own funds against
+// their limits and count it
+pub fn enforce_player_limits(info: &AccountInfo, stake: u64, loss: u64) -> Result<()> {
+    update_player_limits(info, |limits, now| limits.place_bet(stake, loss, now))
+}
+
+// Credit a claimed payout back against the player's losses
+pub fn record_limits_payout(info: &AccountInfo, payout: u64) -> Result<()> {
+    update_player_limits(info, |limits, now| {
+        limits.record_payout(payout, now);
+        Ok(())
+    })
+}
+
+// Check the wallet whose access entry is at `info` against the access mode.
+// The address is fixed by the instruction's seeds, so a listing cannot be
+// hidden. An entry this program never opened is missing, which the allowlist
+// rejects and the denylist lets through
+pub fn check_player_access(global_pool: &GlobalPool, info: &AccountInfo) -> Result<()> {
+    if info.owner != &crate::ID {
+        return global_pool.check_access(None);
+    }
+    let entry = AccessEntry::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    global_pool.check_access(Some(&entry))
+}
+
+// Check the player whose attestation is at `info` when the table requires one.
+// An attestation this program never issued is missing, so it fails the check
+pub fn check_player_attestation(global_pool: &GlobalPool, info: &AccountInfo) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    if info.owner != &crate::ID {
+        return global_pool.check_attestation(None, now);
+    }
+    let attestation = Attestation::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    global_pool.check_attestation(Some(&attestation), now)
+}
+
+// Refuse a single signer withdrawal once the config at `info` sets approvers
+pub fn require_no_withdrawal_approvers(info: &AccountInfo) -> Result<()> {
+    if info.owner != &crate::ID {
+        return Ok(());
+    }
+    let config = WithdrawalConfig::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    require!(config.threshold == 0, GameError::WithdrawalApprovalRequired);
+    Ok(())
+}
+
+// Leaf of a wallet in the allowlist Merkle tree
+pub fn access_merkle_leaf(wallet: &Pubkey) -> [u8; 32] {
+    hashv(&[wallet.as_ref()]).to_bytes()
+}
+
+// Whether `proof` leads from `leaf` to `root`, each pair hashed in sorted order
+pub fn verify_merkle_proof(root: &[u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    let node = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            hashv(&[&node, sibling]).to_bytes()
+        } else {
+            hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    node == *root
+}
+
+// Close an account this program owns, sending whatever it holds to `destination`
+pub fn close_program_account(account: &AccountInfo, destination: &AccountInfo) -> Result<()> {
+    debit_program_account(account, destination, account.lamports())?;
+    // Hand the account back to the system program empty so it can't be revived
+    account.realloc(0, false)?;
+    account.assign(&System::id());
+    Ok(())
+}
+
+// Settle the round in progress from the randomness it was drawn with
+pub fn settle_with_randomness<'info>(
+    player_pool: &mut Account<'info, PlayerPool>,
+    accounts: &SettleAccounts<'info>,
+    round_id: u8,
+    randomness: &[u8; 32],
+    rtp: u64,
+) -> Result<()> {
+    if player_pool.is_batch() {
+        let outcomes = batch_outcomes_from_randomness(randomness, player_pool);
+        return settle_batch(player_pool, accounts, round_id, outcomes, rtp);
+    }
+
+    let is_win = is_win_from_randomness(randomness, player_pool);
+    msg!("Win: {}", is_win);
+    record_coin_faces(randomness, player_pool);
+    settle_round(player_pool, accounts, round_id, is_win, rtp)
+}
+
+// Settle every flip of a batch at once. The game vault ends up holding what
+// the won flips pay, the rest goes to the casino
+pub fn settle_batch<'info>(
+    player_pool: &mut Account<'info, PlayerPool>,
+    accounts: &SettleAccounts<'info>,
+    round_id: u8,
+    outcomes: u64,
+    rtp: u64,
+) -> Result<()> {
+    require!(round_id == player_pool.round, GameError::RoundNumMismatch);
+    require!(
+        player_pool.status == GameStatus::Processing,
+        GameError::NotAllowedStatus
+    );
+
+    record_round_wager(player_pool, accounts, rtp)?;
+    let vault_balance = accounts.game_vault.lamports();
+    let payout = batch_win_amount(&player_pool.batch_amounts, outcomes, rtp)
+        .ok_or(GameError::MathOverflow)?;
+    player_pool.batch_outcomes = outcomes;
+    msg!(
+        "Batch outcomes {:#b}, payout is {}SOL",
+        outcomes,
+        payout as f64 / LAMPORTS_PER_SOL as f64
+    );
+
+    if payout == 0 {
+        return lose_round(player_pool, accounts);
+    }
+
+    match payout.cmp(&vault_balance) {
+        Ordering::Greater => sol_transfer_with_signer(
+            accounts.casino_vault.clone(),
+            accounts.game_vault.clone(),
+            accounts.system_program.clone(),
+            &[&[VAULT_AUTHORITY_SEED.as_bytes(), &[accounts.casino_bump]]],
+            payout - vault_balance,
+        )?,
+        Ordering::Less => sol_transfer_with_signer(
+            accounts.game_vault.clone(),
+            accounts.casino_vault.clone(),
+            accounts.system_program.clone(),
+            &[&[
+                accounts.owner.key().as_ref(),
+                VAULT_AUTHORITY_SEED.as_bytes(),
+                &player_pool.game_session_id.to_be_bytes()[..],
+                &[accounts.game_bump],
+            ]],
+            vault_balance - payout,
+        )?,
+        Ordering::Equal => {}
+    }
+
+    player_pool.status = GameStatus::Win;
+    Ok(())
+}
+
 pub fn resize_account<'info>(
     account_info: AccountInfo<'info>,
     new_space: usize,
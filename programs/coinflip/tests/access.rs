@@ -0,0 +1,215 @@
+mod common;
+
+use coinflip::{
+    constants::{ACCESS_ALLOWLIST, ACCESS_DENYLIST, ACCESS_OPEN, SESSION_ALLOW_PLAY},
+    error::GameError,
+    utils::access_merkle_leaf,
+};
+use common::*;
+use solana_sdk::{hash::hashv, native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer};
+
+const BET: u64 = LAMPORTS_PER_SOL / 2;
+
+/// Root of the allowlist tree over `wallets` and the proof of each, pairs hashed
+/// in sorted order and an odd node carried up as is.
+fn merkle_tree(wallets: &[&Keypair]) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+    let mut level: Vec<[u8; 32]> = wallets
+        .iter()
+        .map(|wallet| access_merkle_leaf(&wallet.pubkey()))
+        .collect();
+    let mut positions: Vec<usize> = (0..wallets.len()).collect();
+    let mut proofs = vec![vec![]; wallets.len()];
+
+    while level.len() > 1 {
+        for (proof, position) in proofs.iter_mut().zip(positions.iter_mut()) {
+            if let Some(sibling) = level.get(*position ^ 1) {
+                proof.push(*sibling);
+            }
+            *position /= 2;
+        }
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] if a <= b => hashv(&[a, b]).to_bytes(),
+                [a, b] => hashv(&[b, a]).to_bytes(),
+                [a] => *a,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    (level[0], proofs)
+}
+
+#[test]
+fn allowlist_mode_only_lets_allowed_wallets_play() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.set_access_mode(ACCESS_ALLOWLIST).unwrap();
+
+    assert_game_error(
+        casino.play_game(&player, true, BET, 1),
+        GameError::PlayerNotAllowed,
+    );
+
+    casino.set_access_entry(&player.pubkey(), true).unwrap();
+    casino.play_game(&player, true, BET, 1).unwrap();
+
+    casino.set_access_entry(&player.pubkey(), false).unwrap();
+    assert_game_error(
+        casino.play_game(&player, true, BET, 2),
+        GameError::PlayerNotAllowed,
+    );
+}
+
+#[test]
+fn allowlist_mode_covers_every_game() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    let key = Keypair::new();
+    casino.deposit_balance(&player, 2 * BET).unwrap();
+    casino
+        .create_session_key(&player, &key.pubkey(), 2 * BET, SESSION_ALLOW_PLAY)
+        .unwrap();
+    casino.set_access_mode(ACCESS_ALLOWLIST).unwrap();
+    // Lamports sent to the entry's address don't make it an entry
+    casino.airdrop(&access_entry(&player.pubkey()), LAMPORTS_PER_SOL);
+
+    assert_game_error(
+        casino.play_dice(&player, 50, BET, 1),
+        GameError::PlayerNotAllowed,
+    );
+    assert_game_error(
+        casino.play_multi_coin(&player, 3, 2, false, BET, 1),
+        GameError::PlayerNotAllowed,
+    );
+    assert_game_error(
+        casino.play_game_from_balance(&player, true, BET, 1),
+        GameError::PlayerNotAllowed,
+    );
+    assert_game_error(
+        casino.send(
+            &[play_game_with_session_key_ix(
+                &key.pubkey(),
+                &player.pubkey(),
+                &casino.operator.pubkey(),
+                true,
+                BET,
+                1,
+            )],
+            &[&key, &casino.operator],
+        ),
+        GameError::PlayerNotAllowed,
+    );
+
+    casino.set_access_mode(ACCESS_DENYLIST).unwrap();
+    casino.set_access_entry(&player.pubkey(), false).unwrap();
+    assert_game_error(
+        casino.play_batch(&player, vec![true, false], vec![BET, BET], 1),
+        GameError::PlayerDenied,
+    );
+}
+
+#[test]
+fn denylist_mode_blocks_denied_wallets() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.set_access_mode(ACCESS_DENYLIST).unwrap();
+    casino.play_game(&player, true, BET, 1).unwrap();
+
+    casino.set_access_entry(&player.pubkey(), false).unwrap();
+    let entry = casino.access_entry(&player.pubkey()).unwrap();
+    assert_eq!((entry.wallet, entry.allowed), (player.pubkey(), false));
+    assert_game_error(
+        casino.play_game(&player, true, BET, 2),
+        GameError::PlayerDenied,
+    );
+
+    casino.remove_access_entry(&player.pubkey()).unwrap();
+    assert!(casino.access_entry(&player.pubkey()).is_none());
+    casino.play_game(&player, true, BET, 2).unwrap();
+}
+
+#[test]
+fn merkle_allowlist_lets_wallets_claim_their_own_entry() {
+    let casino = Casino::new();
+    let players: Vec<Keypair> = (0..3)
+        .map(|_| casino.new_player(10 * LAMPORTS_PER_SOL))
+        .collect();
+    let (root, proofs) = merkle_tree(&players.iter().collect::<Vec<_>>());
+    casino.set_access_mode(ACCESS_ALLOWLIST).unwrap();
+
+    assert_game_error(
+        casino.claim_allowlist_access(&players[0], proofs[0].clone()),
+        GameError::InvalidMerkleProof,
+    );
+    casino.set_access_merkle_root(root).unwrap();
+
+    assert_game_error(
+        casino.claim_allowlist_access(&players[0], proofs[1].clone()),
+        GameError::InvalidMerkleProof,
+    );
+    casino
+        .claim_allowlist_access(&players[0], proofs[0].clone())
+        .unwrap();
+    casino.play_game(&players[0], true, BET, 1).unwrap();
+
+    let stranger = casino.new_player(10 * LAMPORTS_PER_SOL);
+    assert_game_error(
+        casino.claim_allowlist_access(&stranger, proofs[2].clone()),
+        GameError::InvalidMerkleProof,
+    );
+
+    // A wallet denied by the operation authority cannot claim over its entry
+    casino
+        .set_access_entry(&players[2].pubkey(), false)
+        .unwrap();
+    assert!(casino
+        .claim_allowlist_access(&players[2], proofs[2].clone())
+        .is_err());
+    assert_game_error(
+        casino.play_game(&players[2], true, BET, 1),
+        GameError::PlayerNotAllowed,
+    );
+}
+
+#[test]
+fn only_the_authorities_manage_access() {
+    let casino = Casino::new();
+    assert_eq!(casino.global_pool().access_mode, ACCESS_OPEN);
+    let stranger = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    assert_game_error(
+        casino.send(
+            &[set_access_entry_ix(
+                &stranger.pubkey(),
+                &stranger.pubkey(),
+                true,
+            )],
+            &[&stranger],
+        ),
+        GameError::UnauthorizedOperator,
+    );
+    assert_game_error(
+        casino.send(
+            &[set_access_merkle_root_ix(&stranger.pubkey(), [1; 32])],
+            &[&stranger],
+        ),
+        GameError::UnauthorizedOperator,
+    );
+    assert_game_error(
+        casino.send(
+            &[set_global_pool_ix(
+                &stranger.pubkey(),
+                coinflip::instruction::SetAccessMode {
+                    mode: ACCESS_ALLOWLIST,
+                },
+            )],
+            &[&stranger],
+        ),
+        GameError::UnauthorizedUpdateAdmin,
+    );
+    assert_game_error(
+        casino.set_access_mode(ACCESS_DENYLIST + 1),
+        GameError::InvalidAccessMode,
+    );
+}
@@ -0,0 +1,268 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use coinflip::{
+    constants::{MAX_WIN_AMOUNT, MIN_BET_AMOUNT, RTP},
+    error::GameError,
+    instruction,
+};
+use common::*;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signer::Signer};
+
+#[test]
+fn initialize_sets_authorities_and_defaults() {
+    let casino = Casino::new();
+    let global = casino.global_pool();
+
+    assert_eq!(global.super_admin, casino.super_admin.pubkey());
+    assert_eq!(global.operation_authority, casino.operator.pubkey());
+    assert_eq!(global.finance_authority, casino.finance.pubkey());
+    assert_eq!(global.update_authority, casino.update.pubkey());
+    assert_eq!(global.rtp, RTP);
+    assert_eq!(global.max_win_amount, MAX_WIN_AMOUNT);
+    assert_eq!(global.min_bet_amount, MIN_BET_AMOUNT);
+}
+
+#[test]
+fn initialize_cannot_run_twice() {
+    let casino = Casino::new();
+    let attacker = casino.new_player(LAMPORTS_PER_SOL);
+
+    let result = casino.send(
+        &[initialize_ix(
+            &attacker.pubkey(),
+            &attacker.pubkey(),
+            &attacker.pubkey(),
+            &attacker.pubkey(),
+        )],
+        &[&attacker],
+    );
+    assert!(result.is_err());
+    assert_eq!(
+        casino.global_pool().super_admin,
+        casino.super_admin.pubkey()
+    );
+}
+
+#[test]
+fn withdraw_pays_recipient_from_casino_vault() {
+    let casino = Casino::new();
+    let recipient = Pubkey::new_unique();
+    let amount = 3 * LAMPORTS_PER_SOL;
+
+    casino
+        .send(
+            &[withdraw_ix(&casino.finance.pubkey(), &recipient, amount)],
+            &[&casino.finance],
+        )
+        .unwrap();
+
+    assert_eq!(casino.balance(&recipient), amount);
+    assert_eq!(casino.balance(&casino_vault()), CASINO_FUNDS - amount);
+}
+
+#[test]
+fn withdraw_rejects_more_than_the_vault_holds() {
+    let casino = Casino::new();
+
+    let result = casino.send(
+        &[withdraw_ix(
+            &casino.finance.pubkey(),
+            &casino.finance.pubkey(),
+            CASINO_FUNDS,
+        )],
+        &[&casino.finance],
+    );
+    assert_game_error(result, GameError::InsufficientCasinoVault);
+}
+
+#[test]
+fn withdraw_requires_finance_authority() {
+    let casino = Casino::new();
+    let attacker = casino.new_player(LAMPORTS_PER_SOL);
+
+    let result = casino.send(
+        &[withdraw_ix(
+            &attacker.pubkey(),
+            &attacker.pubkey(),
+            LAMPORTS_PER_SOL,
+        )],
+        &[&attacker],
+    );
+    assert_game_error(result, GameError::UnauthorizedFinanceAdmin);
+    assert_eq!(casino.balance(&casino_vault()), CASINO_FUNDS);
+}
+
+#[test]
+fn update_authority_sets_table_limits() {
+    let casino = Casino::new();
+
+    casino
+        .send(
+            &[
+                set_global_pool_ix(&casino.update.pubkey(), instruction::SetRtp { new_rtp: 90 }),
+                set_global_pool_ix(
+                    &casino.update.pubkey(),
+                    instruction::SetMaxWinAmount {
+                        new_max_win_amount: 5 * LAMPORTS_PER_SOL,
+                    },
+                ),
+                set_global_pool_ix(
+                    &casino.update.pubkey(),
+                    instruction::SetMinBetAmount {
+                        new_min_bet_amount: LAMPORTS_PER_SOL,
+                    },
+                ),
+            ],
+            &[&casino.update],
+        )
+        .unwrap();
+
+    let global = casino.global_pool();
+    assert_eq!(global.rtp, 90);
+    assert_eq!(global.max_win_amount, 5 * LAMPORTS_PER_SOL);
+    assert_eq!(global.min_bet_amount, LAMPORTS_PER_SOL);
+}
+
+#[test]
+fn set_rtp_rejects_invalid_rtp() {
+    let casino = Casino::new();
+
+    let result = casino.send(
+        &[set_global_pool_ix(
+            &casino.update.pubkey(),
+            instruction::SetRtp { new_rtp: 100 },
+        )],
+        &[&casino.update],
+    );
+    assert_game_error(result, GameError::InvalidRtp);
+}
+
+#[test]
+fn table_limits_require_update_authority() {
+    let casino = Casino::new();
+
+    let calls = [
+        set_global_pool_ix(
+            &casino.operator.pubkey(),
+            instruction::SetRtp { new_rtp: 50 },
+        ),
+        set_global_pool_ix(
+            &casino.operator.pubkey(),
+            instruction::SetMaxWinAmount {
+                new_max_win_amount: u64::MAX,
+            },
+        ),
+        set_global_pool_ix(
+            &casino.operator.pubkey(),
+            instruction::SetMinBetAmount {
+                new_min_bet_amount: 1,
+            },
+        ),
+    ];
+
+    for ix in calls {
+        let result = casino.send(&[ix], &[&casino.operator]);
+        assert_game_error(result, GameError::UnauthorizedUpdateAdmin);
+    }
+}
+
+#[test]
+fn super_admin_rotates_authorities() {
+    let casino = Casino::new();
+    let operation = Pubkey::new_unique();
+    let finance = Pubkey::new_unique();
+    let update = Pubkey::new_unique();
+
+    casino
+        .send(
+            &[
+                set_authority_ix(
+                    &casino.super_admin.pubkey(),
+                    instruction::SetOperationAuthority {
+                        new_operation_authority: operation,
+                    },
+                ),
+                set_authority_ix(
+                    &casino.super_admin.pubkey(),
+                    instruction::SetFinanceAuthority {
+                        new_finance_authority: finance,
+                    },
+                ),
+                set_authority_ix(
+                    &casino.super_admin.pubkey(),
+                    instruction::SetUpdateAuthority {
+                        new_update_authority: update,
+                    },
+                ),
+            ],
+            &[&casino.super_admin],
+        )
+        .unwrap();
+
+    let global = casino.global_pool();
+    assert_eq!(global.operation_authority, operation);
+    assert_eq!(global.finance_authority, finance);
+    assert_eq!(global.update_authority, update);
+}
+
+#[test]
+fn authorities_require_super_admin() {
+    let casino = Casino::new();
+    let attacker = casino.update.pubkey();
+
+    let calls = [
+        set_authority_ix(
+            &attacker,
+            instruction::SetOperationAuthority {
+                new_operation_authority: attacker,
+            },
+        ),
+        set_authority_ix(
+            &attacker,
+            instruction::SetFinanceAuthority {
+                new_finance_authority: attacker,
+            },
+        ),
+        set_authority_ix(
+            &attacker,
+            instruction::SetUpdateAuthority {
+                new_update_authority: attacker,
+            },
+        ),
+    ];
+
+    for ix in calls {
+        let result = casino.send(&[ix], &[&casino.update]);
+        assert_anchor_error(result, ErrorCode::ConstraintAddress);
+    }
+}
+
+#[test]
+fn super_admin_grows_global_pool_from_an_older_layout() {
+    let casino = Casino::new();
+    // The layout before the settlement nonce was added
+    casino.truncate_account(&global_authority(), 8 + 152);
+
+    casino
+        .send(
+            &[resize_global_pool_ix(&casino.super_admin.pubkey())],
+            &[&casino.super_admin],
+        )
+        .unwrap();
+
+    let global = casino.global_pool();
+    assert_eq!(global.super_admin, casino.super_admin.pubkey());
+    assert_eq!(global.game_nonce, 0);
+}
+
+#[test]
+fn resize_global_pool_requires_super_admin() {
+    let casino = Casino::new();
+
+    let result = casino.send(
+        &[resize_global_pool_ix(&casino.update.pubkey())],
+        &[&casino.update],
+    );
+    assert_game_error(result, GameError::UnauthorizedSuperAdmin);
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use coinflip::{
+    constants::{DAY, SESSION_ALLOW_PLAY},
+    error::GameError,
+};
+use common::*;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer};
+
+const BET: u64 = LAMPORTS_PER_SOL / 2;
+
+/// A casino requiring attestations, its compliance key and an unverified player.
+fn verified_table() -> (Casino, Keypair, Keypair) {
+    let casino = Casino::new();
+    let compliance = casino.new_compliance_authority();
+    casino.set_attestation_required(true).unwrap();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    (casino, compliance, player)
+}
+
+#[test]
+fn tables_requiring_attestations_turn_unverified_players_away() {
+    let (casino, compliance, player) = verified_table();
+    assert_game_error(
+        casino.play_game(&player, true, BET, 1),
+        GameError::AttestationRequired,
+    );
+
+    let expires_at = casino.now() + DAY;
+    casino
+        .issue_attestation(&compliance, &player.pubkey(), expires_at)
+        .unwrap();
+    casino.play_game(&player, true, BET, 1).unwrap();
+
+    let attestation = casino.attestation(&player.pubkey());
+    assert_eq!(attestation.player, player.pubkey());
+    assert!(attestation.verified);
+    assert_eq!(
+        (attestation.expires_at, attestation.issued_at),
+        (expires_at, casino.now())
+    );
+
+    casino.set_attestation_required(false).unwrap();
+    let unverified = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&unverified, true, BET, 1).unwrap();
+}
+
+#[test]
+fn expired_attestations_no_longer_let_players_in() {
+    let (casino, compliance, player) = verified_table();
+    casino
+        .issue_attestation(&compliance, &player.pubkey(), casino.now() + DAY)
+        .unwrap();
+
+    casino.advance_clock(DAY);
+    assert_game_error(
+        casino.play_game(&player, true, BET, 1),
+        GameError::AttestationRequired,
+    );
+
+    assert_game_error(
+        casino.issue_attestation(&compliance, &player.pubkey(), casino.now()),
+        GameError::InvalidAttestationExpiry,
+    );
+    casino
+        .issue_attestation(&compliance, &player.pubkey(), casino.now() + DAY)
+        .unwrap();
+    casino.play_game(&player, true, BET, 1).unwrap();
+}
+
+#[test]
+fn revoked_attestations_no_longer_let_players_in() {
+    let (casino, compliance, player) = verified_table();
+    casino
+        .issue_attestation(&compliance, &player.pubkey(), casino.now() + DAY)
+        .unwrap();
+    casino.play_game(&player, true, BET, 1).unwrap();
+
+    casino
+        .revoke_attestation(&compliance, &player.pubkey())
+        .unwrap();
+
+    assert!(!casino.attestation(&player.pubkey()).verified);
+    assert_game_error(
+        casino.play_game(&player, true, BET, 2),
+        GameError::AttestationRequired,
+    );
+}
+
+#[test]
+fn only_the_compliance_authority_attests() {
+    let (casino, compliance, player) = verified_table();
+    casino
+        .issue_attestation(&compliance, &player.pubkey(), casino.now() + DAY)
+        .unwrap();
+
+    for signer in [&casino.operator, &player] {
+        assert_game_error(
+            casino.issue_attestation(signer, &player.pubkey(), casino.now() + DAY),
+            GameError::UnauthorizedComplianceAuthority,
+        );
+        assert_game_error(
+            casino.revoke_attestation(signer, &player.pubkey()),
+            GameError::UnauthorizedComplianceAuthority,
+        );
+    }
+}
+
+#[test]
+fn every_game_requires_an_attestation() {
+    let (casino, _compliance, player) = verified_table();
+    let key = Keypair::new();
+    casino.deposit_balance(&player, 2 * BET).unwrap();
+    casino
+        .create_session_key(&player, &key.pubkey(), 2 * BET, SESSION_ALLOW_PLAY)
+        .unwrap();
+    // Lamports sent to the attestation's address don't make it an attestation
+    casino.airdrop(&attestation(&player.pubkey()), LAMPORTS_PER_SOL);
+
+    assert_game_error(
+        casino.play_game(&player, true, BET, 1),
+        GameError::AttestationRequired,
+    );
+    assert_game_error(
+        casino.play_dice(&player, 50, BET, 1),
+        GameError::AttestationRequired,
+    );
+    assert_game_error(
+        casino.play_multi_coin(&player, 3, 2, false, BET, 1),
+        GameError::AttestationRequired,
+    );
+    assert_game_error(
+        casino.play_batch(&player, vec![true, false], vec![BET, BET], 1),
+        GameError::AttestationRequired,
+    );
+    assert_game_error(
+        casino.play_game_from_balance(&player, true, BET, 1),
+        GameError::AttestationRequired,
+    );
+    assert_game_error(
+        casino.send(
+            &[play_game_with_session_key_ix(
+                &key.pubkey(),
+                &player.pubkey(),
+                &casino.operator.pubkey(),
+                true,
+                BET,
+                1,
+            )],
+            &[&key, &casino.operator],
+        ),
+        GameError::AttestationRequired,
+    );
+}
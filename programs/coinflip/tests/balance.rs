@@ -0,0 +1,133 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use coinflip::{constants::RTP, error::GameError, math::win_amount};
+use common::*;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer};
+
+const DEPOSIT: u64 = 2 * LAMPORTS_PER_SOL;
+const BET: u64 = LAMPORTS_PER_SOL / 2;
+const SESSION: u64 = 1;
+
+fn funded_player() -> (Casino, Keypair) {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.deposit_balance(&player, DEPOSIT).unwrap();
+    (casino, player)
+}
+
+#[test]
+fn deposits_add_up() {
+    let (casino, player) = funded_player();
+    let wallet = casino.balance(&player.pubkey());
+
+    casino.deposit_balance(&player, DEPOSIT).unwrap();
+
+    assert_eq!(casino.player_balance(&player.pubkey()), 2 * DEPOSIT);
+    assert_eq!(casino.balance(&player.pubkey()), wallet - DEPOSIT);
+}
+
+#[test]
+fn withdraw_balance_returns_what_is_above_rent() {
+    let (casino, player) = funded_player();
+    let wallet = casino.balance(&player.pubkey());
+
+    assert_game_error(
+        casino.withdraw_balance(&player, DEPOSIT + 1),
+        GameError::InsufficientPlayerBalance,
+    );
+
+    casino.withdraw_balance(&player, DEPOSIT).unwrap();
+    assert_eq!(casino.player_balance(&player.pubkey()), 0);
+    assert_eq!(casino.balance(&player.pubkey()), wallet + DEPOSIT);
+}
+
+#[test]
+fn bets_come_from_the_balance() {
+    let (casino, player) = funded_player();
+    let wallet = casino.balance(&player.pubkey());
+
+    casino
+        .play_game_from_balance(&player, true, BET, SESSION)
+        .unwrap();
+
+    assert_eq!(casino.player_balance(&player.pubkey()), DEPOSIT - BET);
+    assert_eq!(casino.balance(&game_vault(&player.pubkey(), SESSION)), BET);
+    assert_eq!(casino.balance(&player.pubkey()), wallet);
+}
+
+#[test]
+fn bet_above_the_balance_is_rejected() {
+    let (casino, player) = funded_player();
+
+    assert_game_error(
+        casino.play_game_from_balance(&player, true, DEPOSIT + 1, SESSION),
+        GameError::InsufficientPlayerBalance,
+    );
+
+    let stranger = casino.new_player(10 * LAMPORTS_PER_SOL);
+    assert_anchor_error(
+        casino.play_game_from_balance(&stranger, true, BET, SESSION),
+        ErrorCode::AccountNotInitialized,
+    );
+}
+
+#[test]
+fn wins_are_credited_to_the_balance() {
+    let (casino, player) = funded_player();
+    casino
+        .play_game_from_balance(&player, true, BET, SESSION)
+        .unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+    let wallet = casino.balance(&player.pubkey());
+
+    casino
+        .claim_reward_to_balance(&player.pubkey(), SESSION)
+        .unwrap();
+
+    assert_eq!(
+        casino.player_balance(&player.pubkey()),
+        DEPOSIT - BET + win_amount(BET, RTP).unwrap()
+    );
+    assert_eq!(casino.balance(&player.pubkey()), wallet);
+    assert!(casino.player_pool(&player.pubkey(), SESSION).is_none());
+}
+
+#[test]
+fn wallet_games_can_be_claimed_to_the_balance() {
+    let (casino, player) = funded_player();
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+
+    assert_game_error(
+        casino.claim_reward_to_balance(&player.pubkey(), SESSION),
+        GameError::NotAllowedStatus,
+    );
+
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+    casino
+        .claim_reward_to_balance(&player.pubkey(), SESSION)
+        .unwrap();
+    assert_eq!(
+        casino.player_balance(&player.pubkey()),
+        DEPOSIT + win_amount(BET, RTP).unwrap()
+    );
+}
+
+#[test]
+fn lost_bets_go_to_the_casino() {
+    let (casino, player) = funded_player();
+    casino
+        .play_game_from_balance(&player, true, BET, SESSION)
+        .unwrap();
+
+    casino
+        .set_result(&player.pubkey(), 1, false, SESSION)
+        .unwrap();
+
+    assert_eq!(casino.player_balance(&player.pubkey()), DEPOSIT - BET);
+    assert_eq!(casino.balance(&casino_vault()), CASINO_FUNDS + BET);
+}
@@ -0,0 +1,229 @@
+mod common;
+
+use coinflip::{
+    account::GameStatus,
+    constants::{MAX_BATCH_FLIPS, RTP},
+    error::GameError,
+    math::win_amount,
+};
+use common::*;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signer::Signer};
+
+const SESSION: u64 = 1;
+const AMOUNTS: [u64; 3] = [
+    LAMPORTS_PER_SOL / 10,
+    LAMPORTS_PER_SOL / 5,
+    3 * LAMPORTS_PER_SOL / 10,
+];
+const TOTAL: u64 = 6 * LAMPORTS_PER_SOL / 10;
+
+fn batch_casino() -> (Casino, solana_sdk::signature::Keypair) {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino
+        .play_batch(&player, vec![true, false, true], AMOUNTS.to_vec(), SESSION)
+        .unwrap();
+    (casino, player)
+}
+
+fn paid(outcomes: u64) -> u64 {
+    AMOUNTS
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| outcomes >> i & 1 == 1)
+        .map(|(_, amount)| win_amount(*amount, RTP).unwrap())
+        .sum()
+}
+
+#[test]
+fn play_batch_escrows_the_total() {
+    let (casino, player) = batch_casino();
+
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert!(pool.is_batch());
+    assert_eq!(pool.batch_choices, 0b101);
+    assert_eq!(pool.batch_amounts, AMOUNTS);
+    assert_eq!(pool.first_bet, TOTAL);
+    assert!(pool.status == GameStatus::Processing);
+    assert_eq!(
+        casino.balance(&game_vault(&player.pubkey(), SESSION)),
+        TOTAL
+    );
+}
+
+#[test]
+fn won_flips_are_topped_up_by_the_casino() {
+    let (casino, player) = batch_casino();
+
+    casino
+        .set_batch_result(&player.pubkey(), 0b101, SESSION)
+        .unwrap();
+
+    let payout = paid(0b101);
+    assert!(payout > TOTAL);
+    assert_eq!(
+        casino.balance(&game_vault(&player.pubkey(), SESSION)),
+        payout
+    );
+    assert_eq!(
+        casino.balance(&casino_vault()),
+        CASINO_FUNDS - (payout - TOTAL)
+    );
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert!(pool.status == GameStatus::Win);
+    assert_eq!(pool.batch_outcomes, 0b101);
+
+    let before = casino.balance(&player.pubkey());
+    casino.claim_reward(&player.pubkey(), SESSION).unwrap();
+    assert_eq!(casino.balance(&player.pubkey()), before + payout);
+}
+
+#[test]
+fn lost_flips_go_to_the_casino() {
+    let (casino, player) = batch_casino();
+
+    casino
+        .set_batch_result(&player.pubkey(), 0b001, SESSION)
+        .unwrap();
+
+    let payout = paid(0b001);
+    assert_eq!(
+        casino.balance(&game_vault(&player.pubkey(), SESSION)),
+        payout
+    );
+    assert_eq!(
+        casino.balance(&casino_vault()),
+        CASINO_FUNDS + TOTAL - payout
+    );
+}
+
+#[test]
+fn losing_every_flip_closes_the_game() {
+    let (casino, player) = batch_casino();
+
+    casino
+        .set_batch_result(&player.pubkey(), 0, SESSION)
+        .unwrap();
+
+    assert_eq!(casino.balance(&casino_vault()), CASINO_FUNDS + TOTAL);
+    assert_eq!(casino.balance(&player_pool(&player.pubkey(), SESSION)), 0);
+}
+
+#[test]
+fn outcome_bits_past_the_batch_are_ignored() {
+    let (casino, player) = batch_casino();
+
+    casino
+        .set_batch_result(&player.pubkey(), u64::MAX, SESSION)
+        .unwrap();
+
+    assert_eq!(
+        casino.balance(&game_vault(&player.pubkey(), SESSION)),
+        paid(0b111)
+    );
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert_eq!(pool.batch_outcomes, 0b111);
+}
+
+#[test]
+fn rejects_invalid_batches() {
+    let casino = Casino::new();
+    let player = casino.new_player(20 * LAMPORTS_PER_SOL);
+    let flips = MAX_BATCH_FLIPS + 1;
+
+    for (choices, amounts) in [
+        (vec![], vec![]),
+        (vec![true], AMOUNTS.to_vec()),
+        (vec![true; flips], vec![LAMPORTS_PER_SOL / 10; flips]),
+    ] {
+        assert_game_error(
+            casino.play_batch(&player, choices, amounts, SESSION),
+            GameError::InvalidBatch,
+        );
+    }
+
+    assert_game_error(
+        casino.play_batch(&player, vec![true, true], vec![TOTAL, 1], SESSION),
+        GameError::InvalidBetAmount,
+    );
+}
+
+#[test]
+fn max_win_covers_every_flip_winning() {
+    let casino = Casino::new();
+    let player = casino.new_player(20 * LAMPORTS_PER_SOL);
+    // Each flip is fine on its own, 15 SOL winning at once is not
+    let flips = MAX_BATCH_FLIPS;
+
+    assert_game_error(
+        casino.play_batch(
+            &player,
+            vec![true; flips],
+            vec![3 * LAMPORTS_PER_SOL / 10; flips],
+            SESSION,
+        ),
+        GameError::InvalidBetAmountMaxWinAmountViolation,
+    );
+}
+
+#[test]
+fn batch_is_settled_as_a_whole() {
+    let (casino, player) = batch_casino();
+
+    assert_game_error(
+        casino.set_result(&player.pubkey(), 1, true, SESSION),
+        GameError::SettledAsBatch,
+    );
+
+    casino
+        .set_batch_result(&player.pubkey(), 0b111, SESSION)
+        .unwrap();
+    assert_game_error(
+        casino.double_bet(&player, true, SESSION),
+        GameError::NotAllowedDoubleBet,
+    );
+
+    let single = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino
+        .play_game(&single, true, LAMPORTS_PER_SOL / 10, SESSION)
+        .unwrap();
+    assert_game_error(
+        casino.set_batch_result(&single.pubkey(), 0b1, SESSION),
+        GameError::NotBatchGame,
+    );
+}
+
+#[test]
+fn provider_randomness_settles_a_full_batch() {
+    let casino = Casino::with_mock_provider();
+    let player = casino.new_player(20 * LAMPORTS_PER_SOL);
+    let flips = MAX_BATCH_FLIPS;
+    let amount = LAMPORTS_PER_SOL / 10;
+    let nonce = casino.global_pool().game_nonce;
+
+    let mut ix = play_batch_ix(
+        &player.pubkey(),
+        &casino.operator.pubkey(),
+        vec![true; flips],
+        vec![amount; flips],
+        SESSION,
+    );
+    ix.accounts
+        .extend(provider_accounts(&player.pubkey(), SESSION, nonce));
+    casino.send(&[ix], &[&player, &casino.operator]).unwrap();
+
+    // Every flip bet on heads, so the flips won are the bits that are set
+    let mut randomness = [0; 32];
+    randomness[..8].copy_from_slice(&0x0f0f_0f0f_0f0f_0f0fu64.to_le_bytes());
+    casino
+        .fulfill(&player.pubkey(), SESSION, randomness)
+        .unwrap();
+
+    let outcomes = 0x0f0f_0f0f_0f0f_0f0f & ((1 << flips) - 1);
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert_eq!(pool.batch_outcomes, outcomes);
+    assert_eq!(
+        casino.balance(&game_vault(&player.pubkey(), SESSION)),
+        outcomes.count_ones() as u64 * win_amount(amount, RTP).unwrap()
+    );
+}
@@ -0,0 +1,211 @@
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use coinflip::{
+    constants::{MIN_BOND_WITHDRAWAL_DELAY, RTP, SETTLEMENT_TIMEOUT},
+    error::GameError,
+    instruction,
+    math::{casino_top_up, win_amount},
+};
+use common::*;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signer::Signer};
+
+const BET: u64 = LAMPORTS_PER_SOL / 5;
+const SESSION: u64 = 1;
+const BOND: u64 = 5 * LAMPORTS_PER_SOL;
+
+fn bonded_casino() -> Casino {
+    let casino = Casino::new();
+    casino.post_bond(BOND).unwrap();
+    casino
+}
+
+#[test]
+fn overdue_round_pays_full_win_from_bond() {
+    let casino = bonded_casino();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    let player_before = casino.balance(&player.pubkey());
+    let bond_before = casino.balance(&operator_bond());
+
+    casino.advance_clock(SETTLEMENT_TIMEOUT + 1);
+    casino.slash_operator(&player, SESSION).unwrap();
+
+    let top_up = casino_top_up(BET, RTP).unwrap();
+    assert_eq!(
+        casino.balance(&player.pubkey()),
+        player_before + win_amount(BET, RTP).unwrap()
+    );
+    assert_eq!(casino.balance(&operator_bond()), bond_before - top_up);
+    assert_eq!(casino.balance(&player_pool(&player.pubkey(), SESSION)), 0);
+
+    let bond = casino.operator_bond();
+    assert!(bond.slashed);
+    assert_eq!(bond.slash_count, 1);
+    assert_eq!(bond.total_slashed, top_up);
+}
+
+#[test]
+fn cannot_slash_before_deadline() {
+    let casino = bonded_casino();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+
+    casino.advance_clock(SETTLEMENT_TIMEOUT - 1);
+    assert_game_error(
+        casino.slash_operator(&player, SESSION),
+        GameError::SettlementNotOverdue,
+    );
+}
+
+#[test]
+fn settled_round_cannot_be_slashed() {
+    let casino = bonded_casino();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+
+    casino.advance_clock(SETTLEMENT_TIMEOUT + 1);
+    assert_game_error(
+        casino.slash_operator(&player, SESSION),
+        GameError::NotAllowedStatus,
+    );
+}
+
+#[test]
+fn double_bet_gets_a_fresh_deadline() {
+    let casino = bonded_casino();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+
+    casino.advance_clock(SETTLEMENT_TIMEOUT - 10);
+    casino.double_bet(&player, true, SESSION).unwrap();
+    casino.advance_clock(20);
+
+    assert_game_error(
+        casino.slash_operator(&player, SESSION),
+        GameError::SettlementNotOverdue,
+    );
+}
+
+#[test]
+fn provider_rounds_have_no_deadline() {
+    let casino = Casino::with_mock_provider();
+    casino.post_bond(BOND).unwrap();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino
+        .play_game_with_provider(&player, true, BET, SESSION)
+        .unwrap();
+
+    casino.advance_clock(SETTLEMENT_TIMEOUT + 1);
+    assert_game_error(
+        casino.slash_operator(&player, SESSION),
+        GameError::NoSettlementDeadline,
+    );
+}
+
+#[test]
+fn short_bond_pays_what_it_holds() {
+    let casino = Casino::new();
+    let bond = casino_top_up(BET, RTP).unwrap() / 2;
+    casino.post_bond(bond).unwrap();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    let player_before = casino.balance(&player.pubkey());
+    let bond_before = casino.balance(&operator_bond());
+
+    casino.advance_clock(SETTLEMENT_TIMEOUT + 1);
+    casino.slash_operator(&player, SESSION).unwrap();
+
+    assert_eq!(casino.balance(&player.pubkey()), player_before + BET + bond);
+    assert_eq!(casino.balance(&operator_bond()), bond_before - bond);
+    assert_eq!(casino.operator_bond().total_slashed, bond);
+}
+
+#[test]
+fn bond_withdrawal_unlocks_after_two_timeouts() {
+    let casino = bonded_casino();
+    let withdraw = |data| {
+        casino.send(
+            &[withdraw_bond_ix(&casino.operator.pubkey(), data)],
+            &[&casino.operator],
+        )
+    };
+    casino
+        .send(
+            &[withdraw_bond_ix(
+                &casino.operator.pubkey(),
+                instruction::RequestBondWithdrawal { amount: BOND },
+            )],
+            &[&casino.operator],
+        )
+        .unwrap();
+
+    casino.advance_clock(SETTLEMENT_TIMEOUT);
+    assert_game_error(
+        withdraw(instruction::WithdrawBond {}),
+        GameError::BondWithdrawalLocked,
+    );
+
+    casino.advance_clock(SETTLEMENT_TIMEOUT);
+    let operator_before = casino.balance(&casino.operator.pubkey());
+    withdraw(instruction::WithdrawBond {}).unwrap();
+    assert_eq!(
+        casino.balance(&casino.operator.pubkey()),
+        operator_before + BOND
+    );
+}
+
+#[test]
+fn bond_withdrawal_waits_even_without_deadlines() {
+    let casino = bonded_casino();
+    casino
+        .send(
+            &[set_global_pool_ix(
+                &casino.update.pubkey(),
+                instruction::SetSettlementTimeout { timeout: 0 },
+            )],
+            &[&casino.update],
+        )
+        .unwrap();
+    casino
+        .send(
+            &[withdraw_bond_ix(
+                &casino.operator.pubkey(),
+                instruction::RequestBondWithdrawal { amount: BOND },
+            )],
+            &[&casino.operator],
+        )
+        .unwrap();
+    let withdraw = || {
+        casino.send(
+            &[withdraw_bond_ix(
+                &casino.operator.pubkey(),
+                instruction::WithdrawBond {},
+            )],
+            &[&casino.operator],
+        )
+    };
+
+    casino.advance_clock(MIN_BOND_WITHDRAWAL_DELAY - 1);
+    assert_game_error(withdraw(), GameError::BondWithdrawalLocked);
+    casino.advance_clock(1);
+    withdraw().unwrap();
+}
+
+#[test]
+fn bond_requires_operator() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    assert_game_error(
+        casino.send(&[post_bond_ix(&player.pubkey(), BOND)], &[&player]),
+        GameError::UnauthorizedOperator,
+    );
+}
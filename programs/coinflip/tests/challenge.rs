@@ -0,0 +1,272 @@
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use coinflip::{
+    constants::{CHALLENGE_RAKE, CHALLENGE_TIMEOUT, PERMILLE},
+    error::GameError,
+    instruction,
+};
+use common::*;
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Keypair, signer::Signer,
+};
+
+const STAKE: u64 = LAMPORTS_PER_SOL;
+const ID: u64 = 1;
+
+struct Match {
+    casino: Casino,
+    creator: Keypair,
+    opponent: Keypair,
+}
+
+/// An open challenge on heads, returning the rent its account holds.
+fn open_challenge() -> (Match, u64) {
+    let casino = Casino::new();
+    let creator = casino.new_player(10 * LAMPORTS_PER_SOL);
+    let opponent = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.create_challenge(&creator, ID, true, STAKE).unwrap();
+
+    let rent = casino.balance(&challenge(&creator.pubkey(), ID)) - STAKE;
+    let game = Match {
+        casino,
+        creator,
+        opponent,
+    };
+    (game, rent)
+}
+
+fn accepted_challenge() -> (Match, u64) {
+    let (game, rent) = open_challenge();
+    game.casino
+        .accept_challenge(&game.opponent, &game.creator.pubkey(), ID)
+        .unwrap();
+    (game, rent)
+}
+
+#[test]
+fn create_challenge_escrows_the_stake() {
+    let (game, _) = open_challenge();
+
+    let challenge = game.casino.challenge(&game.creator.pubkey(), ID).unwrap();
+    assert_eq!(challenge.creator, game.creator.pubkey());
+    assert!(challenge.is_open());
+    assert_eq!(challenge.stake, STAKE);
+    assert!(challenge.creator_is_head);
+}
+
+#[test]
+fn accept_challenge_escrows_the_matching_stake() {
+    let (game, rent) = open_challenge();
+    let opponent_before = game.casino.balance(&game.opponent.pubkey());
+
+    game.casino
+        .accept_challenge(&game.opponent, &game.creator.pubkey(), ID)
+        .unwrap();
+
+    let key = challenge(&game.creator.pubkey(), ID);
+    assert_eq!(game.casino.balance(&key), rent + 2 * STAKE);
+    assert_eq!(
+        game.casino.balance(&game.opponent.pubkey()),
+        opponent_before - STAKE
+    );
+    let challenge = game.casino.challenge(&game.creator.pubkey(), ID).unwrap();
+    assert_eq!(challenge.opponent, game.opponent.pubkey());
+}
+
+#[test]
+fn winner_takes_the_pot_minus_rake() {
+    for (is_head, creator_wins) in [(true, true), (false, false)] {
+        let (game, rent) = accepted_challenge();
+        let creator_before = game.casino.balance(&game.creator.pubkey());
+        let opponent_before = game.casino.balance(&game.opponent.pubkey());
+
+        game.casino
+            .settle_challenge(&game.creator.pubkey(), &game.opponent.pubkey(), ID, is_head)
+            .unwrap();
+
+        let rake = 2 * STAKE * CHALLENGE_RAKE / PERMILLE;
+        let (creator_won, opponent_won) = match creator_wins {
+            true => (2 * STAKE - rake, 0),
+            false => (0, 2 * STAKE - rake),
+        };
+        assert_eq!(
+            game.casino.balance(&game.creator.pubkey()),
+            creator_before + rent + creator_won
+        );
+        assert_eq!(
+            game.casino.balance(&game.opponent.pubkey()),
+            opponent_before + opponent_won
+        );
+        assert_eq!(game.casino.balance(&casino_vault()), CASINO_FUNDS + rake);
+        assert!(game.casino.challenge(&game.creator.pubkey(), ID).is_none());
+    }
+}
+
+#[test]
+fn rake_follows_the_config() {
+    let (game, _) = accepted_challenge();
+    game.casino
+        .send(
+            &[set_global_pool_ix(
+                &game.casino.update.pubkey(),
+                instruction::SetChallengeConfig {
+                    rake: 0,
+                    timeout: CHALLENGE_TIMEOUT,
+                },
+            )],
+            &[&game.casino.update],
+        )
+        .unwrap();
+
+    game.casino
+        .settle_challenge(&game.creator.pubkey(), &game.opponent.pubkey(), ID, true)
+        .unwrap();
+    assert_eq!(game.casino.balance(&casino_vault()), CASINO_FUNDS);
+
+    assert_game_error(
+        game.casino.send(
+            &[set_global_pool_ix(
+                &game.casino.update.pubkey(),
+                instruction::SetChallengeConfig {
+                    rake: 101,
+                    timeout: CHALLENGE_TIMEOUT,
+                },
+            )],
+            &[&game.casino.update],
+        ),
+        GameError::InvalidChallengeRake,
+    );
+}
+
+#[test]
+fn settlement_requires_operator() {
+    let (game, _) = accepted_challenge();
+
+    assert_game_error(
+        game.casino.send(
+            &[settle_challenge_ix(
+                &game.creator.pubkey(),
+                &game.creator.pubkey(),
+                &game.opponent.pubkey(),
+                ID,
+                true,
+            )],
+            &[&game.creator],
+        ),
+        GameError::UnauthorizedOperator,
+    );
+}
+
+#[test]
+fn open_challenge_cannot_be_settled() {
+    let (game, _) = open_challenge();
+
+    assert_game_error(
+        game.casino
+            .settle_challenge(&game.creator.pubkey(), &Pubkey::new_unique(), ID, true),
+        GameError::ChallengeNotAccepted,
+    );
+}
+
+#[test]
+fn challenge_is_accepted_once_and_not_by_its_creator() {
+    let (game, _) = open_challenge();
+    let creator = game.creator.pubkey();
+
+    assert_game_error(
+        game.casino.accept_challenge(&game.creator, &creator, ID),
+        GameError::OwnChallenge,
+    );
+
+    game.casino
+        .accept_challenge(&game.opponent, &creator, ID)
+        .unwrap();
+    let late = game.casino.new_player(10 * LAMPORTS_PER_SOL);
+    assert_game_error(
+        game.casino.accept_challenge(&late, &creator, ID),
+        GameError::ChallengeNotOpen,
+    );
+}
+
+#[test]
+fn creator_cancels_an_open_challenge() {
+    let (game, rent) = open_challenge();
+    let creator = game.creator.pubkey();
+    let creator_before = game.casino.balance(&creator);
+
+    assert_game_error(
+        game.casino.send(
+            &[cancel_challenge_ix(&game.opponent.pubkey(), &creator, ID)],
+            &[&game.opponent],
+        ),
+        GameError::ChallengeNotCancellable,
+    );
+
+    game.casino
+        .send(
+            &[cancel_challenge_ix(&creator, &creator, ID)],
+            &[&game.creator],
+        )
+        .unwrap();
+    assert_eq!(game.casino.balance(&creator), creator_before + STAKE + rent);
+    assert!(game.casino.challenge(&creator, ID).is_none());
+}
+
+#[test]
+fn expired_challenge_cannot_be_accepted_and_anyone_can_cancel_it() {
+    let (game, rent) = open_challenge();
+    let creator = game.creator.pubkey();
+    let creator_before = game.casino.balance(&creator);
+
+    game.casino.advance_clock(CHALLENGE_TIMEOUT + 1);
+    assert_game_error(
+        game.casino.accept_challenge(&game.opponent, &creator, ID),
+        GameError::ChallengeExpired,
+    );
+
+    game.casino
+        .send(
+            &[cancel_challenge_ix(&game.opponent.pubkey(), &creator, ID)],
+            &[&game.opponent],
+        )
+        .unwrap();
+    assert_eq!(game.casino.balance(&creator), creator_before + STAKE + rent);
+}
+
+#[test]
+fn accepted_challenge_cannot_be_cancelled() {
+    let (game, _) = accepted_challenge();
+    let creator = game.creator.pubkey();
+
+    assert_game_error(
+        game.casino.send(
+            &[cancel_challenge_ix(&creator, &creator, ID)],
+            &[&game.creator],
+        ),
+        GameError::ChallengeNotOpen,
+    );
+}
+
+#[test]
+fn unsettled_challenge_is_refunded_after_its_deadline() {
+    let (game, rent) = accepted_challenge();
+    let creator = game.creator.pubkey();
+    let opponent = game.opponent.pubkey();
+    let refund = || {
+        game.casino
+            .send(&[refund_challenge_ix(&creator, &opponent, ID)], &[])
+    };
+
+    game.casino.advance_clock(CHALLENGE_TIMEOUT);
+    assert_game_error(refund(), GameError::ChallengeNotOverdue);
+
+    let creator_before = game.casino.balance(&creator);
+    let opponent_before = game.casino.balance(&opponent);
+    game.casino.advance_clock(1);
+    refund().unwrap();
+
+    assert_eq!(game.casino.balance(&creator), creator_before + STAKE + rent);
+    assert_eq!(game.casino.balance(&opponent), opponent_before + STAKE);
+}
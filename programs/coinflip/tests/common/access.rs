@@ -0,0 +1,117 @@
+use super::*;
+
+pub fn access_entry(wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[wallet.as_ref(), ACCESS_ENTRY_SEED.as_bytes()],
+        &coinflip::ID,
+    )
+    .0
+}
+
+pub fn set_access_merkle_root_ix(operator: &Pubkey, root: [u8; 32]) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::SetAccessMerkleRoot {
+            operator: *operator,
+            global_authority: global_authority(),
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::SetAccessMerkleRoot { root }.data(),
+    }
+}
+
+pub fn set_access_entry_ix(operator: &Pubkey, wallet: &Pubkey, allowed: bool) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::SetAccessEntry {
+            operator: *operator,
+            wallet: *wallet,
+            global_authority: global_authority(),
+            access_entry: access_entry(wallet),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::SetAccessEntry { allowed }.data(),
+    }
+}
+
+pub fn remove_access_entry_ix(operator: &Pubkey, wallet: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::RemoveAccessEntry {
+            operator: *operator,
+            wallet: *wallet,
+            global_authority: global_authority(),
+            access_entry: access_entry(wallet),
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::RemoveAccessEntry {}.data(),
+    }
+}
+
+pub fn claim_allowlist_access_ix(player: &Pubkey, proof: Vec<[u8; 32]>) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::ClaimAllowlistAccess {
+            player: *player,
+            global_authority: global_authority(),
+            access_entry: access_entry(player),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::ClaimAllowlistAccess { proof }.data(),
+    }
+}
+
+impl Casino {
+    pub fn access_entry(&self, wallet: &Pubkey) -> Option<AccessEntry> {
+        let account = self.svm.borrow().get_account(&access_entry(wallet))?;
+        AccessEntry::try_deserialize(&mut account.data.as_slice()).ok()
+    }
+
+    pub fn set_access_mode(&self, mode: u8) -> TransactionResult {
+        self.send(
+            &[set_global_pool_ix(
+                &self.update.pubkey(),
+                coinflip::instruction::SetAccessMode { mode },
+            )],
+            &[&self.update],
+        )
+    }
+
+    pub fn set_access_merkle_root(&self, root: [u8; 32]) -> TransactionResult {
+        self.send(
+            &[set_access_merkle_root_ix(&self.operator.pubkey(), root)],
+            &[&self.operator],
+        )
+    }
+
+    pub fn set_access_entry(&self, wallet: &Pubkey, allowed: bool) -> TransactionResult {
+        self.send(
+            &[set_access_entry_ix(
+                &self.operator.pubkey(),
+                wallet,
+                allowed,
+            )],
+            &[&self.operator],
+        )
+    }
+
+    pub fn remove_access_entry(&self, wallet: &Pubkey) -> TransactionResult {
+        self.send(
+            &[remove_access_entry_ix(&self.operator.pubkey(), wallet)],
+            &[&self.operator],
+        )
+    }
+
+    pub fn claim_allowlist_access(
+        &self,
+        player: &Keypair,
+        proof: Vec<[u8; 32]>,
+    ) -> TransactionResult {
+        self.send(
+            &[claim_allowlist_access_ix(&player.pubkey(), proof)],
+            &[player],
+        )
+    }
+}
@@ -0,0 +1,94 @@
+use super::*;
+
+pub fn attestation(player: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[player.as_ref(), ATTESTATION_SEED.as_bytes()],
+        &coinflip::ID,
+    )
+    .0
+}
+
+pub fn issue_attestation_ix(compliance: &Pubkey, player: &Pubkey, expires_at: i64) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::IssueAttestation {
+            compliance_authority: *compliance,
+            player: *player,
+            global_authority: global_authority(),
+            attestation: attestation(player),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::IssueAttestation { expires_at }.data(),
+    }
+}
+
+pub fn revoke_attestation_ix(compliance: &Pubkey, player: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::RevokeAttestation {
+            compliance_authority: *compliance,
+            player: *player,
+            global_authority: global_authority(),
+            attestation: attestation(player),
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::RevokeAttestation {}.data(),
+    }
+}
+
+impl Casino {
+    pub fn attestation(&self, player: &Pubkey) -> Attestation {
+        let account = self.svm.borrow().get_account(&attestation(player)).unwrap();
+        Attestation::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    /// Hands attestations over to a new compliance key and returns it.
+    pub fn new_compliance_authority(&self) -> Keypair {
+        let compliance = self.new_player(10 * LAMPORTS_PER_SOL);
+        self.send(
+            &[set_authority_ix(
+                &self.super_admin.pubkey(),
+                coinflip::instruction::SetComplianceAuthority {
+                    new_compliance_authority: compliance.pubkey(),
+                },
+            )],
+            &[&self.super_admin],
+        )
+        .unwrap();
+        compliance
+    }
+
+    pub fn set_attestation_required(&self, required: bool) -> TransactionResult {
+        self.send(
+            &[set_global_pool_ix(
+                &self.update.pubkey(),
+                coinflip::instruction::SetAttestationRequired { required },
+            )],
+            &[&self.update],
+        )
+    }
+
+    pub fn issue_attestation(
+        &self,
+        compliance: &Keypair,
+        player: &Pubkey,
+        expires_at: i64,
+    ) -> TransactionResult {
+        self.send(
+            &[issue_attestation_ix(
+                &compliance.pubkey(),
+                player,
+                expires_at,
+            )],
+            &[compliance],
+        )
+    }
+
+    pub fn revoke_attestation(&self, compliance: &Keypair, player: &Pubkey) -> TransactionResult {
+        self.send(
+            &[revoke_attestation_ix(&compliance.pubkey(), player)],
+            &[compliance],
+        )
+    }
+}
@@ -0,0 +1,162 @@
+use super::*;
+
+pub fn player_balance(player: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[player.as_ref(), PLAYER_BALANCE_SEED.as_bytes()],
+        &coinflip::ID,
+    )
+    .0
+}
+
+pub fn play_game_from_balance_ix(
+    owner: &Pubkey,
+    operator: &Pubkey,
+    is_head: bool,
+    bet_amount: u64,
+    game_session_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::PlayGameFromBalance {
+            owner: *owner,
+            operator: *operator,
+            player_balance: player_balance(owner),
+            player_pool: player_pool(owner, game_session_id),
+            global_authority: global_authority(),
+            casino_vault: casino_vault(),
+            game_vault: game_vault(owner, game_session_id),
+            player_limits: player_limits(owner),
+            access_entry: access_entry(owner),
+            attestation: attestation(owner),
+            player_stats: player_stats(owner),
+            vip_config: vip_config(),
+            jackpot: jackpot(),
+            tournament: None,
+            tournament_entry: None,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::PlayGameFromBalance {
+            is_head,
+            bet_amount,
+            game_session_id,
+        }
+        .data(),
+    }
+}
+
+pub fn claim_reward_to_balance_ix(
+    operator: &Pubkey,
+    player: &Pubkey,
+    game_session_id: u64,
+) -> Instruction {
+    claim_reward_to_balance_in_tournament_ix(operator, player, None, game_session_id)
+}
+
+/// `claim_reward_to_balance` scored for `player`'s entry in tournament `tournament_id`.
+pub fn claim_reward_to_balance_in_tournament_ix(
+    operator: &Pubkey,
+    player: &Pubkey,
+    tournament_id: Option<u64>,
+    game_session_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::ClaimRewardToBalance {
+            operator: *operator,
+            player: *player,
+            player_balance: player_balance(player),
+            player_pool: player_pool(player, game_session_id),
+            global_authority: global_authority(),
+            game_vault: game_vault(player, game_session_id),
+            jackpot: jackpot(),
+            tournament: tournament_id.map(tournament),
+            tournament_entry: tournament_id.map(|id| tournament_entry(id, player)),
+            player_limits: player_limits(player),
+            player_stats: player_stats(player),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::ClaimRewardToBalance { game_session_id }.data(),
+    }
+}
+
+pub fn deposit_balance_ix(player: &Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::DepositBalance {
+            player: *player,
+            player_balance: player_balance(player),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::DepositBalance { amount }.data(),
+    }
+}
+
+pub fn withdraw_balance_ix(player: &Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::WithdrawBalance {
+            player: *player,
+            player_balance: player_balance(player),
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::WithdrawBalance { amount }.data(),
+    }
+}
+
+impl Casino {
+    /// Lamports `player` can bet from their prepaid balance.
+    pub fn player_balance(&self, player: &Pubkey) -> u64 {
+        let key = player_balance(player);
+        let rent = self
+            .svm
+            .borrow()
+            .minimum_balance_for_rent_exemption(8 + PlayerBalance::DATA_SIZE);
+        self.balance(&key).saturating_sub(rent)
+    }
+
+    pub fn deposit_balance(&self, player: &Keypair, amount: u64) -> TransactionResult {
+        self.send(&[deposit_balance_ix(&player.pubkey(), amount)], &[player])
+    }
+
+    pub fn withdraw_balance(&self, player: &Keypair, amount: u64) -> TransactionResult {
+        self.send(&[withdraw_balance_ix(&player.pubkey(), amount)], &[player])
+    }
+
+    pub fn play_game_from_balance(
+        &self,
+        player: &Keypair,
+        is_head: bool,
+        bet_amount: u64,
+        game_session_id: u64,
+    ) -> TransactionResult {
+        self.send(
+            &[play_game_from_balance_ix(
+                &player.pubkey(),
+                &self.operator.pubkey(),
+                is_head,
+                bet_amount,
+                game_session_id,
+            )],
+            &[player, &self.operator],
+        )
+    }
+
+    pub fn claim_reward_to_balance(
+        &self,
+        player: &Pubkey,
+        game_session_id: u64,
+    ) -> TransactionResult {
+        self.send(
+            &[claim_reward_to_balance_ix(
+                &self.operator.pubkey(),
+                player,
+                game_session_id,
+            )],
+            &[&self.operator],
+        )
+    }
+}
@@ -0,0 +1,75 @@
+use super::*;
+
+pub fn operator_bond() -> Pubkey {
+    Pubkey::find_program_address(&[OPERATOR_BOND_SEED.as_bytes()], &coinflip::ID).0
+}
+
+pub fn post_bond_ix(operator: &Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::PostBond {
+            operator: *operator,
+            global_authority: global_authority(),
+            operator_bond: operator_bond(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::PostBond { amount }.data(),
+    }
+}
+
+pub fn withdraw_bond_ix(operator: &Pubkey, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::WithdrawBond {
+            operator: *operator,
+            global_authority: global_authority(),
+            operator_bond: operator_bond(),
+        }
+        .to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn slash_operator_ix(operator: &Pubkey, owner: &Pubkey, game_session_id: u64) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::SlashOperator {
+            owner: *owner,
+            operator: *operator,
+            global_authority: global_authority(),
+            player_pool: player_pool(owner, game_session_id),
+            game_vault: game_vault(owner, game_session_id),
+            operator_bond: operator_bond(),
+            player_limits: player_limits(owner),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::SlashOperator { game_session_id }.data(),
+    }
+}
+
+impl Casino {
+    pub fn operator_bond(&self) -> OperatorBond {
+        let account = self.svm.borrow().get_account(&operator_bond()).unwrap();
+        OperatorBond::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub fn post_bond(&self, amount: u64) -> TransactionResult {
+        self.send(
+            &[post_bond_ix(&self.operator.pubkey(), amount)],
+            &[&self.operator],
+        )
+    }
+
+    pub fn slash_operator(&self, player: &Keypair, game_session_id: u64) -> TransactionResult {
+        self.send(
+            &[slash_operator_ix(
+                &self.operator.pubkey(),
+                &player.pubkey(),
+                game_session_id,
+            )],
+            &[player],
+        )
+    }
+}
@@ -0,0 +1,167 @@
+use super::*;
+
+pub fn challenge(creator: &Pubkey, challenge_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            creator.as_ref(),
+            CHALLENGE_SEED.as_bytes(),
+            &challenge_id.to_be_bytes(),
+        ],
+        &coinflip::ID,
+    )
+    .0
+}
+
+pub fn create_challenge_ix(
+    creator: &Pubkey,
+    challenge_id: u64,
+    is_head: bool,
+    stake: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::CreateChallenge {
+            creator: *creator,
+            global_authority: global_authority(),
+            challenge: challenge(creator, challenge_id),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::CreateChallenge {
+            challenge_id,
+            is_head,
+            stake,
+        }
+        .data(),
+    }
+}
+
+pub fn accept_challenge_ix(opponent: &Pubkey, creator: &Pubkey, challenge_id: u64) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::AcceptChallenge {
+            opponent: *opponent,
+            creator: *creator,
+            global_authority: global_authority(),
+            challenge: challenge(creator, challenge_id),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::AcceptChallenge { challenge_id }.data(),
+    }
+}
+
+pub fn cancel_challenge_ix(signer: &Pubkey, creator: &Pubkey, challenge_id: u64) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::CancelChallenge {
+            signer: *signer,
+            creator: *creator,
+            challenge: challenge(creator, challenge_id),
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::CancelChallenge { challenge_id }.data(),
+    }
+}
+
+pub fn refund_challenge_ix(creator: &Pubkey, opponent: &Pubkey, challenge_id: u64) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::RefundChallenge {
+            creator: *creator,
+            opponent: *opponent,
+            challenge: challenge(creator, challenge_id),
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::RefundChallenge { challenge_id }.data(),
+    }
+}
+
+pub fn settle_challenge_ix(
+    operator: &Pubkey,
+    creator: &Pubkey,
+    opponent: &Pubkey,
+    challenge_id: u64,
+    is_head: bool,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::SettleChallenge {
+            operator: *operator,
+            creator: *creator,
+            opponent: *opponent,
+            global_authority: global_authority(),
+            challenge: challenge(creator, challenge_id),
+            casino_vault: casino_vault(),
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::SettleChallenge {
+            challenge_id,
+            is_head,
+        }
+        .data(),
+    }
+}
+
+impl Casino {
+    pub fn challenge(&self, creator: &Pubkey, challenge_id: u64) -> Option<Challenge> {
+        let account = self
+            .svm
+            .borrow()
+            .get_account(&challenge(creator, challenge_id))?;
+        Challenge::try_deserialize(&mut account.data.as_slice()).ok()
+    }
+
+    pub fn create_challenge(
+        &self,
+        creator: &Keypair,
+        challenge_id: u64,
+        is_head: bool,
+        stake: u64,
+    ) -> TransactionResult {
+        self.send(
+            &[create_challenge_ix(
+                &creator.pubkey(),
+                challenge_id,
+                is_head,
+                stake,
+            )],
+            &[creator],
+        )
+    }
+
+    pub fn accept_challenge(
+        &self,
+        opponent: &Keypair,
+        creator: &Pubkey,
+        challenge_id: u64,
+    ) -> TransactionResult {
+        self.send(
+            &[accept_challenge_ix(
+                &opponent.pubkey(),
+                creator,
+                challenge_id,
+            )],
+            &[opponent],
+        )
+    }
+
+    pub fn settle_challenge(
+        &self,
+        creator: &Pubkey,
+        opponent: &Pubkey,
+        challenge_id: u64,
+        is_head: bool,
+    ) -> TransactionResult {
+        self.send(
+            &[settle_challenge_ix(
+                &self.operator.pubkey(),
+                creator,
+                opponent,
+                challenge_id,
+                is_head,
+            )],
+            &[&self.operator],
+        )
+    }
+}
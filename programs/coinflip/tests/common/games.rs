@@ -0,0 +1,239 @@
+use super::*;
+
+pub fn play_dice_ix(
+    owner: &Pubkey,
+    operator: &Pubkey,
+    target: u8,
+    bet_amount: u64,
+    game_session_id: u64,
+) -> Instruction {
+    play_dice_in_tournament_ix(owner, operator, None, target, bet_amount, game_session_id)
+}
+
+/// `play_dice` scored for `owner`'s entry in tournament `tournament_id`.
+pub fn play_dice_in_tournament_ix(
+    owner: &Pubkey,
+    operator: &Pubkey,
+    tournament_id: Option<u64>,
+    target: u8,
+    bet_amount: u64,
+    game_session_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::PlayDice {
+            owner: *owner,
+            operator: *operator,
+            player_pool: player_pool(owner, game_session_id),
+            global_authority: global_authority(),
+            casino_vault: casino_vault(),
+            game_vault: game_vault(owner, game_session_id),
+            player_limits: player_limits(owner),
+            access_entry: access_entry(owner),
+            attestation: attestation(owner),
+            player_stats: player_stats(owner),
+            vip_config: vip_config(),
+            jackpot: jackpot(),
+            tournament: tournament_id.map(tournament),
+            tournament_entry: tournament_id.map(|id| tournament_entry(id, owner)),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::PlayDice {
+            target,
+            bet_amount,
+            game_session_id,
+        }
+        .data(),
+    }
+}
+
+pub fn play_multi_coin_ix(
+    owner: &Pubkey,
+    operator: &Pubkey,
+    coins: u8,
+    heads: u8,
+    exact: bool,
+    bet_amount: u64,
+    game_session_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::PlayMultiCoin {
+            owner: *owner,
+            operator: *operator,
+            player_pool: player_pool(owner, game_session_id),
+            global_authority: global_authority(),
+            casino_vault: casino_vault(),
+            game_vault: game_vault(owner, game_session_id),
+            player_limits: player_limits(owner),
+            access_entry: access_entry(owner),
+            attestation: attestation(owner),
+            player_stats: player_stats(owner),
+            vip_config: vip_config(),
+            jackpot: jackpot(),
+            tournament: None,
+            tournament_entry: None,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::PlayMultiCoin {
+            coins,
+            heads,
+            exact,
+            bet_amount,
+            game_session_id,
+        }
+        .data(),
+    }
+}
+
+pub fn play_batch_ix(
+    owner: &Pubkey,
+    operator: &Pubkey,
+    choices: Vec<bool>,
+    amounts: Vec<u64>,
+    game_session_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::PlayBatch {
+            owner: *owner,
+            operator: *operator,
+            player_pool: player_pool(owner, game_session_id),
+            global_authority: global_authority(),
+            casino_vault: casino_vault(),
+            game_vault: game_vault(owner, game_session_id),
+            player_limits: player_limits(owner),
+            access_entry: access_entry(owner),
+            attestation: attestation(owner),
+            player_stats: player_stats(owner),
+            vip_config: vip_config(),
+            jackpot: jackpot(),
+            tournament: None,
+            tournament_entry: None,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::PlayBatch {
+            choices,
+            amounts,
+            game_session_id,
+        }
+        .data(),
+    }
+}
+
+pub fn set_batch_result_ix(
+    operator: &Pubkey,
+    owner: &Pubkey,
+    round_id: u8,
+    outcomes: u64,
+    game_session_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::SetBatchResult {
+            operator: *operator,
+            owner: *owner,
+            global_authority: global_authority(),
+            player_pool: player_pool(owner, game_session_id),
+            game_vault: game_vault(owner, game_session_id),
+            casino_vault: casino_vault(),
+            player_stats: player_stats(owner),
+            vip_config: vip_config(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::SetBatchResult {
+            round_id,
+            outcomes,
+            game_session_id,
+        }
+        .data(),
+    }
+}
+
+impl Casino {
+    pub fn play_dice(
+        &self,
+        player: &Keypair,
+        target: u8,
+        bet_amount: u64,
+        game_session_id: u64,
+    ) -> TransactionResult {
+        self.send(
+            &[play_dice_ix(
+                &player.pubkey(),
+                &self.operator.pubkey(),
+                target,
+                bet_amount,
+                game_session_id,
+            )],
+            &[player, &self.operator],
+        )
+    }
+
+    pub fn play_multi_coin(
+        &self,
+        player: &Keypair,
+        coins: u8,
+        heads: u8,
+        exact: bool,
+        bet_amount: u64,
+        game_session_id: u64,
+    ) -> TransactionResult {
+        self.send(
+            &[play_multi_coin_ix(
+                &player.pubkey(),
+                &self.operator.pubkey(),
+                coins,
+                heads,
+                exact,
+                bet_amount,
+                game_session_id,
+            )],
+            &[player, &self.operator],
+        )
+    }
+
+    pub fn play_batch(
+        &self,
+        player: &Keypair,
+        choices: Vec<bool>,
+        amounts: Vec<u64>,
+        game_session_id: u64,
+    ) -> TransactionResult {
+        self.send(
+            &[play_batch_ix(
+                &player.pubkey(),
+                &self.operator.pubkey(),
+                choices,
+                amounts,
+                game_session_id,
+            )],
+            &[player, &self.operator],
+        )
+    }
+
+    pub fn set_batch_result(
+        &self,
+        player: &Pubkey,
+        outcomes: u64,
+        game_session_id: u64,
+    ) -> TransactionResult {
+        self.send(
+            &[set_batch_result_ix(
+                &self.operator.pubkey(),
+                player,
+                1,
+                outcomes,
+                game_session_id,
+            )],
+            &[&self.operator],
+        )
+    }
+}
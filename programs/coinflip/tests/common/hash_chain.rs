@@ -0,0 +1,41 @@
+use super::*;
+
+pub fn settle_with_reveal_ix(
+    operator: &Pubkey,
+    owner: &Pubkey,
+    game_session_id: u64,
+    reveal: [u8; 32],
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::SettleWithReveal {
+            operator: *operator,
+            owner: *owner,
+            global_authority: global_authority(),
+            player_pool: player_pool(owner, game_session_id),
+            game_vault: game_vault(owner, game_session_id),
+            casino_vault: casino_vault(),
+            player_stats: player_stats(owner),
+            vip_config: vip_config(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::SettleWithReveal {
+            game_session_id,
+            reveal,
+        }
+        .data(),
+    }
+}
+
+pub fn publish_hash_chain_ix(operator: &Pubkey, tail: [u8; 32], length: u64) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::PublishHashChain {
+            operator: *operator,
+            global_authority: global_authority(),
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::PublishHashChain { tail, length }.data(),
+    }
+}
@@ -0,0 +1,125 @@
+use super::*;
+
+/// Ed25519 precompile instruction carrying `signer`'s signature of a bet intent.
+pub fn signed_intent_ix(
+    signer: &Keypair,
+    player: &Pubkey,
+    is_head: bool,
+    bet_amount: u64,
+    game_session_id: u64,
+    expires_at: i64,
+    nonce: u64,
+) -> Instruction {
+    let message = bet_intent_message(
+        player,
+        is_head,
+        bet_amount,
+        game_session_id,
+        expires_at,
+        nonce,
+    );
+    let signature = signer.sign_message(&message);
+
+    solana_ed25519_program::new_ed25519_instruction_with_signature(
+        &message,
+        &signature.into(),
+        &signer.pubkey().to_bytes(),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn play_game_with_intent_ix(
+    operator: &Pubkey,
+    owner: &Pubkey,
+    session_key: Option<Pubkey>,
+    is_head: bool,
+    bet_amount: u64,
+    game_session_id: u64,
+    expires_at: i64,
+    nonce: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::PlayGameWithIntent {
+            operator: *operator,
+            owner: *owner,
+            player_balance: player_balance(owner),
+            session_key,
+            player_pool: player_pool(owner, game_session_id),
+            global_authority: global_authority(),
+            casino_vault: casino_vault(),
+            game_vault: game_vault(owner, game_session_id),
+            player_limits: player_limits(owner),
+            access_entry: access_entry(owner),
+            attestation: attestation(owner),
+            instructions: sysvar::instructions::ID,
+            player_stats: player_stats(owner),
+            vip_config: vip_config(),
+            jackpot: jackpot(),
+            tournament: None,
+            tournament_entry: None,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::PlayGameWithIntent {
+            is_head,
+            bet_amount,
+            game_session_id,
+            expires_at,
+            nonce,
+        }
+        .data(),
+    }
+}
+
+impl Casino {
+    /// Nonce the next bet intent of `player` has to carry.
+    pub fn intent_nonce(&self, player: &Pubkey) -> u64 {
+        let account = self
+            .svm
+            .borrow()
+            .get_account(&player_balance(player))
+            .unwrap();
+        PlayerBalance::try_deserialize(&mut account.data.as_slice())
+            .unwrap()
+            .intent_nonce
+    }
+
+    /// Operator submits a bet intent of `owner` signed by `signer`, valid for a minute.
+    pub fn play_game_with_intent(
+        &self,
+        signer: &Keypair,
+        owner: &Pubkey,
+        session_key: Option<Pubkey>,
+        bet_amount: u64,
+        game_session_id: u64,
+    ) -> TransactionResult {
+        let expires_at = self.now() + 60;
+        let nonce = self.intent_nonce(owner);
+        self.send(
+            &[
+                signed_intent_ix(
+                    signer,
+                    owner,
+                    true,
+                    bet_amount,
+                    game_session_id,
+                    expires_at,
+                    nonce,
+                ),
+                play_game_with_intent_ix(
+                    &self.operator.pubkey(),
+                    owner,
+                    session_key,
+                    true,
+                    bet_amount,
+                    game_session_id,
+                    expires_at,
+                    nonce,
+                ),
+            ],
+            &[&self.operator],
+        )
+    }
+}
@@ -0,0 +1,62 @@
+use super::*;
+
+pub fn jackpot() -> Pubkey {
+    Pubkey::find_program_address(&[JACKPOT_SEED.as_bytes()], &coinflip::ID).0
+}
+
+pub fn set_jackpot_ix(admin: &Pubkey, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::SetJackpot {
+            admin: *admin,
+            global_authority: global_authority(),
+            jackpot: jackpot(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+impl Casino {
+    pub fn jackpot(&self) -> Jackpot {
+        let account = self.svm.borrow().get_account(&jackpot()).unwrap();
+        Jackpot::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    /// Lamports the next jackpot winner takes.
+    pub fn jackpot_balance(&self) -> u64 {
+        let rent = self
+            .svm
+            .borrow()
+            .minimum_balance_for_rent_exemption(8 + Jackpot::DATA_SIZE);
+        self.balance(&jackpot()).saturating_sub(rent)
+    }
+
+    pub fn set_jackpot_config(
+        &self,
+        contribution_rate: u64,
+        trigger_streak: u8,
+    ) -> TransactionResult {
+        self.send(
+            &[set_jackpot_ix(
+                &self.update.pubkey(),
+                coinflip::instruction::SetJackpotConfig {
+                    contribution_rate,
+                    trigger_streak,
+                },
+            )],
+            &[&self.update],
+        )
+    }
+
+    pub fn fund_jackpot(&self, amount: u64) -> TransactionResult {
+        self.send(
+            &[set_jackpot_ix(
+                &self.update.pubkey(),
+                coinflip::instruction::FundJackpot { amount },
+            )],
+            &[&self.update],
+        )
+    }
+}
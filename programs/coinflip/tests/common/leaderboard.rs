@@ -0,0 +1,130 @@
+use super::*;
+
+pub fn leaderboard(period: u8, period_index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            LEADERBOARD_SEED.as_bytes(),
+            &[period],
+            &period_index.to_be_bytes(),
+        ],
+        &coinflip::ID,
+    )
+    .0
+}
+
+pub fn player_stats(player: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[player.as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        &coinflip::ID,
+    )
+    .0
+}
+
+pub fn open_leaderboard_ix(payer: &Pubkey, period: u8, period_index: u64) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::OpenLeaderboard {
+            payer: *payer,
+            leaderboard: leaderboard(period, period_index),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::OpenLeaderboard {
+            period,
+            period_index,
+        }
+        .data(),
+    }
+}
+
+pub fn open_player_stats_ix(payer: &Pubkey, player: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::OpenPlayerStats {
+            payer: *payer,
+            player: *player,
+            player_stats: player_stats(player),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::OpenPlayerStats {}.data(),
+    }
+}
+
+/// Passes `leaderboards` to `ix` as the remaining accounts it ranks players on.
+pub fn with_leaderboards(mut ix: Instruction, leaderboards: &[Pubkey]) -> Instruction {
+    ix.accounts.extend(
+        leaderboards
+            .iter()
+            .map(|leaderboard| AccountMeta::new(*leaderboard, false)),
+    );
+    ix
+}
+
+impl Casino {
+    pub fn leaderboard(&self, period: u8, period_index: u64) -> Leaderboard {
+        let account = self
+            .svm
+            .borrow()
+            .get_account(&leaderboard(period, period_index))
+            .unwrap();
+        Leaderboard::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub fn player_stats(&self, player: &Pubkey) -> PlayerStats {
+        let account = self
+            .svm
+            .borrow()
+            .get_account(&player_stats(player))
+            .unwrap();
+        PlayerStats::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub fn open_leaderboard(&self, period: u8, period_index: u64) -> TransactionResult {
+        self.send(
+            &[open_leaderboard_ix(
+                &self.payer.pubkey(),
+                period,
+                period_index,
+            )],
+            &[&self.payer],
+        )
+    }
+
+    pub fn open_player_stats(&self, player: &Pubkey) -> TransactionResult {
+        self.send(
+            &[open_player_stats_ix(&self.payer.pubkey(), player)],
+            &[&self.payer],
+        )
+    }
+
+    /// `set_result` ranking the settled volume on `leaderboards`.
+    pub fn set_ranked_result(
+        &self,
+        player: &Pubkey,
+        round_id: u8,
+        is_win: bool,
+        game_session_id: u64,
+        leaderboards: &[Pubkey],
+    ) -> TransactionResult {
+        let ix = set_result_ix(
+            &self.operator.pubkey(),
+            player,
+            round_id,
+            is_win,
+            game_session_id,
+        );
+        self.send(&[with_leaderboards(ix, leaderboards)], &[&self.operator])
+    }
+
+    /// `claim_reward` ranking the payout on `leaderboards`.
+    pub fn claim_ranked_reward(
+        &self,
+        player: &Pubkey,
+        game_session_id: u64,
+        leaderboards: &[Pubkey],
+    ) -> TransactionResult {
+        let ix = claim_reward_ix(&self.operator.pubkey(), player, game_session_id);
+        self.send(&[with_leaderboards(ix, leaderboards)], &[&self.operator])
+    }
+}
@@ -0,0 +1,61 @@
+use super::*;
+
+pub fn player_limits(player: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[player.as_ref(), PLAYER_LIMITS_SEED.as_bytes()],
+        &coinflip::ID,
+    )
+    .0
+}
+
+pub fn set_player_limits_ix(
+    player: &Pubkey,
+    wager_limits: [u64; 3],
+    loss_limits: [u64; 3],
+    self_excluded_until: i64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::SetPlayerLimits {
+            player: *player,
+            player_limits: player_limits(player),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::SetPlayerLimits {
+            wager_limits,
+            loss_limits,
+            self_excluded_until,
+        }
+        .data(),
+    }
+}
+
+impl Casino {
+    pub fn player_limits(&self, player: &Pubkey) -> PlayerLimits {
+        let account = self
+            .svm
+            .borrow()
+            .get_account(&player_limits(player))
+            .unwrap();
+        PlayerLimits::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub fn set_player_limits(
+        &self,
+        player: &Keypair,
+        wager_limits: [u64; 3],
+        loss_limits: [u64; 3],
+        self_excluded_until: i64,
+    ) -> TransactionResult {
+        self.send(
+            &[set_player_limits_ix(
+                &player.pubkey(),
+                wager_limits,
+                loss_limits,
+                self_excluded_until,
+            )],
+            &[player],
+        )
+    }
+}
@@ -0,0 +1,501 @@
+// LiteSVM reports failed transactions with their full metadata
+#![allow(clippy::result_large_err)]
+#![allow(dead_code, unused_imports)]
+
+mod access;
+mod attestation;
+mod balance;
+mod bond;
+mod challenge;
+mod games;
+mod hash_chain;
+mod intent;
+mod jackpot;
+mod leaderboard;
+mod limits;
+mod promo;
+mod randomness;
+mod session_key;
+mod signed_result;
+mod slot_hash;
+mod tournament;
+mod vip;
+mod withdrawal;
+
+pub use access::*;
+pub use attestation::*;
+pub use balance::*;
+pub use bond::*;
+pub use challenge::*;
+pub use games::*;
+pub use hash_chain::*;
+pub use intent::*;
+pub use jackpot::*;
+pub use leaderboard::*;
+pub use limits::*;
+pub use promo::*;
+pub use randomness::*;
+pub use session_key::*;
+pub use signed_result::*;
+pub use slot_hash::*;
+pub use tournament::*;
+pub use vip::*;
+pub use withdrawal::*;
+
+use std::cell::RefCell;
+
+use anchor_lang::{
+    error::ErrorCode, system_program, AccountDeserialize, InstructionData, ToAccountMetas,
+};
+use coinflip::{
+    account::{
+        AccessEntry, Attestation, Challenge, GlobalPool, Jackpot, Leaderboard, OperatorBond,
+        PlayerBalance, PlayerLimits, PlayerPool, PlayerStats, PromoCredit, SessionKey, Tournament,
+        TournamentEntry, VipConfig, VipTier, WithdrawalConfig, WithdrawalProposal,
+    },
+    constants::{
+        ACCESS_ENTRY_SEED, ATTESTATION_SEED, CHALLENGE_SEED, GLOBAL_AUTHORITY_SEED, JACKPOT_SEED,
+        LEADERBOARD_SEED, OPERATOR_BOND_SEED, PLAYER_BALANCE_SEED, PLAYER_LIMITS_SEED,
+        PLAYER_POOL_SEED, PLAYER_STATS_SEED, PROMO_CREDIT_SEED, RANDOMNESS_REQUESTER_SEED,
+        SESSION_KEY_SEED, TOURNAMENT_ENTRY_SEED, TOURNAMENT_SEED, VAULT_AUTHORITY_SEED,
+        VIP_CONFIG_SEED, WITHDRAWAL_CONFIG_SEED, WITHDRAWAL_PROPOSAL_SEED,
+    },
+    error::GameError,
+    randomness::randomness_seed,
+    utils::{bet_intent_message, settlement_message},
+};
+use litesvm::{types::TransactionResult, LiteSVM};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    hash::Hash,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    slot_hashes::SlotHashes,
+    sysvar,
+    transaction::{Transaction, TransactionError},
+};
+
+/// Built by `anchor build`, which has to run before `cargo test`.
+const PROGRAM_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../target/deploy/coinflip.so"
+);
+const MOCK_RANDOMNESS_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../target/deploy/mock_randomness.so"
+);
+
+pub const CASINO_FUNDS: u64 = 100 * LAMPORTS_PER_SOL;
+
+pub fn global_authority() -> Pubkey {
+    Pubkey::find_program_address(&[GLOBAL_AUTHORITY_SEED.as_bytes()], &coinflip::ID).0
+}
+
+pub fn casino_vault() -> Pubkey {
+    Pubkey::find_program_address(&[VAULT_AUTHORITY_SEED.as_bytes()], &coinflip::ID).0
+}
+
+pub fn player_pool(player: &Pubkey, game_session_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            player.as_ref(),
+            PLAYER_POOL_SEED.as_bytes(),
+            &game_session_id.to_be_bytes(),
+        ],
+        &coinflip::ID,
+    )
+    .0
+}
+
+pub fn game_vault(player: &Pubkey, game_session_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            player.as_ref(),
+            VAULT_AUTHORITY_SEED.as_bytes(),
+            &game_session_id.to_be_bytes(),
+        ],
+        &coinflip::ID,
+    )
+    .0
+}
+
+pub fn initialize_ix(
+    admin: &Pubkey,
+    operate: &Pubkey,
+    finance: &Pubkey,
+    update: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::Initialize {
+            global_authority: global_authority(),
+            casino_vault: casino_vault(),
+            jackpot: jackpot(),
+            vip_config: vip_config(),
+            admin: *admin,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::Initialize {
+            operate_admin: *operate,
+            financial_admin: *finance,
+            update_admin: *update,
+        }
+        .data(),
+    }
+}
+
+pub fn play_game_ix(
+    owner: &Pubkey,
+    operator: &Pubkey,
+    is_head: bool,
+    bet_amount: u64,
+    game_session_id: u64,
+) -> Instruction {
+    play_game_in_tournament_ix(owner, operator, None, is_head, bet_amount, game_session_id)
+}
+
+pub fn set_result_ix(
+    operator: &Pubkey,
+    owner: &Pubkey,
+    round_id: u8,
+    is_win: bool,
+    game_session_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::SetResult {
+            operator: *operator,
+            owner: *owner,
+            global_authority: global_authority(),
+            player_pool: player_pool(owner, game_session_id),
+            game_vault: game_vault(owner, game_session_id),
+            casino_vault: casino_vault(),
+            player_stats: player_stats(owner),
+            vip_config: vip_config(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::SetResult {
+            round_id,
+            is_win,
+            game_session_id,
+        }
+        .data(),
+    }
+}
+
+pub fn double_bet_ix(
+    owner: &Pubkey,
+    operator: &Pubkey,
+    is_head: bool,
+    game_session_id: u64,
+) -> Instruction {
+    double_bet_in_tournament_ix(owner, operator, None, is_head, game_session_id)
+}
+
+/// `double_bet` scored for `owner`'s entry in tournament `tournament_id`.
+pub fn double_bet_in_tournament_ix(
+    owner: &Pubkey,
+    operator: &Pubkey,
+    tournament_id: Option<u64>,
+    is_head: bool,
+    game_session_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::DoubleBet {
+            owner: *owner,
+            operator: *operator,
+            player_pool: player_pool(owner, game_session_id),
+            global_authority: global_authority(),
+            casino_vault: casino_vault(),
+            game_vault: game_vault(owner, game_session_id),
+            player_limits: player_limits(owner),
+            player_stats: player_stats(owner),
+            vip_config: vip_config(),
+            tournament: tournament_id.map(tournament),
+            tournament_entry: tournament_id.map(|id| tournament_entry(id, owner)),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::DoubleBet {
+            is_head,
+            game_session_id,
+        }
+        .data(),
+    }
+}
+
+pub fn claim_reward_ix(operator: &Pubkey, player: &Pubkey, game_session_id: u64) -> Instruction {
+    claim_reward_in_tournament_ix(operator, player, None, game_session_id)
+}
+
+pub fn set_global_pool_ix(admin: &Pubkey, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::SetGlobalPool {
+            admin: *admin,
+            global_pool: global_authority(),
+        }
+        .to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn set_authority_ix(admin: &Pubkey, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::SetAuthority {
+            admin: *admin,
+            global_pool: global_authority(),
+        }
+        .to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn resize_global_pool_ix(admin: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::ResizeGlobalPool {
+            admin: *admin,
+            global_authority: global_authority(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::ResizeGlobalPool {}.data(),
+    }
+}
+
+/// An initialized casino with a funded vault. Transaction fees are paid by a
+/// separate `payer` so balances of the accounts under test only move through
+/// the program.
+pub struct Casino {
+    svm: RefCell<LiteSVM>,
+    pub payer: Keypair,
+    pub super_admin: Keypair,
+    pub operator: Keypair,
+    pub finance: Keypair,
+    pub update: Keypair,
+}
+
+impl Casino {
+    pub fn new() -> Self {
+        Self::with_program(PROGRAM_PATH)
+    }
+
+    pub fn with_program(program_path: &str) -> Self {
+        let mut svm = LiteSVM::new();
+        svm.add_program_from_file(coinflip::ID, program_path)
+            .expect("run `anchor build` before the integration tests");
+
+        let casino = Self {
+            svm: RefCell::new(svm),
+            payer: Keypair::new(),
+            super_admin: Keypair::new(),
+            operator: Keypair::new(),
+            finance: Keypair::new(),
+            update: Keypair::new(),
+        };
+
+        casino.airdrop(&casino.payer.pubkey(), 100 * LAMPORTS_PER_SOL);
+        for admin in [
+            &casino.super_admin,
+            &casino.operator,
+            &casino.finance,
+            &casino.update,
+        ] {
+            casino.airdrop(&admin.pubkey(), 10 * LAMPORTS_PER_SOL);
+        }
+
+        casino
+            .send(
+                &[initialize_ix(
+                    &casino.super_admin.pubkey(),
+                    &casino.operator.pubkey(),
+                    &casino.finance.pubkey(),
+                    &casino.update.pubkey(),
+                )],
+                &[&casino.super_admin],
+            )
+            .unwrap();
+        casino.airdrop(&casino_vault(), CASINO_FUNDS);
+
+        casino
+    }
+
+    pub fn airdrop(&self, to: &Pubkey, lamports: u64) {
+        self.svm.borrow_mut().airdrop(to, lamports).unwrap();
+    }
+
+    pub fn new_player(&self, lamports: u64) -> Keypair {
+        let player = Keypair::new();
+        self.airdrop(&player.pubkey(), lamports);
+        player
+    }
+
+    /// Send `instructions` signed by the fee payer and `signers`.
+    pub fn send(&self, instructions: &[Instruction], signers: &[&Keypair]) -> TransactionResult {
+        self.send_with_payer(&self.payer, instructions, signers)
+    }
+
+    /// Send a transaction whose fee is paid by `payer`, e.g. a player
+    /// submitting an instruction that needs no other signature.
+    pub fn send_with_payer(
+        &self,
+        payer: &Keypair,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> TransactionResult {
+        let mut svm = self.svm.borrow_mut();
+        let mut all_signers = vec![payer];
+        all_signers.extend_from_slice(signers);
+
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &all_signers,
+            svm.latest_blockhash(),
+        );
+        let result = svm.send_transaction(tx);
+        // Identical transactions in one test must not collide as duplicates
+        svm.expire_blockhash();
+        result
+    }
+
+    /// Cut an account's data down to `len` bytes, e.g. to stand in for an
+    /// account written by an older program version.
+    pub fn truncate_account(&self, key: &Pubkey, len: usize) {
+        let mut svm = self.svm.borrow_mut();
+        let mut account = svm.get_account(key).unwrap();
+        account.data.truncate(len);
+        svm.set_account(*key, account).unwrap();
+    }
+
+    pub fn advance_clock(&self, seconds: i64) {
+        let mut svm = self.svm.borrow_mut();
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += seconds;
+        svm.set_sysvar(&clock);
+    }
+
+    pub fn now(&self) -> i64 {
+        self.svm.borrow().get_sysvar::<Clock>().unix_timestamp
+    }
+
+    pub fn account(&self, key: &Pubkey) -> Option<Account> {
+        self.svm.borrow().get_account(key)
+    }
+
+    pub fn balance(&self, key: &Pubkey) -> u64 {
+        self.svm.borrow().get_balance(key).unwrap_or_default()
+    }
+
+    /// Sum of the balances of `keys`.
+    pub fn total(&self, keys: &[Pubkey]) -> u64 {
+        keys.iter().map(|key| self.balance(key)).sum()
+    }
+
+    pub fn global_pool(&self) -> GlobalPool {
+        let account = self.svm.borrow().get_account(&global_authority()).unwrap();
+        GlobalPool::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub fn player_pool(&self, player: &Pubkey, game_session_id: u64) -> Option<PlayerPool> {
+        let account = self
+            .svm
+            .borrow()
+            .get_account(&player_pool(player, game_session_id))?;
+        PlayerPool::try_deserialize(&mut account.data.as_slice()).ok()
+    }
+
+    pub fn play_game(
+        &self,
+        player: &Keypair,
+        is_head: bool,
+        bet_amount: u64,
+        game_session_id: u64,
+    ) -> TransactionResult {
+        self.send(
+            &[play_game_ix(
+                &player.pubkey(),
+                &self.operator.pubkey(),
+                is_head,
+                bet_amount,
+                game_session_id,
+            )],
+            &[player, &self.operator],
+        )
+    }
+
+    pub fn set_result(
+        &self,
+        player: &Pubkey,
+        round_id: u8,
+        is_win: bool,
+        game_session_id: u64,
+    ) -> TransactionResult {
+        self.send(
+            &[set_result_ix(
+                &self.operator.pubkey(),
+                player,
+                round_id,
+                is_win,
+                game_session_id,
+            )],
+            &[&self.operator],
+        )
+    }
+
+    pub fn double_bet(
+        &self,
+        player: &Keypair,
+        is_head: bool,
+        game_session_id: u64,
+    ) -> TransactionResult {
+        self.send(
+            &[double_bet_ix(
+                &player.pubkey(),
+                &self.operator.pubkey(),
+                is_head,
+                game_session_id,
+            )],
+            &[player, &self.operator],
+        )
+    }
+
+    pub fn claim_reward(&self, player: &Pubkey, game_session_id: u64) -> TransactionResult {
+        self.send(
+            &[claim_reward_ix(
+                &self.operator.pubkey(),
+                player,
+                game_session_id,
+            )],
+            &[&self.operator],
+        )
+    }
+}
+
+fn custom_error(result: TransactionResult) -> u32 {
+    match result {
+        Err(failed) => match failed.err {
+            TransactionError::InstructionError(_, InstructionError::Custom(code)) => code,
+            err => panic!("expected a custom program error, got {err:?}"),
+        },
+        Ok(_) => panic!("expected the transaction to fail"),
+    }
+}
+
+pub fn assert_game_error(result: TransactionResult, expected: GameError) {
+    assert_eq!(custom_error(result), u32::from(expected));
+}
+
+pub fn assert_anchor_error(result: TransactionResult, expected: ErrorCode) {
+    assert_eq!(custom_error(result), u32::from(expected));
+}
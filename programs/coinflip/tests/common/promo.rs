@@ -0,0 +1,168 @@
+use super::*;
+
+pub fn promo_credit(player: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[player.as_ref(), PROMO_CREDIT_SEED.as_bytes()],
+        &coinflip::ID,
+    )
+    .0
+}
+
+pub fn play_game_with_promo_credit_ix(
+    owner: &Pubkey,
+    operator: &Pubkey,
+    is_head: bool,
+    bet_amount: u64,
+    game_session_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::PlayGameWithPromoCredit {
+            owner: *owner,
+            operator: *operator,
+            promo_credit: promo_credit(owner),
+            player_pool: player_pool(owner, game_session_id),
+            global_authority: global_authority(),
+            casino_vault: casino_vault(),
+            game_vault: game_vault(owner, game_session_id),
+            player_limits: player_limits(owner),
+            access_entry: access_entry(owner),
+            attestation: attestation(owner),
+            player_stats: player_stats(owner),
+            vip_config: vip_config(),
+            tournament: None,
+            tournament_entry: None,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::PlayGameWithPromoCredit {
+            is_head,
+            bet_amount,
+            game_session_id,
+        }
+        .data(),
+    }
+}
+
+pub fn issue_promo_credit_ix(
+    operator: &Pubkey,
+    player: &Pubkey,
+    amount: u64,
+    wagering_requirement: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::IssuePromoCredit {
+            operator: *operator,
+            player: *player,
+            global_authority: global_authority(),
+            promo_credit: promo_credit(player),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::IssuePromoCredit {
+            amount,
+            wagering_requirement,
+        }
+        .data(),
+    }
+}
+
+pub fn claim_promo_reward_ix(
+    operator: &Pubkey,
+    player: &Pubkey,
+    game_session_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::ClaimPromoReward {
+            operator: *operator,
+            player: *player,
+            player_pool: player_pool(player, game_session_id),
+            global_authority: global_authority(),
+            casino_vault: casino_vault(),
+            game_vault: game_vault(player, game_session_id),
+            promo_credit: promo_credit(player),
+            tournament: None,
+            tournament_entry: None,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::ClaimPromoReward { game_session_id }.data(),
+    }
+}
+
+pub fn withdraw_promo_winnings_ix(player: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::WithdrawPromoWinnings {
+            player: *player,
+            promo_credit: promo_credit(player),
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::WithdrawPromoWinnings {}.data(),
+    }
+}
+
+impl Casino {
+    pub fn promo_credit(&self, player: &Pubkey) -> PromoCredit {
+        let account = self
+            .svm
+            .borrow()
+            .get_account(&promo_credit(player))
+            .unwrap();
+        PromoCredit::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub fn issue_promo_credit(
+        &self,
+        player: &Pubkey,
+        amount: u64,
+        wagering_requirement: u64,
+    ) -> TransactionResult {
+        self.send(
+            &[issue_promo_credit_ix(
+                &self.operator.pubkey(),
+                player,
+                amount,
+                wagering_requirement,
+            )],
+            &[&self.operator],
+        )
+    }
+
+    pub fn play_game_with_promo_credit(
+        &self,
+        player: &Keypair,
+        is_head: bool,
+        bet_amount: u64,
+        game_session_id: u64,
+    ) -> TransactionResult {
+        self.send(
+            &[play_game_with_promo_credit_ix(
+                &player.pubkey(),
+                &self.operator.pubkey(),
+                is_head,
+                bet_amount,
+                game_session_id,
+            )],
+            &[player, &self.operator],
+        )
+    }
+
+    pub fn claim_promo_reward(&self, player: &Pubkey, game_session_id: u64) -> TransactionResult {
+        self.send(
+            &[claim_promo_reward_ix(
+                &self.operator.pubkey(),
+                player,
+                game_session_id,
+            )],
+            &[&self.operator],
+        )
+    }
+
+    pub fn withdraw_promo_winnings(&self, player: &Keypair) -> TransactionResult {
+        self.send(&[withdraw_promo_winnings_ix(&player.pubkey())], &[player])
+    }
+}
@@ -0,0 +1,150 @@
+use super::*;
+
+pub fn randomness_requester() -> Pubkey {
+    Pubkey::find_program_address(&[RANDOMNESS_REQUESTER_SEED.as_bytes()], &coinflip::ID).0
+}
+
+/// The mock provider's account for the round of `player`'s game holding `nonce`.
+pub fn randomness_request(player: &Pubkey, game_session_id: u64, nonce: u64) -> Pubkey {
+    let seed = randomness_seed(&player_pool(player, game_session_id), nonce);
+    Pubkey::find_program_address(
+        &[
+            mock_randomness::REQUEST_SEED.as_bytes(),
+            randomness_requester().as_ref(),
+            &seed,
+        ],
+        &mock_randomness::ID,
+    )
+    .0
+}
+
+/// Remaining accounts of `play_game` and `double_bet` when the mock provider
+/// generates the outcome.
+pub fn provider_accounts(player: &Pubkey, game_session_id: u64, nonce: u64) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(mock_randomness::ID, false),
+        AccountMeta::new_readonly(randomness_requester(), false),
+        AccountMeta::new(randomness_request(player, game_session_id, nonce), false),
+    ]
+}
+
+/// Mock provider fulfilling `request` for `player`'s game, calling back
+/// `consume_randomness` with the accounts the game registered.
+pub fn fulfill_ix(
+    fulfiller: &Pubkey,
+    request: &Pubkey,
+    operator: &Pubkey,
+    player: &Pubkey,
+    game_session_id: u64,
+    randomness: [u8; 32],
+) -> Instruction {
+    let mut accounts = mock_randomness::accounts::Fulfill {
+        fulfiller: *fulfiller,
+        request: *request,
+        provider_authority: coinflip::randomness::provider_authority(&mock_randomness::ID),
+        callback_program: coinflip::ID,
+    }
+    .to_account_metas(None);
+    accounts.extend([
+        AccountMeta::new(*operator, false),
+        AccountMeta::new(*player, false),
+        AccountMeta::new(global_authority(), false),
+        AccountMeta::new(player_pool(player, game_session_id), false),
+        AccountMeta::new(game_vault(player, game_session_id), false),
+        AccountMeta::new(casino_vault(), false),
+        AccountMeta::new(player_stats(player), false),
+        AccountMeta::new_readonly(vip_config(), false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ]);
+
+    Instruction {
+        program_id: mock_randomness::ID,
+        accounts,
+        data: mock_randomness::instruction::Fulfill { randomness }.data(),
+    }
+}
+
+impl Casino {
+    /// A casino whose outcomes come from the mock randomness provider.
+    pub fn with_mock_provider() -> Self {
+        let casino = Self::new();
+        casino
+            .svm
+            .borrow_mut()
+            .add_program_from_file(mock_randomness::ID, MOCK_RANDOMNESS_PATH)
+            .expect("run `anchor build` before the integration tests");
+        casino
+            .send(
+                &[set_authority_ix(
+                    &casino.super_admin.pubkey(),
+                    coinflip::instruction::SetRandomnessProvider {
+                        new_randomness_provider: mock_randomness::ID,
+                    },
+                )],
+                &[&casino.super_admin],
+            )
+            .unwrap();
+        casino
+    }
+
+    /// `play_game` on a casino using the mock provider.
+    pub fn play_game_with_provider(
+        &self,
+        player: &Keypair,
+        is_head: bool,
+        bet_amount: u64,
+        game_session_id: u64,
+    ) -> TransactionResult {
+        let nonce = self.global_pool().game_nonce;
+        let mut ix = play_game_ix(
+            &player.pubkey(),
+            &self.operator.pubkey(),
+            is_head,
+            bet_amount,
+            game_session_id,
+        );
+        ix.accounts
+            .extend(provider_accounts(&player.pubkey(), game_session_id, nonce));
+        self.send(&[ix], &[player, &self.operator])
+    }
+
+    /// `double_bet` on a casino using the mock provider.
+    pub fn double_bet_with_provider(
+        &self,
+        player: &Keypair,
+        is_head: bool,
+        game_session_id: u64,
+    ) -> TransactionResult {
+        let nonce = self.global_pool().game_nonce;
+        let mut ix = double_bet_ix(
+            &player.pubkey(),
+            &self.operator.pubkey(),
+            is_head,
+            game_session_id,
+        );
+        ix.accounts
+            .extend(provider_accounts(&player.pubkey(), game_session_id, nonce));
+        self.send(&[ix], &[player, &self.operator])
+    }
+
+    /// Fulfill the pending request of `player`'s game with `randomness`.
+    pub fn fulfill(
+        &self,
+        player: &Pubkey,
+        game_session_id: u64,
+        randomness: [u8; 32],
+    ) -> TransactionResult {
+        let nonce = self.player_pool(player, game_session_id).unwrap().nonce;
+        self.send(
+            &[fulfill_ix(
+                &self.payer.pubkey(),
+                &randomness_request(player, game_session_id, nonce),
+                &self.operator.pubkey(),
+                player,
+                game_session_id,
+                randomness,
+            )],
+            &[],
+        )
+    }
+}
@@ -0,0 +1,187 @@
+use super::*;
+
+pub fn session_key(owner: &Pubkey, key: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), SESSION_KEY_SEED.as_bytes(), key.as_ref()],
+        &coinflip::ID,
+    )
+    .0
+}
+
+pub fn create_session_key_ix(
+    owner: &Pubkey,
+    key: &Pubkey,
+    spending_cap: u64,
+    expires_at: i64,
+    allowed: u8,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::CreateSessionKey {
+            owner: *owner,
+            session_key: session_key(owner, key),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::CreateSessionKey {
+            key: *key,
+            spending_cap,
+            expires_at,
+            allowed,
+        }
+        .data(),
+    }
+}
+
+pub fn revoke_session_key_ix(owner: &Pubkey, key: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::RevokeSessionKey {
+            owner: *owner,
+            session_key: session_key(owner, key),
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::RevokeSessionKey { key: *key }.data(),
+    }
+}
+
+pub fn play_game_with_session_key_ix(
+    signer: &Pubkey,
+    owner: &Pubkey,
+    operator: &Pubkey,
+    is_head: bool,
+    bet_amount: u64,
+    game_session_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::PlayGameWithSessionKey {
+            signer: *signer,
+            owner: *owner,
+            session_key: session_key(owner, signer),
+            operator: *operator,
+            player_balance: player_balance(owner),
+            player_pool: player_pool(owner, game_session_id),
+            global_authority: global_authority(),
+            casino_vault: casino_vault(),
+            game_vault: game_vault(owner, game_session_id),
+            player_limits: player_limits(owner),
+            access_entry: access_entry(owner),
+            attestation: attestation(owner),
+            player_stats: player_stats(owner),
+            vip_config: vip_config(),
+            jackpot: jackpot(),
+            tournament: None,
+            tournament_entry: None,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::PlayGameWithSessionKey {
+            is_head,
+            bet_amount,
+            game_session_id,
+        }
+        .data(),
+    }
+}
+
+pub fn double_bet_with_session_key_ix(
+    signer: &Pubkey,
+    owner: &Pubkey,
+    operator: &Pubkey,
+    is_head: bool,
+    game_session_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::DoubleBetWithSessionKey {
+            signer: *signer,
+            owner: *owner,
+            session_key: session_key(owner, signer),
+            operator: *operator,
+            player_pool: player_pool(owner, game_session_id),
+            global_authority: global_authority(),
+            casino_vault: casino_vault(),
+            game_vault: game_vault(owner, game_session_id),
+            player_limits: player_limits(owner),
+            player_stats: player_stats(owner),
+            vip_config: vip_config(),
+            tournament: None,
+            tournament_entry: None,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::DoubleBetWithSessionKey {
+            is_head,
+            game_session_id,
+        }
+        .data(),
+    }
+}
+
+pub fn claim_reward_with_session_key_ix(
+    signer: &Pubkey,
+    owner: &Pubkey,
+    operator: &Pubkey,
+    game_session_id: u64,
+) -> Instruction {
+    claim_reward_with_session_key_in_tournament_ix(signer, owner, operator, None, game_session_id)
+}
+
+/// `claim_reward_with_session_key` scored for `owner`'s entry in tournament `tournament_id`.
+pub fn claim_reward_with_session_key_in_tournament_ix(
+    signer: &Pubkey,
+    owner: &Pubkey,
+    operator: &Pubkey,
+    tournament_id: Option<u64>,
+    game_session_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::ClaimRewardWithSessionKey {
+            signer: *signer,
+            owner: *owner,
+            session_key: session_key(owner, signer),
+            operator: *operator,
+            player_pool: player_pool(owner, game_session_id),
+            global_authority: global_authority(),
+            game_vault: game_vault(owner, game_session_id),
+            jackpot: jackpot(),
+            tournament: tournament_id.map(tournament),
+            tournament_entry: tournament_id.map(|id| tournament_entry(id, owner)),
+            player_limits: player_limits(owner),
+            player_stats: player_stats(owner),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::ClaimRewardWithSessionKey { game_session_id }.data(),
+    }
+}
+
+impl Casino {
+    pub fn session_key(&self, owner: &Pubkey, key: &Pubkey) -> Option<SessionKey> {
+        let account = self.svm.borrow().get_account(&session_key(owner, key))?;
+        SessionKey::try_deserialize(&mut account.data.as_slice()).ok()
+    }
+
+    /// Authorize `key` to sign for `owner` for an hour.
+    pub fn create_session_key(
+        &self,
+        owner: &Keypair,
+        key: &Pubkey,
+        spending_cap: u64,
+        allowed: u8,
+    ) -> TransactionResult {
+        self.send(
+            &[create_session_key_ix(
+                &owner.pubkey(),
+                key,
+                spending_cap,
+                self.now() + 3600,
+                allowed,
+            )],
+            &[owner],
+        )
+    }
+}
@@ -0,0 +1,91 @@
+use super::*;
+
+/// The Ed25519 precompile instruction carrying `signer`'s signature over a
+/// settlement, to be placed right before `settle_with_signature_ix`.
+pub fn signed_settlement_ix(
+    signer: &Keypair,
+    player: &Pubkey,
+    game_session_id: u64,
+    round_id: u8,
+    is_win: bool,
+    nonce: u64,
+) -> Instruction {
+    let message = settlement_message(player, game_session_id, round_id, is_win, nonce);
+    let signature = signer.sign_message(&message);
+
+    solana_ed25519_program::new_ed25519_instruction_with_signature(
+        &message,
+        &signature.into(),
+        &signer.pubkey().to_bytes(),
+    )
+}
+
+pub fn settle_with_signature_ix(
+    operator: &Pubkey,
+    owner: &Pubkey,
+    round_id: u8,
+    is_win: bool,
+    game_session_id: u64,
+    nonce: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::SettleWithSignature {
+            operator: *operator,
+            owner: *owner,
+            global_authority: global_authority(),
+            player_pool: player_pool(owner, game_session_id),
+            game_vault: game_vault(owner, game_session_id),
+            casino_vault: casino_vault(),
+            instructions: sysvar::instructions::ID,
+            player_stats: player_stats(owner),
+            vip_config: vip_config(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::SettleWithSignature {
+            round_id,
+            is_win,
+            game_session_id,
+            nonce,
+        }
+        .data(),
+    }
+}
+
+impl Casino {
+    /// Settle the round in progress with a result signed by the operator but
+    /// submitted by `submitter`.
+    pub fn settle_with_signature(
+        &self,
+        submitter: &Keypair,
+        player: &Pubkey,
+        round_id: u8,
+        is_win: bool,
+        game_session_id: u64,
+    ) -> TransactionResult {
+        let nonce = self.player_pool(player, game_session_id).unwrap().nonce;
+        self.send_with_payer(
+            submitter,
+            &[
+                signed_settlement_ix(
+                    &self.operator,
+                    player,
+                    game_session_id,
+                    round_id,
+                    is_win,
+                    nonce,
+                ),
+                settle_with_signature_ix(
+                    &self.operator.pubkey(),
+                    player,
+                    round_id,
+                    is_win,
+                    game_session_id,
+                    nonce,
+                ),
+            ],
+            &[],
+        )
+    }
+}
@@ -0,0 +1,57 @@
+use super::*;
+
+pub fn play_game_with_slot_hash_ix(
+    owner: &Pubkey,
+    operator: &Pubkey,
+    is_head: bool,
+    bet_amount: u64,
+    game_session_id: u64,
+    player_seed: [u8; 32],
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        data: coinflip::instruction::PlayGameWithSlotHash {
+            is_head,
+            bet_amount,
+            game_session_id,
+            player_seed,
+        }
+        .data(),
+        ..play_game_ix(owner, operator, is_head, bet_amount, game_session_id)
+    }
+}
+
+pub fn settle_with_slot_hash_ix(
+    operator: &Pubkey,
+    owner: &Pubkey,
+    game_session_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::SettleWithSlotHash {
+            operator: *operator,
+            owner: *owner,
+            global_authority: global_authority(),
+            player_pool: player_pool(owner, game_session_id),
+            game_vault: game_vault(owner, game_session_id),
+            casino_vault: casino_vault(),
+            slot_hashes: sysvar::slot_hashes::ID,
+            player_stats: player_stats(owner),
+            vip_config: vip_config(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::SettleWithSlotHash { game_session_id }.data(),
+    }
+}
+
+impl Casino {
+    pub fn warp_to_slot(&self, slot: u64) {
+        self.svm.borrow_mut().warp_to_slot(slot);
+    }
+
+    /// Replace the SlotHashes sysvar with `entries`.
+    pub fn set_slot_hashes(&self, entries: &[(u64, Hash)]) {
+        self.svm.borrow_mut().set_sysvar(&SlotHashes::new(entries));
+    }
+}
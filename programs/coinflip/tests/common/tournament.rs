@@ -0,0 +1,278 @@
+use super::*;
+
+pub fn tournament(tournament_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[TOURNAMENT_SEED.as_bytes(), &tournament_id.to_be_bytes()],
+        &coinflip::ID,
+    )
+    .0
+}
+
+pub fn tournament_entry(tournament_id: u64, player: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            tournament(tournament_id).as_ref(),
+            TOURNAMENT_ENTRY_SEED.as_bytes(),
+            player.as_ref(),
+        ],
+        &coinflip::ID,
+    )
+    .0
+}
+
+/// `play_game` scored for `owner`'s entry in tournament `tournament_id`.
+pub fn play_game_in_tournament_ix(
+    owner: &Pubkey,
+    operator: &Pubkey,
+    tournament_id: Option<u64>,
+    is_head: bool,
+    bet_amount: u64,
+    game_session_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::PlayGame {
+            owner: *owner,
+            operator: *operator,
+            player_pool: player_pool(owner, game_session_id),
+            global_authority: global_authority(),
+            casino_vault: casino_vault(),
+            game_vault: game_vault(owner, game_session_id),
+            jackpot: jackpot(),
+            tournament: tournament_id.map(tournament),
+            tournament_entry: tournament_id.map(|id| tournament_entry(id, owner)),
+            player_limits: player_limits(owner),
+            access_entry: access_entry(owner),
+            attestation: attestation(owner),
+            player_stats: player_stats(owner),
+            vip_config: vip_config(),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::PlayGame {
+            is_head,
+            bet_amount,
+            game_session_id,
+        }
+        .data(),
+    }
+}
+
+/// `claim_reward` scored for `player`'s entry in tournament `tournament_id`.
+pub fn claim_reward_in_tournament_ix(
+    operator: &Pubkey,
+    player: &Pubkey,
+    tournament_id: Option<u64>,
+    game_session_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::ClaimReward {
+            operator: *operator,
+            player: *player,
+            player_pool: player_pool(player, game_session_id),
+            global_authority: global_authority(),
+            casino_vault: casino_vault(),
+            game_vault: game_vault(player, game_session_id),
+            jackpot: jackpot(),
+            tournament: tournament_id.map(tournament),
+            tournament_entry: tournament_id.map(|id| tournament_entry(id, player)),
+            player_limits: player_limits(player),
+            player_stats: player_stats(player),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::ClaimReward { game_session_id }.data(),
+    }
+}
+
+pub fn create_tournament_ix(
+    admin: &Pubkey,
+    tournament_id: u64,
+    start_at: i64,
+    end_at: i64,
+    entry_fee: u64,
+    scoring: u8,
+    prize_split: Vec<u16>,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::CreateTournament {
+            admin: *admin,
+            global_authority: global_authority(),
+            tournament: tournament(tournament_id),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::CreateTournament {
+            tournament_id,
+            start_at,
+            end_at,
+            entry_fee,
+            scoring,
+            prize_split,
+        }
+        .data(),
+    }
+}
+
+pub fn fund_tournament_ix(sponsor: &Pubkey, tournament_id: u64, amount: u64) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::FundTournament {
+            sponsor: *sponsor,
+            tournament: tournament(tournament_id),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::FundTournament {
+            tournament_id,
+            amount,
+        }
+        .data(),
+    }
+}
+
+pub fn enter_tournament_ix(player: &Pubkey, tournament_id: u64) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::EnterTournament {
+            player: *player,
+            tournament: tournament(tournament_id),
+            tournament_entry: tournament_entry(tournament_id, player),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::EnterTournament { tournament_id }.data(),
+    }
+}
+
+pub fn rank_tournament_entry_ix(player: &Pubkey, tournament_id: u64) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::RankTournamentEntry {
+            player: *player,
+            tournament: tournament(tournament_id),
+            tournament_entry: tournament_entry(tournament_id, player),
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::RankTournamentEntry { tournament_id }.data(),
+    }
+}
+
+/// Finalizes the tournament, paying `winners` in ranking order.
+pub fn finalize_tournament_ix(tournament_id: u64, winners: &[Pubkey]) -> Instruction {
+    let mut accounts = coinflip::accounts::FinalizeTournament {
+        tournament: tournament(tournament_id),
+        casino_vault: casino_vault(),
+    }
+    .to_account_metas(None);
+    accounts.extend(
+        winners
+            .iter()
+            .map(|winner| AccountMeta::new(*winner, false)),
+    );
+    Instruction {
+        program_id: coinflip::ID,
+        accounts,
+        data: coinflip::instruction::FinalizeTournament { tournament_id }.data(),
+    }
+}
+
+impl Casino {
+    pub fn tournament(&self, tournament_id: u64) -> Tournament {
+        let account = self
+            .svm
+            .borrow()
+            .get_account(&tournament(tournament_id))
+            .unwrap();
+        Tournament::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub fn tournament_entry(&self, tournament_id: u64, player: &Pubkey) -> TournamentEntry {
+        let account = self
+            .svm
+            .borrow()
+            .get_account(&tournament_entry(tournament_id, player))
+            .unwrap();
+        TournamentEntry::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    /// Prize pool of the tournament, what it holds above its rent.
+    pub fn prize_pool(&self, tournament_id: u64) -> u64 {
+        let rent = self
+            .svm
+            .borrow()
+            .minimum_balance_for_rent_exemption(8 + Tournament::DATA_SIZE);
+        self.balance(&tournament(tournament_id))
+            .saturating_sub(rent)
+    }
+
+    /// Tournament starting now and running for an hour.
+    pub fn create_tournament(
+        &self,
+        tournament_id: u64,
+        entry_fee: u64,
+        scoring: u8,
+        prize_split: Vec<u16>,
+    ) -> TransactionResult {
+        let now = self.now();
+        self.send(
+            &[create_tournament_ix(
+                &self.update.pubkey(),
+                tournament_id,
+                now,
+                now + 3600,
+                entry_fee,
+                scoring,
+                prize_split,
+            )],
+            &[&self.update],
+        )
+    }
+
+    pub fn enter_tournament(&self, player: &Keypair, tournament_id: u64) -> TransactionResult {
+        self.send(
+            &[enter_tournament_ix(&player.pubkey(), tournament_id)],
+            &[player],
+        )
+    }
+
+    pub fn play_tournament_game(
+        &self,
+        player: &Keypair,
+        tournament_id: u64,
+        bet_amount: u64,
+        game_session_id: u64,
+    ) -> TransactionResult {
+        self.send(
+            &[play_game_in_tournament_ix(
+                &player.pubkey(),
+                &self.operator.pubkey(),
+                Some(tournament_id),
+                true,
+                bet_amount,
+                game_session_id,
+            )],
+            &[player, &self.operator],
+        )
+    }
+
+    pub fn claim_tournament_reward(
+        &self,
+        player: &Pubkey,
+        tournament_id: u64,
+        game_session_id: u64,
+    ) -> TransactionResult {
+        self.send(
+            &[claim_reward_in_tournament_ix(
+                &self.operator.pubkey(),
+                player,
+                Some(tournament_id),
+                game_session_id,
+            )],
+            &[&self.operator],
+        )
+    }
+}
@@ -0,0 +1,51 @@
+use super::*;
+
+pub fn vip_config() -> Pubkey {
+    Pubkey::find_program_address(&[VIP_CONFIG_SEED.as_bytes()], &coinflip::ID).0
+}
+
+pub fn set_vip_config_ix(admin: &Pubkey, tiers: Vec<VipTier>) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::SetVipConfig {
+            admin: *admin,
+            global_authority: global_authority(),
+            vip_config: vip_config(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::SetVipConfig { tiers }.data(),
+    }
+}
+
+pub fn claim_rakeback_ix(player: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::ClaimRakeback {
+            player: *player,
+            player_stats: player_stats(player),
+            casino_vault: casino_vault(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::ClaimRakeback {}.data(),
+    }
+}
+
+impl Casino {
+    pub fn vip_config(&self) -> VipConfig {
+        let account = self.svm.borrow().get_account(&vip_config()).unwrap();
+        VipConfig::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub fn set_vip_config(&self, tiers: Vec<VipTier>) -> TransactionResult {
+        self.send(
+            &[set_vip_config_ix(&self.update.pubkey(), tiers)],
+            &[&self.update],
+        )
+    }
+
+    pub fn claim_rakeback(&self, player: &Keypair) -> TransactionResult {
+        self.send(&[claim_rakeback_ix(&player.pubkey())], &[player])
+    }
+}
@@ -0,0 +1,210 @@
+use super::*;
+
+pub fn withdrawal_config() -> Pubkey {
+    Pubkey::find_program_address(&[WITHDRAWAL_CONFIG_SEED.as_bytes()], &coinflip::ID).0
+}
+
+pub fn withdrawal_proposal(proposal_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            WITHDRAWAL_PROPOSAL_SEED.as_bytes(),
+            &proposal_id.to_be_bytes(),
+        ],
+        &coinflip::ID,
+    )
+    .0
+}
+
+pub fn withdraw_ix(financial_admin: &Pubkey, recipient: &Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::Withdraw {
+            financial_admin: *financial_admin,
+            recipient: *recipient,
+            global_authority: global_authority(),
+            casino_vault: casino_vault(),
+            withdrawal_config: withdrawal_config(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::Withdraw { amount }.data(),
+    }
+}
+
+pub fn set_withdrawal_approvers_ix(
+    admin: &Pubkey,
+    approvers: Vec<Pubkey>,
+    threshold: u8,
+    proposal_timeout: i64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::SetWithdrawalApprovers {
+            admin: *admin,
+            global_authority: global_authority(),
+            withdrawal_config: withdrawal_config(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::SetWithdrawalApprovers {
+            approvers,
+            threshold,
+            proposal_timeout,
+        }
+        .data(),
+    }
+}
+
+/// Proposes a withdrawal that takes the config's next id, `proposal_id`.
+pub fn propose_withdrawal_ix(
+    financial_admin: &Pubkey,
+    proposal_id: u64,
+    recipient: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::ProposeWithdrawal {
+            financial_admin: *financial_admin,
+            global_authority: global_authority(),
+            withdrawal_config: withdrawal_config(),
+            proposal: withdrawal_proposal(proposal_id),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::ProposeWithdrawal {
+            recipient: *recipient,
+            amount,
+        }
+        .data(),
+    }
+}
+
+pub fn approve_withdrawal_ix(approver: &Pubkey, proposal_id: u64) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::ApproveWithdrawal {
+            approver: *approver,
+            withdrawal_config: withdrawal_config(),
+            proposal: withdrawal_proposal(proposal_id),
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::ApproveWithdrawal { proposal_id }.data(),
+    }
+}
+
+pub fn execute_withdrawal_ix(
+    signer: &Pubkey,
+    proposer: &Pubkey,
+    recipient: &Pubkey,
+    proposal_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::ExecuteWithdrawal {
+            signer: *signer,
+            proposer: *proposer,
+            recipient: *recipient,
+            withdrawal_config: withdrawal_config(),
+            proposal: withdrawal_proposal(proposal_id),
+            casino_vault: casino_vault(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::ExecuteWithdrawal { proposal_id }.data(),
+    }
+}
+
+pub fn cancel_withdrawal_ix(signer: &Pubkey, proposer: &Pubkey, proposal_id: u64) -> Instruction {
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::CancelWithdrawal {
+            signer: *signer,
+            proposer: *proposer,
+            global_authority: global_authority(),
+            withdrawal_config: withdrawal_config(),
+            proposal: withdrawal_proposal(proposal_id),
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::CancelWithdrawal { proposal_id }.data(),
+    }
+}
+
+impl Casino {
+    pub fn withdrawal_config(&self) -> WithdrawalConfig {
+        let account = self.svm.borrow().get_account(&withdrawal_config()).unwrap();
+        WithdrawalConfig::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub fn withdrawal_proposal(&self, proposal_id: u64) -> Option<WithdrawalProposal> {
+        let account = self
+            .svm
+            .borrow()
+            .get_account(&withdrawal_proposal(proposal_id))?;
+        WithdrawalProposal::try_deserialize(&mut account.data.as_slice()).ok()
+    }
+
+    pub fn set_withdrawal_approvers(
+        &self,
+        approvers: &[&Keypair],
+        threshold: u8,
+        proposal_timeout: i64,
+    ) -> TransactionResult {
+        self.send(
+            &[set_withdrawal_approvers_ix(
+                &self.super_admin.pubkey(),
+                approvers.iter().map(|approver| approver.pubkey()).collect(),
+                threshold,
+                proposal_timeout,
+            )],
+            &[&self.super_admin],
+        )
+    }
+
+    /// Proposes a withdrawal from the finance authority and returns its id.
+    pub fn propose_withdrawal(&self, recipient: &Pubkey, amount: u64) -> u64 {
+        let proposal_id = self.withdrawal_config().next_proposal_id;
+        self.send(
+            &[propose_withdrawal_ix(
+                &self.finance.pubkey(),
+                proposal_id,
+                recipient,
+                amount,
+            )],
+            &[&self.finance],
+        )
+        .unwrap();
+        proposal_id
+    }
+
+    pub fn approve_withdrawal(&self, approver: &Keypair, proposal_id: u64) -> TransactionResult {
+        self.send(
+            &[approve_withdrawal_ix(&approver.pubkey(), proposal_id)],
+            &[approver],
+        )
+    }
+
+    pub fn execute_withdrawal(&self, signer: &Keypair, proposal_id: u64) -> TransactionResult {
+        let recipient = self.withdrawal_proposal(proposal_id).unwrap().recipient;
+        self.send(
+            &[execute_withdrawal_ix(
+                &signer.pubkey(),
+                &self.finance.pubkey(),
+                &recipient,
+                proposal_id,
+            )],
+            &[signer],
+        )
+    }
+
+    pub fn cancel_withdrawal(&self, signer: &Keypair, proposal_id: u64) -> TransactionResult {
+        self.send(
+            &[cancel_withdrawal_ix(
+                &signer.pubkey(),
+                &self.finance.pubkey(),
+                proposal_id,
+            )],
+            &[signer],
+        )
+    }
+}
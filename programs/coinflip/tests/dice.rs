@@ -0,0 +1,152 @@
+mod common;
+
+use coinflip::{account::GameStatus, constants::RTP, error::GameError, math::win_amount_at_chance};
+use common::*;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signer::Signer};
+
+const BET: u64 = LAMPORTS_PER_SOL / 5;
+const SESSION: u64 = 1;
+const TARGET: u8 = 25;
+
+#[test]
+fn play_dice_opens_a_dice_round() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    casino.play_dice(&player, TARGET, BET, SESSION).unwrap();
+
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert_eq!(pool.dice_target, TARGET);
+    assert_eq!(pool.win_odds(), (TARGET as u64, 100));
+    assert!(pool.status == GameStatus::Processing);
+    assert_eq!(casino.balance(&game_vault(&player.pubkey(), SESSION)), BET);
+}
+
+#[test]
+fn dice_win_pays_rtp_over_win_chance() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_dice(&player, TARGET, BET, SESSION).unwrap();
+
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+
+    assert_eq!(
+        casino.balance(&game_vault(&player.pubkey(), SESSION)),
+        BET * RTP / TARGET as u64
+    );
+}
+
+#[test]
+fn dice_loss_goes_to_the_casino() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_dice(&player, TARGET, BET, SESSION).unwrap();
+
+    casino
+        .set_result(&player.pubkey(), 1, false, SESSION)
+        .unwrap();
+
+    assert_eq!(casino.balance(&casino_vault()), CASINO_FUNDS + BET);
+    assert_eq!(casino.balance(&player_pool(&player.pubkey(), SESSION)), 0);
+}
+
+#[test]
+fn double_bet_keeps_the_target() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_dice(&player, 50, BET, SESSION).unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+
+    casino.double_bet(&player, true, SESSION).unwrap();
+    casino
+        .set_result(&player.pubkey(), 2, true, SESSION)
+        .unwrap();
+
+    let first = win_amount_at_chance(BET, RTP, 50).unwrap();
+    assert_eq!(
+        casino.balance(&game_vault(&player.pubkey(), SESSION)),
+        win_amount_at_chance(first, RTP, 50).unwrap()
+    );
+    assert_eq!(
+        casino
+            .player_pool(&player.pubkey(), SESSION)
+            .unwrap()
+            .dice_target,
+        50
+    );
+}
+
+#[test]
+fn rejects_targets_out_of_range() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    for target in [0, 100] {
+        assert_game_error(
+            casino.play_dice(&player, target, BET, SESSION),
+            GameError::InvalidDiceTarget,
+        );
+    }
+}
+
+#[test]
+fn rejects_targets_paying_no_more_than_the_stake() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    // At the RTP a roll under the RTP pays back exactly the stake
+    for target in [RTP as u8, 99] {
+        assert_game_error(
+            casino.play_dice(&player, target, BET, SESSION),
+            GameError::PayoutBelowStake,
+        );
+    }
+    casino
+        .play_dice(&player, RTP as u8 - 1, BET, SESSION)
+        .unwrap();
+}
+
+#[test]
+fn max_win_applies_the_dice_multiplier() {
+    let casino = Casino::new();
+    let player = casino.new_player(20 * LAMPORTS_PER_SOL);
+    // A coin flip at this stake is allowed, rolling under 1 would pay 95x
+    let bet = 2 * LAMPORTS_PER_SOL;
+    casino.play_game(&player, true, bet, SESSION).unwrap();
+
+    assert_game_error(
+        casino.play_dice(&player, 1, bet, SESSION + 1),
+        GameError::InvalidBetAmountMaxWinAmountViolation,
+    );
+}
+
+#[test]
+fn provider_randomness_rolls_the_dice() {
+    let casino = Casino::with_mock_provider();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    let nonce = casino.global_pool().game_nonce;
+    let mut ix = play_dice_ix(
+        &player.pubkey(),
+        &casino.operator.pubkey(),
+        TARGET,
+        BET,
+        SESSION,
+    );
+    ix.accounts
+        .extend(provider_accounts(&player.pubkey(), SESSION, nonce));
+    casino.send(&[ix], &[&player, &casino.operator]).unwrap();
+
+    // Rolls 24, just under the target
+    let mut randomness = [0; 32];
+    randomness[0] = 124;
+    casino
+        .fulfill(&player.pubkey(), SESSION, randomness)
+        .unwrap();
+
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert!(pool.status == GameStatus::Win);
+}
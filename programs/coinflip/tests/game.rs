@@ -0,0 +1,352 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use coinflip::{account::GameStatus, error::GameError};
+use common::*;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signer::Signer};
+
+const BET: u64 = LAMPORTS_PER_SOL / 5;
+const SESSION: u64 = 1;
+
+/// Every account a game moves lamports between.
+fn game_accounts(casino: &Casino, player: &Pubkey, game_session_id: u64) -> Vec<Pubkey> {
+    vec![
+        casino_vault(),
+        game_vault(player, game_session_id),
+        player_pool(player, game_session_id),
+        *player,
+        casino.operator.pubkey(),
+    ]
+}
+
+#[test]
+fn play_game_escrows_bet_and_opens_player_pool() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+
+    assert_eq!(casino.balance(&game_vault(&player.pubkey(), SESSION)), BET);
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert!(pool.status == GameStatus::Processing);
+    assert_eq!(pool.round, 1);
+    assert_eq!(pool.first_bet, BET);
+    assert_eq!(pool.player, player.pubkey());
+    assert!(pool.is_head);
+    assert_eq!(pool.game_session_id, SESSION);
+}
+
+#[test]
+fn win_double_and_claim_conserves_lamports() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    let accounts = game_accounts(&casino, &player.pubkey(), SESSION);
+    let total = casino.total(&accounts);
+
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    assert_eq!(casino.total(&accounts), total);
+
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+    assert_eq!(casino.total(&accounts), total);
+    let first_win = BET * 2 * 95 / 100;
+    assert_eq!(
+        casino.balance(&game_vault(&player.pubkey(), SESSION)),
+        first_win
+    );
+    assert!(
+        casino
+            .player_pool(&player.pubkey(), SESSION)
+            .unwrap()
+            .status
+            == GameStatus::Win
+    );
+
+    casino.double_bet(&player, false, SESSION).unwrap();
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert!(pool.status == GameStatus::Processing);
+    assert_eq!(pool.round, 2);
+    assert!(!pool.is_head);
+
+    casino
+        .set_result(&player.pubkey(), 2, true, SESSION)
+        .unwrap();
+    assert_eq!(casino.total(&accounts), total);
+    let second_win = first_win * 2 * 95 / 100;
+    assert_eq!(
+        casino.balance(&game_vault(&player.pubkey(), SESSION)),
+        second_win
+    );
+
+    let player_before = casino.balance(&player.pubkey());
+    casino.claim_reward(&player.pubkey(), SESSION).unwrap();
+    assert_eq!(casino.total(&accounts), total);
+    assert_eq!(casino.balance(&player.pubkey()), player_before + second_win);
+    assert_eq!(casino.balance(&game_vault(&player.pubkey(), SESSION)), 0);
+    assert_eq!(casino.balance(&player_pool(&player.pubkey(), SESSION)), 0);
+    assert!(casino.player_pool(&player.pubkey(), SESSION).is_none());
+
+    // The claimed session opens again
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+}
+
+#[test]
+fn loss_moves_stake_to_casino_and_refunds_pool_rent() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    let accounts = game_accounts(&casino, &player.pubkey(), SESSION);
+    let total = casino.total(&accounts);
+    let operator_before = casino.balance(&casino.operator.pubkey());
+
+    casino.play_game(&player, false, BET, SESSION).unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, false, SESSION)
+        .unwrap();
+
+    assert_eq!(casino.total(&accounts), total);
+    assert_eq!(casino.balance(&casino_vault()), CASINO_FUNDS + BET);
+    assert_eq!(casino.balance(&casino.operator.pubkey()), operator_before);
+    assert_eq!(casino.balance(&game_vault(&player.pubkey(), SESSION)), 0);
+    assert_eq!(casino.balance(&player_pool(&player.pubkey(), SESSION)), 0);
+}
+
+#[test]
+fn win_then_double_and_lose() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    let accounts = game_accounts(&casino, &player.pubkey(), SESSION);
+    let total = casino.total(&accounts);
+
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+    casino.double_bet(&player, true, SESSION).unwrap();
+    casino
+        .set_result(&player.pubkey(), 2, false, SESSION)
+        .unwrap();
+
+    assert_eq!(casino.total(&accounts), total);
+    // The casino tops up the first win and takes the whole vault back
+    let first_win = BET * 2 * 95 / 100;
+    assert_eq!(
+        casino.balance(&casino_vault()),
+        CASINO_FUNDS - (first_win - BET) + first_win
+    );
+    assert!(casino.player_pool(&player.pubkey(), SESSION).is_none());
+}
+
+#[test]
+fn session_can_be_replayed_after_a_loss() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, false, SESSION)
+        .unwrap();
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+
+    assert_eq!(
+        casino.player_pool(&player.pubkey(), SESSION).unwrap().round,
+        1
+    );
+}
+
+#[test]
+fn play_game_rejects_bet_below_minimum() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    let result = casino.play_game(
+        &player,
+        true,
+        casino.global_pool().min_bet_amount - 1,
+        SESSION,
+    );
+    assert_game_error(result, GameError::InvalidBetAmount);
+}
+
+#[test]
+fn play_game_rejects_bet_violating_max_win() {
+    let casino = Casino::new();
+    let player = casino.new_player(100 * LAMPORTS_PER_SOL);
+
+    // A 90% net gain on 20 SOL exceeds the default 10 SOL max win
+    let result = casino.play_game(&player, true, 20 * LAMPORTS_PER_SOL, SESSION);
+    assert_game_error(result, GameError::InvalidBetAmountMaxWinAmountViolation);
+}
+
+#[test]
+fn play_game_rejects_insufficient_user_balance() {
+    let casino = Casino::new();
+    let player = casino.new_player(BET);
+
+    let result = casino.play_game(&player, true, BET, SESSION);
+    assert_game_error(result, GameError::InsufficientUserBalance);
+}
+
+#[test]
+fn play_game_rejects_insufficient_casino_vault() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino
+        .send(
+            &[withdraw_ix(
+                &casino.finance.pubkey(),
+                &casino.finance.pubkey(),
+                CASINO_FUNDS - BET,
+            )],
+            &[&casino.finance],
+        )
+        .unwrap();
+
+    let result = casino.play_game(&player, true, BET, SESSION);
+    assert_game_error(result, GameError::InsufficientCasinoVault);
+}
+
+#[test]
+fn play_game_requires_operation_authority() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    let impostor = casino.new_player(LAMPORTS_PER_SOL);
+
+    let result = casino.send(
+        &[play_game_ix(
+            &player.pubkey(),
+            &impostor.pubkey(),
+            true,
+            BET,
+            SESSION,
+        )],
+        &[&player, &impostor],
+    );
+    assert_game_error(result, GameError::UnauthorizedOperator);
+}
+
+#[test]
+fn set_result_rejects_round_mismatch() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+
+    let result = casino.set_result(&player.pubkey(), 2, true, SESSION);
+    assert_game_error(result, GameError::RoundNumMismatch);
+}
+
+#[test]
+fn set_result_rejects_already_settled_round() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+
+    let vault = casino.balance(&game_vault(&player.pubkey(), SESSION));
+    let result = casino.set_result(&player.pubkey(), 1, true, SESSION);
+    assert_game_error(result, GameError::NotAllowedStatus);
+    assert_eq!(
+        casino.balance(&game_vault(&player.pubkey(), SESSION)),
+        vault
+    );
+}
+
+#[test]
+fn set_result_requires_operation_authority() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+
+    let result = casino.send(
+        &[set_result_ix(
+            &player.pubkey(),
+            &player.pubkey(),
+            1,
+            true,
+            SESSION,
+        )],
+        &[&player],
+    );
+    assert_game_error(result, GameError::UnauthorizedOperator);
+}
+
+#[test]
+fn double_bet_requires_a_win() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+
+    let result = casino.double_bet(&player, true, SESSION);
+    assert_game_error(result, GameError::NotAllowedDoubleBet);
+}
+
+#[test]
+fn double_bet_rejects_max_win_violation() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+    casino
+        .send(
+            &[set_global_pool_ix(
+                &casino.update.pubkey(),
+                coinflip::instruction::SetMaxWinAmount {
+                    new_max_win_amount: BET,
+                },
+            )],
+            &[&casino.update],
+        )
+        .unwrap();
+
+    let result = casino.double_bet(&player, true, SESSION);
+    assert_game_error(result, GameError::InvalidBetAmountMaxWinAmountViolation);
+}
+
+#[test]
+fn double_bet_is_bound_to_the_original_player() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    let other = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+
+    // `NotOriginalPlayer` is shadowed by the pool seeds, which already bind
+    // the pool to the signing owner
+    let mut ix = double_bet_ix(&other.pubkey(), &casino.operator.pubkey(), true, SESSION);
+    ix.accounts[2].pubkey = player_pool(&player.pubkey(), SESSION);
+    ix.accounts[5].pubkey = game_vault(&player.pubkey(), SESSION);
+    let result = casino.send(&[ix], &[&other, &casino.operator]);
+    assert_anchor_error(result, ErrorCode::ConstraintSeeds);
+}
+
+#[test]
+fn claim_reward_requires_a_win() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+
+    let result = casino.claim_reward(&player.pubkey(), SESSION);
+    assert_game_error(result, GameError::NotAllowedStatus);
+}
+
+#[test]
+fn claim_reward_requires_operation_authority() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+
+    let result = casino.send(
+        &[claim_reward_ix(&player.pubkey(), &player.pubkey(), SESSION)],
+        &[&player],
+    );
+    assert_game_error(result, GameError::UnauthorizedOperator);
+}
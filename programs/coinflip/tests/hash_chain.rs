@@ -0,0 +1,254 @@
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use coinflip::{
+    account::{GameStatus, PlayerPool},
+    constants::SETTLEMENT_TIMEOUT,
+    error::GameError,
+    utils::{chain_bet_commitment, chain_game_randomness, is_head_from_randomness},
+};
+use common::*;
+use solana_sdk::{hash::hash, native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer};
+
+const BET: u64 = LAMPORTS_PER_SOL / 5;
+const SESSION: u64 = 1;
+const CHAIN_LENGTH: usize = 5;
+
+/// `chain[i + 1] == sha256(chain[i])`, the last element is published.
+fn hash_chain(secret: [u8; 32]) -> Vec<[u8; 32]> {
+    let mut chain = vec![secret];
+    for _ in 0..CHAIN_LENGTH {
+        chain.push(hash(chain.last().unwrap()).to_bytes());
+    }
+    chain
+}
+
+fn publish(casino: &Casino, tail: [u8; 32], length: u64) -> litesvm::types::TransactionResult {
+    casino.send(
+        &[publish_hash_chain_ix(
+            &casino.operator.pubkey(),
+            tail,
+            length,
+        )],
+        &[&casino.operator],
+    )
+}
+
+fn chain_casino(chain: &[[u8; 32]]) -> Casino {
+    let casino = Casino::new();
+    publish(&casino, *chain.last().unwrap(), CHAIN_LENGTH as u64).unwrap();
+    casino
+}
+
+fn reveal(
+    casino: &Casino,
+    player: &Keypair,
+    reveal: [u8; 32],
+) -> litesvm::types::TransactionResult {
+    casino.send(
+        &[settle_with_reveal_ix(
+            &casino.operator.pubkey(),
+            &player.pubkey(),
+            SESSION,
+            reveal,
+        )],
+        &[],
+    )
+}
+
+/// Check the game `pending` was before `reveal` settled as the reveal says.
+fn assert_settled_by(casino: &Casino, player: &Keypair, pending: &PlayerPool, reveal: [u8; 32]) {
+    let randomness = chain_game_randomness(&reveal, pending, SESSION);
+    match casino.player_pool(&player.pubkey(), SESSION) {
+        Some(pool) => {
+            assert!(is_head_from_randomness(&randomness));
+            assert!(pool.status == GameStatus::Win);
+        }
+        None => assert!(!is_head_from_randomness(&randomness)),
+    }
+}
+
+#[test]
+fn rounds_take_successive_chain_positions() {
+    let chain = hash_chain([9; 32]);
+    let casino = chain_casino(&chain);
+    let first = casino.new_player(10 * LAMPORTS_PER_SOL);
+    let second = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    casino.play_game(&first, true, BET, SESSION).unwrap();
+    casino.play_game(&second, true, BET, SESSION).unwrap();
+
+    let pool = |player: &Keypair| casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert_eq!(pool(&first).chain_position, 1);
+    assert_eq!(pool(&second).chain_position, 2);
+    assert_eq!(casino.global_pool().hash_chain_assigned, 2);
+}
+
+#[test]
+fn rounds_commit_their_bet_before_the_reveal() {
+    let chain = hash_chain([9; 32]);
+    let casino = chain_casino(&chain);
+    let heads = casino.new_player(10 * LAMPORTS_PER_SOL);
+    let tails = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.warp_to_slot(1_000);
+    casino.play_game(&heads, true, BET, SESSION).unwrap();
+    casino.play_game(&tails, false, BET, SESSION).unwrap();
+
+    let heads = casino.player_pool(&heads.pubkey(), SESSION).unwrap();
+    let tails = casino.player_pool(&tails.pubkey(), SESSION).unwrap();
+    assert_eq!(heads.player_seed, chain_bet_commitment(&heads, 1_000));
+    assert_eq!(tails.player_seed, chain_bet_commitment(&tails, 1_000));
+    assert_ne!(heads.player_seed, tails.player_seed);
+}
+
+#[test]
+fn reveals_settle_rounds_in_order() {
+    let chain = hash_chain([9; 32]);
+    let casino = chain_casino(&chain);
+    let first = casino.new_player(10 * LAMPORTS_PER_SOL);
+    let second = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&first, true, BET, SESSION).unwrap();
+    casino.play_game(&second, true, BET, SESSION).unwrap();
+    let first_pool = casino.player_pool(&first.pubkey(), SESSION).unwrap();
+    let second_pool = casino.player_pool(&second.pubkey(), SESSION).unwrap();
+
+    reveal(&casino, &first, chain[CHAIN_LENGTH - 1]).unwrap();
+    reveal(&casino, &second, chain[CHAIN_LENGTH - 2]).unwrap();
+
+    assert_settled_by(&casino, &first, &first_pool, chain[CHAIN_LENGTH - 1]);
+    assert_settled_by(&casino, &second, &second_pool, chain[CHAIN_LENGTH - 2]);
+    let global = casino.global_pool();
+    assert_eq!(global.hash_chain_head, chain[CHAIN_LENGTH - 2]);
+    assert_eq!(global.hash_chain_revealed, 2);
+}
+
+#[test]
+fn rejects_reveal_out_of_order() {
+    let chain = hash_chain([9; 32]);
+    let casino = chain_casino(&chain);
+    let first = casino.new_player(10 * LAMPORTS_PER_SOL);
+    let second = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&first, true, BET, SESSION).unwrap();
+    casino.play_game(&second, true, BET, SESSION).unwrap();
+
+    assert_game_error(
+        reveal(&casino, &second, chain[CHAIN_LENGTH - 1]),
+        GameError::HashChainOutOfOrder,
+    );
+}
+
+#[test]
+fn rejects_value_that_is_not_the_preimage() {
+    let chain = hash_chain([9; 32]);
+    let casino = chain_casino(&chain);
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+
+    assert_game_error(
+        reveal(&casino, &player, chain[CHAIN_LENGTH - 2]),
+        GameError::InvalidReveal,
+    );
+    assert_game_error(
+        reveal(&casino, &player, chain[CHAIN_LENGTH]),
+        GameError::InvalidReveal,
+    );
+}
+
+#[test]
+fn operator_cannot_set_result_of_chain_rounds() {
+    let chain = hash_chain([9; 32]);
+    let casino = chain_casino(&chain);
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+
+    assert_game_error(
+        casino.set_result(&player.pubkey(), 1, true, SESSION),
+        GameError::SettledByHashChain,
+    );
+}
+
+#[test]
+fn new_chain_waits_for_pending_rounds() {
+    let chain = hash_chain([9; 32]);
+    let casino = chain_casino(&chain);
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+
+    assert_game_error(publish(&casino, [1; 32], 3), GameError::HashChainPending);
+
+    reveal(&casino, &player, chain[CHAIN_LENGTH - 1]).unwrap();
+    publish(&casino, [1; 32], 3).unwrap();
+    let global = casino.global_pool();
+    assert_eq!(global.hash_chain_head, [1; 32]);
+    assert_eq!(global.hash_chain_end, 4);
+}
+
+#[test]
+fn exhausted_chain_returns_rounds_to_the_operator() {
+    let chain = hash_chain([9; 32]);
+    let casino = Casino::new();
+    publish(&casino, chain[CHAIN_LENGTH], 1).unwrap();
+    let first = casino.new_player(10 * LAMPORTS_PER_SOL);
+    let second = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    casino.play_game(&first, true, BET, SESSION).unwrap();
+    casino.play_game(&second, true, BET, SESSION).unwrap();
+
+    let pool = |player: &Keypair| casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert_eq!(pool(&first).chain_position, 1);
+    assert_eq!(pool(&second).chain_position, 0);
+    casino
+        .set_result(&second.pubkey(), 1, true, SESSION)
+        .unwrap();
+}
+
+#[test]
+fn slashed_rounds_are_skipped_by_the_next_reveal() {
+    let chain = hash_chain([9; 32]);
+    let casino = chain_casino(&chain);
+    casino.post_bond(5 * LAMPORTS_PER_SOL).unwrap();
+    let first = casino.new_player(10 * LAMPORTS_PER_SOL);
+    let second = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&first, true, BET, SESSION).unwrap();
+    casino.play_game(&second, true, BET, SESSION).unwrap();
+    casino.advance_clock(SETTLEMENT_TIMEOUT + 1);
+
+    assert_game_error(
+        casino.slash_operator(&second, SESSION),
+        GameError::HashChainOutOfOrder,
+    );
+    casino.slash_operator(&first, SESSION).unwrap();
+    let global = casino.global_pool();
+    assert_eq!(global.hash_chain_revealed, 1);
+    assert_eq!(global.hash_chain_skipped, 1);
+
+    // The slashed position's own value is no longer the one to reveal
+    assert_game_error(
+        reveal(&casino, &second, chain[CHAIN_LENGTH - 1]),
+        GameError::InvalidReveal,
+    );
+    let second_pool = casino.player_pool(&second.pubkey(), SESSION).unwrap();
+    reveal(&casino, &second, chain[CHAIN_LENGTH - 2]).unwrap();
+    assert_settled_by(&casino, &second, &second_pool, chain[CHAIN_LENGTH - 2]);
+    let global = casino.global_pool();
+    assert_eq!(global.hash_chain_head, chain[CHAIN_LENGTH - 2]);
+    assert_eq!(
+        (global.hash_chain_revealed, global.hash_chain_skipped),
+        (2, 0)
+    );
+}
+
+#[test]
+fn hash_chain_requires_operator() {
+    let casino = Casino::new();
+    let player = casino.new_player(LAMPORTS_PER_SOL);
+
+    assert_game_error(
+        casino.send(
+            &[publish_hash_chain_ix(&player.pubkey(), [1; 32], 1)],
+            &[&player],
+        ),
+        GameError::UnauthorizedOperator,
+    );
+}
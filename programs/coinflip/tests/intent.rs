@@ -0,0 +1,188 @@
+mod common;
+
+use coinflip::{constants::SESSION_ALLOW_PLAY, error::GameError};
+use common::*;
+use solana_sdk::{
+    instruction::Instruction, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Keypair,
+    signer::Signer,
+};
+
+const DEPOSIT: u64 = 2 * LAMPORTS_PER_SOL;
+const BET: u64 = LAMPORTS_PER_SOL / 2;
+const SESSION: u64 = 1;
+
+fn funded_player() -> (Casino, Keypair) {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.deposit_balance(&player, DEPOSIT).unwrap();
+    (casino, player)
+}
+
+/// Intent of `player` signed by `signer`, submitted for `bet_amount` which
+/// may differ from the signed one.
+fn intent(
+    casino: &Casino,
+    signer: &Keypair,
+    player: &Pubkey,
+    signed_amount: u64,
+    bet_amount: u64,
+    expires_at: i64,
+    nonce: u64,
+) -> [Instruction; 2] {
+    [
+        signed_intent_ix(
+            signer,
+            player,
+            true,
+            signed_amount,
+            SESSION,
+            expires_at,
+            nonce,
+        ),
+        play_game_with_intent_ix(
+            &casino.operator.pubkey(),
+            player,
+            None,
+            true,
+            bet_amount,
+            SESSION,
+            expires_at,
+            nonce,
+        ),
+    ]
+}
+
+#[test]
+fn operator_submits_the_players_intent() {
+    let (casino, player) = funded_player();
+    let wallet = casino.balance(&player.pubkey());
+
+    casino
+        .play_game_with_intent(&player, &player.pubkey(), None, BET, SESSION)
+        .unwrap();
+
+    assert_eq!(casino.player_balance(&player.pubkey()), DEPOSIT - BET);
+    assert_eq!(casino.balance(&game_vault(&player.pubkey(), SESSION)), BET);
+    assert_eq!(casino.balance(&player.pubkey()), wallet);
+    assert_eq!(casino.intent_nonce(&player.pubkey()), 1);
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert_eq!(pool.player, player.pubkey());
+}
+
+#[test]
+fn intent_is_used_once() {
+    let (casino, player) = funded_player();
+    let expires_at = casino.now() + 60;
+    let signed = intent(&casino, &player, &player.pubkey(), BET, BET, expires_at, 0);
+
+    casino.send(&signed, &[&casino.operator]).unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, false, SESSION)
+        .unwrap();
+
+    // The game is gone, so only the nonce stops the intent from betting again
+    assert_game_error(
+        casino.send(&signed, &[&casino.operator]),
+        GameError::InvalidIntentNonce,
+    );
+    assert_eq!(casino.player_balance(&player.pubkey()), DEPOSIT - BET);
+}
+
+#[test]
+fn expired_intent_is_rejected() {
+    let (casino, player) = funded_player();
+    let expires_at = casino.now() + 60;
+    casino.advance_clock(61);
+
+    assert_game_error(
+        casino.send(
+            &intent(&casino, &player, &player.pubkey(), BET, BET, expires_at, 0),
+            &[&casino.operator],
+        ),
+        GameError::IntentExpired,
+    );
+}
+
+#[test]
+fn intent_must_be_signed_by_the_player_as_submitted() {
+    let (casino, player) = funded_player();
+    let expires_at = casino.now() + 60;
+    let stranger = Keypair::new();
+
+    assert_game_error(
+        casino.send(
+            &intent(
+                &casino,
+                &stranger,
+                &player.pubkey(),
+                BET,
+                BET,
+                expires_at,
+                0,
+            ),
+            &[&casino.operator],
+        ),
+        GameError::InvalidIntentSignature,
+    );
+    assert_game_error(
+        casino.send(
+            &intent(
+                &casino,
+                &player,
+                &player.pubkey(),
+                BET,
+                DEPOSIT,
+                expires_at,
+                0,
+            ),
+            &[&casino.operator],
+        ),
+        GameError::InvalidIntentSignature,
+    );
+}
+
+#[test]
+fn session_key_intents_count_against_its_cap() {
+    let (casino, player) = funded_player();
+    let key = Keypair::new();
+    casino
+        .create_session_key(&player, &key.pubkey(), BET, SESSION_ALLOW_PLAY)
+        .unwrap();
+    let delegated = Some(session_key(&player.pubkey(), &key.pubkey()));
+
+    assert_game_error(
+        casino.play_game_with_intent(&player, &player.pubkey(), delegated, BET, SESSION),
+        GameError::InvalidIntentSignature,
+    );
+
+    casino
+        .play_game_with_intent(&key, &player.pubkey(), delegated, BET, SESSION)
+        .unwrap();
+    assert_eq!(casino.player_balance(&player.pubkey()), DEPOSIT - BET);
+
+    assert_game_error(
+        casino.play_game_with_intent(&key, &player.pubkey(), delegated, BET, SESSION + 1),
+        GameError::SessionSpendingCapExceeded,
+    );
+}
+
+#[test]
+fn session_key_of_another_player_is_rejected() {
+    let (casino, player) = funded_player();
+    let other = casino.new_player(10 * LAMPORTS_PER_SOL);
+    let key = Keypair::new();
+    casino
+        .create_session_key(&other, &key.pubkey(), BET, SESSION_ALLOW_PLAY)
+        .unwrap();
+
+    assert_game_error(
+        casino.play_game_with_intent(
+            &key,
+            &player.pubkey(),
+            Some(session_key(&other.pubkey(), &key.pubkey())),
+            BET,
+            SESSION,
+        ),
+        GameError::SessionKeyNotAllowed,
+    );
+}
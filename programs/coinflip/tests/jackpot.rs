@@ -0,0 +1,200 @@
+mod common;
+
+use coinflip::{
+    constants::{JACKPOT_STREAK, MAX_JACKPOT_CONTRIBUTION, RTP},
+    error::GameError,
+    instruction::{FundJackpot, SetJackpotConfig},
+    math::win_amount,
+};
+use common::*;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer};
+
+const BET: u64 = LAMPORTS_PER_SOL / 2;
+const POT: u64 = LAMPORTS_PER_SOL;
+const SESSION: u64 = 1;
+
+/// A casino with `POT` in its jackpot, paid for a streak of `trigger_streak`.
+fn funded_jackpot(trigger_streak: u8) -> (Casino, Keypair) {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.fund_jackpot(POT).unwrap();
+    casino.set_jackpot_config(0, trigger_streak).unwrap();
+    (casino, player)
+}
+
+#[test]
+fn bets_feed_the_jackpot_from_their_stake() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.set_jackpot_config(10, JACKPOT_STREAK).unwrap();
+    let wallet = casino.balance(&player.pubkey());
+
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+
+    let contribution = BET / 100;
+    assert_eq!(casino.jackpot_balance(), contribution);
+    assert_eq!(casino.jackpot().total_contributed, contribution);
+    assert_eq!(casino.balance(&casino_vault()), CASINO_FUNDS);
+    assert_eq!(
+        casino.balance(&game_vault(&player.pubkey(), SESSION)),
+        BET - contribution
+    );
+    assert_eq!(casino.balance(&player.pubkey()), wallet - BET);
+    assert!(
+        casino
+            .player_pool(&player.pubkey(), SESSION)
+            .unwrap()
+            .jackpot_eligible
+    );
+}
+
+#[test]
+fn every_game_type_feeds_the_jackpot() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.set_jackpot_config(10, JACKPOT_STREAK).unwrap();
+    casino.deposit_balance(&player, BET).unwrap();
+
+    casino.play_dice(&player, 50, BET, 1).unwrap();
+    casino
+        .play_multi_coin(&player, 3, 2, false, BET, 2)
+        .unwrap();
+    casino
+        .play_batch(&player, vec![true, false], vec![BET / 2; 2], 3)
+        .unwrap();
+    casino
+        .play_game_from_balance(&player, true, BET, 4)
+        .unwrap();
+
+    let contribution = BET / 100;
+    assert_eq!(casino.jackpot_balance(), 4 * contribution);
+    assert_eq!(casino.balance(&casino_vault()), CASINO_FUNDS);
+    assert_eq!(casino.player_balance(&player.pubkey()), 0);
+    for session in 1..=4 {
+        assert_eq!(
+            casino.balance(&game_vault(&player.pubkey(), session)),
+            BET - contribution
+        );
+    }
+}
+
+#[test]
+fn claiming_the_trigger_streak_wins_the_jackpot() {
+    let (casino, player) = funded_jackpot(2);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+    casino.double_bet(&player, true, SESSION).unwrap();
+    casino
+        .set_result(&player.pubkey(), 2, true, SESSION)
+        .unwrap();
+    let wallet = casino.balance(&player.pubkey());
+
+    casino.claim_reward(&player.pubkey(), SESSION).unwrap();
+
+    let payout = win_amount(win_amount(BET, RTP).unwrap(), RTP).unwrap();
+    assert_eq!(casino.balance(&player.pubkey()), wallet + payout + POT);
+    assert_eq!(casino.jackpot_balance(), 0);
+    let jackpot = casino.jackpot();
+    assert_eq!(jackpot.total_paid, POT);
+    assert_eq!(jackpot.last_winner, player.pubkey());
+}
+
+#[test]
+fn shorter_streak_leaves_the_jackpot() {
+    let (casino, player) = funded_jackpot(2);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+    let wallet = casino.balance(&player.pubkey());
+
+    casino.claim_reward(&player.pubkey(), SESSION).unwrap();
+
+    assert_eq!(
+        casino.balance(&player.pubkey()),
+        wallet + win_amount(BET, RTP).unwrap()
+    );
+    assert_eq!(casino.jackpot_balance(), POT);
+}
+
+#[test]
+fn only_coin_flips_win_the_jackpot() {
+    let (casino, player) = funded_jackpot(1);
+    casino.play_dice(&player, 90, BET, SESSION).unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+
+    casino.claim_reward(&player.pubkey(), SESSION).unwrap();
+
+    assert_eq!(casino.jackpot_balance(), POT);
+}
+
+#[test]
+fn jackpot_can_be_claimed_to_the_balance() {
+    let (casino, player) = funded_jackpot(1);
+    casino.deposit_balance(&player, BET).unwrap();
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+
+    casino
+        .claim_reward_to_balance(&player.pubkey(), SESSION)
+        .unwrap();
+
+    assert_eq!(
+        casino.player_balance(&player.pubkey()),
+        BET + win_amount(BET, RTP).unwrap() + POT
+    );
+    assert_eq!(casino.jackpot_balance(), 0);
+}
+
+#[test]
+fn update_authority_governs_the_jackpot() {
+    let casino = Casino::new();
+    let jackpot = casino.jackpot();
+    assert_eq!(jackpot.contribution_rate, 0);
+    assert_eq!(jackpot.trigger_streak, JACKPOT_STREAK);
+
+    let stranger = casino.new_player(10 * LAMPORTS_PER_SOL);
+    assert_game_error(
+        casino.send(
+            &[set_jackpot_ix(
+                &stranger.pubkey(),
+                SetJackpotConfig {
+                    contribution_rate: 10,
+                    trigger_streak: 1,
+                },
+            )],
+            &[&stranger],
+        ),
+        GameError::UnauthorizedUpdateAdmin,
+    );
+    assert_game_error(
+        casino.send(
+            &[set_jackpot_ix(
+                &stranger.pubkey(),
+                FundJackpot { amount: POT },
+            )],
+            &[&stranger],
+        ),
+        GameError::UnauthorizedUpdateAdmin,
+    );
+    assert_game_error(
+        casino.set_jackpot_config(MAX_JACKPOT_CONTRIBUTION + 1, 1),
+        GameError::InvalidJackpotContribution,
+    );
+
+    casino
+        .set_jackpot_config(MAX_JACKPOT_CONTRIBUTION, 3)
+        .unwrap();
+    casino.fund_jackpot(POT).unwrap();
+
+    let jackpot = casino.jackpot();
+    assert_eq!(jackpot.contribution_rate, MAX_JACKPOT_CONTRIBUTION);
+    assert_eq!(jackpot.trigger_streak, 3);
+    assert_eq!(casino.jackpot_balance(), POT);
+}
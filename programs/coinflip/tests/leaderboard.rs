@@ -0,0 +1,242 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use coinflip::{
+    account::Leaderboard,
+    constants::{DAY, LEADERBOARD_ALL_TIME, LEADERBOARD_DAILY, LEADERBOARD_WEEKLY, RTP},
+    error::GameError,
+    math::win_amount,
+};
+use common::*;
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Keypair, signer::Signer,
+};
+
+const BET: u64 = LAMPORTS_PER_SOL / 2;
+const SESSION: u64 = 1;
+
+/// A casino with today's and the all-time leaderboard open, and `count`
+/// players with their stats open.
+fn ranked_players(count: usize) -> (Casino, Vec<Keypair>, [Pubkey; 2]) {
+    let casino = Casino::new();
+    let today = Leaderboard::period_index(LEADERBOARD_DAILY, casino.now());
+    casino.open_leaderboard(LEADERBOARD_DAILY, today).unwrap();
+    casino.open_leaderboard(LEADERBOARD_ALL_TIME, 0).unwrap();
+    let players = (0..count)
+        .map(|_| {
+            let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+            casino.open_player_stats(&player.pubkey()).unwrap();
+            player
+        })
+        .collect();
+    let boards = [
+        leaderboard(LEADERBOARD_DAILY, today),
+        leaderboard(LEADERBOARD_ALL_TIME, 0),
+    ];
+    (casino, players, boards)
+}
+
+/// Plays a game of `bet` that `player` loses, settled on `boards`.
+fn lose(casino: &Casino, player: &Keypair, bet: u64, session: u64, boards: &[Pubkey]) {
+    casino.play_game(player, true, bet, session).unwrap();
+    casino
+        .set_ranked_result(&player.pubkey(), 1, false, session, boards)
+        .unwrap();
+}
+
+/// Plays a game of `bet` that `player` wins and claims on `boards`.
+fn win(casino: &Casino, player: &Keypair, bet: u64, session: u64, boards: &[Pubkey]) {
+    casino.play_game(player, true, bet, session).unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, true, session)
+        .unwrap();
+    casino
+        .claim_ranked_reward(&player.pubkey(), session, boards)
+        .unwrap();
+}
+
+#[test]
+fn leaderboards_open_for_the_current_or_a_later_period() {
+    let casino = Casino::new();
+    // Move past the first day so there is an earlier period to reject
+    casino.advance_clock(2 * DAY);
+    let today = Leaderboard::period_index(LEADERBOARD_DAILY, casino.now());
+
+    for (period, period_index) in [
+        (3, 0),
+        (LEADERBOARD_DAILY, today - 1),
+        (LEADERBOARD_ALL_TIME, 1),
+    ] {
+        assert_game_error(
+            casino.open_leaderboard(period, period_index),
+            GameError::InvalidLeaderboard,
+        );
+    }
+
+    casino.open_leaderboard(LEADERBOARD_DAILY, today).unwrap();
+    casino
+        .open_leaderboard(LEADERBOARD_DAILY, today + 1)
+        .unwrap();
+    let this_week = Leaderboard::period_index(LEADERBOARD_WEEKLY, casino.now());
+    casino
+        .open_leaderboard(LEADERBOARD_WEEKLY, this_week)
+        .unwrap();
+
+    let board = casino.leaderboard(LEADERBOARD_DAILY, today + 1);
+    assert_eq!(board.period, LEADERBOARD_DAILY);
+    assert_eq!(board.period_index, today + 1);
+    assert!(board.by_volume.is_empty() && board.by_win.is_empty());
+}
+
+#[test]
+fn bets_rank_players_by_volume() {
+    let (casino, players, boards) = ranked_players(3);
+    for (player, bet) in players.iter().zip([BET, 3 * BET, 2 * BET]) {
+        lose(&casino, player, bet, SESSION, &boards);
+    }
+
+    let board = casino.leaderboard(LEADERBOARD_ALL_TIME, 0);
+    let ranking: Vec<_> = board.by_volume.iter().map(|row| row.player).collect();
+    assert_eq!(
+        ranking,
+        [
+            players[1].pubkey(),
+            players[2].pubkey(),
+            players[0].pubkey()
+        ]
+    );
+    assert_eq!(board.by_volume[0].amount, 3 * BET);
+
+    // A second game adds up with the first instead of taking another row
+    lose(&casino, &players[0], 3 * BET, SESSION + 1, &boards);
+
+    let today = Leaderboard::period_index(LEADERBOARD_DAILY, casino.now());
+    let board = casino.leaderboard(LEADERBOARD_DAILY, today);
+    assert_eq!(board.by_volume.len(), 3);
+    assert_eq!(board.by_volume[0].player, players[0].pubkey());
+    assert_eq!(board.by_volume[0].amount, 4 * BET);
+    assert_eq!(
+        casino.player_stats(&players[0].pubkey()).total_volume,
+        4 * BET
+    );
+}
+
+#[test]
+fn claimed_wins_rank_the_biggest_payout() {
+    let (casino, players, boards) = ranked_players(2);
+    win(&casino, &players[0], BET, SESSION, &boards);
+    win(&casino, &players[1], 2 * BET, SESSION, &boards);
+    win(&casino, &players[1], BET, SESSION + 1, &boards);
+
+    let board = casino.leaderboard(LEADERBOARD_ALL_TIME, 0);
+    let ranking: Vec<_> = board
+        .by_win
+        .iter()
+        .map(|row| (row.player, row.amount))
+        .collect();
+    assert_eq!(
+        ranking,
+        [
+            (players[1].pubkey(), win_amount(2 * BET, RTP).unwrap()),
+            (players[0].pubkey(), win_amount(BET, RTP).unwrap()),
+        ]
+    );
+}
+
+#[test]
+fn wins_claimed_to_the_balance_are_ranked() {
+    let (casino, players, boards) = ranked_players(1);
+    let player = &players[0];
+    casino.deposit_balance(player, BET).unwrap();
+    casino.play_game(player, true, BET, SESSION).unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+
+    let ix = claim_reward_to_balance_ix(&casino.operator.pubkey(), &player.pubkey(), SESSION);
+    casino
+        .send(&[with_leaderboards(ix, &boards)], &[&casino.operator])
+        .unwrap();
+
+    let board = casino.leaderboard(LEADERBOARD_ALL_TIME, 0);
+    assert_eq!(board.by_win[0].player, player.pubkey());
+    assert_eq!(board.by_win[0].amount, win_amount(BET, RTP).unwrap());
+}
+
+#[test]
+fn boards_of_a_past_period_are_skipped() {
+    let (casino, players, boards) = ranked_players(1);
+    let player = &players[0];
+    lose(&casino, player, BET, SESSION, &boards);
+
+    casino.advance_clock(DAY);
+    lose(&casino, player, 2 * BET, SESSION + 1, &boards);
+
+    let yesterday = casino.leaderboard(
+        LEADERBOARD_DAILY,
+        casino.player_stats(&player.pubkey()).day - 1,
+    );
+    assert_eq!(yesterday.by_volume[0].amount, BET);
+    assert_eq!(
+        casino.leaderboard(LEADERBOARD_ALL_TIME, 0).by_volume[0].amount,
+        3 * BET
+    );
+    let stats = casino.player_stats(&player.pubkey());
+    assert_eq!(stats.daily_volume, 2 * BET);
+    assert_eq!(stats.total_volume, 3 * BET);
+}
+
+#[test]
+fn bets_without_stats_leave_the_boards() {
+    let (casino, _, boards) = ranked_players(0);
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    lose(&casino, &player, BET, SESSION, &boards);
+    assert!(casino
+        .leaderboard(LEADERBOARD_ALL_TIME, 0)
+        .by_volume
+        .is_empty());
+
+    casino.open_player_stats(&player.pubkey()).unwrap();
+    casino.play_game(&player, true, BET, SESSION + 1).unwrap();
+    assert_anchor_error(
+        casino.set_ranked_result(
+            &player.pubkey(),
+            1,
+            false,
+            SESSION + 1,
+            &[player_stats(&player.pubkey())],
+        ),
+        ErrorCode::AccountDiscriminatorMismatch,
+    );
+}
+
+#[test]
+fn bets_are_ranked_once_settled() {
+    let (casino, players, boards) = ranked_players(1);
+    let player = &players[0];
+    let ix = play_game_ix(
+        &player.pubkey(),
+        &casino.operator.pubkey(),
+        true,
+        BET,
+        SESSION,
+    );
+    casino
+        .send(
+            &[with_leaderboards(ix, &boards)],
+            &[player, &casino.operator],
+        )
+        .unwrap();
+    assert!(casino
+        .leaderboard(LEADERBOARD_ALL_TIME, 0)
+        .by_volume
+        .is_empty());
+
+    casino
+        .set_ranked_result(&player.pubkey(), 1, false, SESSION, &boards)
+        .unwrap();
+    let board = casino.leaderboard(LEADERBOARD_ALL_TIME, 0);
+    assert_eq!(board.by_volume[0].player, player.pubkey());
+    assert_eq!(board.by_volume[0].amount, BET);
+}
@@ -0,0 +1,260 @@
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use coinflip::{
+    constants::{
+        DAY, LIMITS_COOLING_OFF, LIMIT_DAILY, LIMIT_MONTHLY, LIMIT_WEEKLY,
+        SESSION_ALLOW_DOUBLE_BET, SESSION_ALLOW_PLAY, SETTLEMENT_TIMEOUT, WEEK,
+    },
+    error::GameError,
+};
+use common::*;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer};
+
+const BET: u64 = LAMPORTS_PER_SOL / 2;
+const NO_LIMITS: [u64; 3] = [0; 3];
+
+fn limited_player() -> (Casino, Keypair) {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    (casino, player)
+}
+
+fn daily(limit: u64) -> [u64; 3] {
+    let mut limits = NO_LIMITS;
+    limits[LIMIT_DAILY] = limit;
+    limits
+}
+
+#[test]
+fn self_exclusion_blocks_bets_until_it_ends() {
+    let (casino, player) = limited_player();
+    let until = casino.now() + 3 * DAY;
+    casino
+        .set_player_limits(&player, NO_LIMITS, NO_LIMITS, until)
+        .unwrap();
+
+    assert_game_error(
+        casino.play_game(&player, true, BET, 1),
+        GameError::SelfExcluded,
+    );
+
+    // Lifting the exclusion is a loosening, so it waits out the cooling-off
+    casino
+        .set_player_limits(&player, NO_LIMITS, NO_LIMITS, 0)
+        .unwrap();
+    assert_game_error(
+        casino.play_game(&player, true, BET, 1),
+        GameError::SelfExcluded,
+    );
+
+    casino.advance_clock(LIMITS_COOLING_OFF);
+    casino.play_game(&player, true, BET, 1).unwrap();
+    assert_eq!(
+        casino.player_limits(&player.pubkey()).self_excluded_until,
+        0
+    );
+}
+
+#[test]
+fn self_exclusion_blocks_every_game() {
+    let (casino, player) = limited_player();
+    casino.deposit_balance(&player, 2 * BET).unwrap();
+    casino
+        .set_player_limits(&player, NO_LIMITS, NO_LIMITS, casino.now() + DAY)
+        .unwrap();
+
+    assert_game_error(
+        casino.play_dice(&player, 50, BET, 1),
+        GameError::SelfExcluded,
+    );
+    assert_game_error(
+        casino.play_multi_coin(&player, 3, 2, false, BET, 1),
+        GameError::SelfExcluded,
+    );
+    assert_game_error(
+        casino.play_batch(&player, vec![true, false], vec![BET, BET], 1),
+        GameError::SelfExcluded,
+    );
+    assert_game_error(
+        casino.play_game_from_balance(&player, true, BET, 1),
+        GameError::SelfExcluded,
+    );
+}
+
+#[test]
+fn self_exclusion_blocks_session_key_bets() {
+    let (casino, player) = limited_player();
+    let key = Keypair::new();
+    casino.deposit_balance(&player, 2 * BET).unwrap();
+    casino
+        .create_session_key(
+            &player,
+            &key.pubkey(),
+            2 * BET,
+            SESSION_ALLOW_PLAY | SESSION_ALLOW_DOUBLE_BET,
+        )
+        .unwrap();
+    let play = |game_session_id| {
+        casino.send(
+            &[play_game_with_session_key_ix(
+                &key.pubkey(),
+                &player.pubkey(),
+                &casino.operator.pubkey(),
+                true,
+                BET,
+                game_session_id,
+            )],
+            &[&key, &casino.operator],
+        )
+    };
+
+    play(1).unwrap();
+    casino.set_result(&player.pubkey(), 1, true, 1).unwrap();
+    casino
+        .set_player_limits(&player, NO_LIMITS, NO_LIMITS, casino.now() + DAY)
+        .unwrap();
+
+    assert_game_error(play(2), GameError::SelfExcluded);
+    assert_game_error(
+        casino.send(
+            &[double_bet_with_session_key_ix(
+                &key.pubkey(),
+                &player.pubkey(),
+                &casino.operator.pubkey(),
+                true,
+                1,
+            )],
+            &[&key, &casino.operator],
+        ),
+        GameError::SelfExcluded,
+    );
+}
+
+#[test]
+fn wager_limits_cap_the_stakes_of_each_period() {
+    let (casino, player) = limited_player();
+    // Start of a week, so the next day is still in it
+    casino.advance_clock(WEEK - casino.now() % WEEK);
+    let mut limits = daily(2 * BET);
+    limits[LIMIT_WEEKLY] = 3 * BET;
+    casino
+        .set_player_limits(&player, limits, NO_LIMITS, 0)
+        .unwrap();
+
+    casino.play_game(&player, true, BET, 1).unwrap();
+    assert_game_error(
+        casino.play_game(&player, true, 2 * BET, 2),
+        GameError::WagerLimitExceeded,
+    );
+    casino.play_game(&player, true, BET, 2).unwrap();
+
+    casino.advance_clock(DAY);
+    assert_game_error(
+        casino.play_game(&player, true, 2 * BET, 3),
+        GameError::WagerLimitExceeded,
+    );
+    casino.play_game(&player, true, BET, 3).unwrap();
+
+    let limits = casino.player_limits(&player.pubkey());
+    assert_eq!(limits.wagered[LIMIT_DAILY], BET);
+    assert_eq!(limits.wagered[LIMIT_WEEKLY], 3 * BET);
+}
+
+#[test]
+fn claimed_payouts_make_up_for_losses() {
+    let (casino, player) = limited_player();
+    let mut limits = NO_LIMITS;
+    limits[LIMIT_MONTHLY] = BET + BET / 2;
+    casino
+        .set_player_limits(&player, NO_LIMITS, limits, 0)
+        .unwrap();
+
+    casino.play_game(&player, true, BET, 1).unwrap();
+    assert_game_error(
+        casino.play_game(&player, true, BET, 2),
+        GameError::LossLimitExceeded,
+    );
+
+    casino.set_result(&player.pubkey(), 1, true, 1).unwrap();
+    casino.claim_reward(&player.pubkey(), 1).unwrap();
+    assert_eq!(casino.player_limits(&player.pubkey()).lost, [0; 3]);
+
+    casino.play_game(&player, true, BET, 2).unwrap();
+}
+
+#[test]
+fn payouts_claimed_to_the_balance_make_up_for_losses() {
+    let (casino, player) = limited_player();
+    let mut limits = NO_LIMITS;
+    limits[LIMIT_MONTHLY] = BET + BET / 2;
+    casino
+        .set_player_limits(&player, NO_LIMITS, limits, 0)
+        .unwrap();
+    casino.deposit_balance(&player, BET).unwrap();
+
+    casino.play_game(&player, true, BET, 1).unwrap();
+    casino.set_result(&player.pubkey(), 1, true, 1).unwrap();
+    casino.claim_reward_to_balance(&player.pubkey(), 1).unwrap();
+    assert_eq!(casino.player_limits(&player.pubkey()).lost, [0; 3]);
+}
+
+#[test]
+fn slashed_rounds_make_up_for_losses() {
+    let (casino, player) = limited_player();
+    casino.post_bond(5 * LAMPORTS_PER_SOL).unwrap();
+    let mut limits = NO_LIMITS;
+    limits[LIMIT_MONTHLY] = BET + BET / 2;
+    casino
+        .set_player_limits(&player, NO_LIMITS, limits, 0)
+        .unwrap();
+
+    casino.play_game(&player, true, BET, 1).unwrap();
+    casino.advance_clock(SETTLEMENT_TIMEOUT + 1);
+    casino.slash_operator(&player, 1).unwrap();
+    assert_eq!(casino.player_limits(&player.pubkey()).lost, [0; 3]);
+
+    casino.play_game(&player, true, BET, 2).unwrap();
+}
+
+#[test]
+fn double_bets_count_against_the_wager_limit() {
+    let (casino, player) = limited_player();
+    casino
+        .set_player_limits(&player, daily(BET + BET / 2), NO_LIMITS, 0)
+        .unwrap();
+    casino.play_game(&player, true, BET, 1).unwrap();
+    casino.set_result(&player.pubkey(), 1, true, 1).unwrap();
+
+    assert_game_error(
+        casino.double_bet(&player, true, 1),
+        GameError::WagerLimitExceeded,
+    );
+}
+
+#[test]
+fn tightening_applies_at_once_and_loosening_after_the_cooling_off() {
+    let (casino, player) = limited_player();
+    casino
+        .set_player_limits(&player, daily(2 * BET), NO_LIMITS, 0)
+        .unwrap();
+
+    casino
+        .set_player_limits(&player, daily(10 * BET), daily(BET), 0)
+        .unwrap();
+
+    let limits = casino.player_limits(&player.pubkey());
+    assert_eq!(limits.player, player.pubkey());
+    assert_eq!(limits.wager_limits, daily(2 * BET));
+    assert_eq!(limits.loss_limits, daily(BET));
+    assert_eq!(limits.pending_wager_limits, daily(10 * BET));
+    assert_eq!(limits.pending_at, casino.now() + LIMITS_COOLING_OFF);
+
+    casino.advance_clock(LIMITS_COOLING_OFF);
+    casino.play_game(&player, true, BET, 1).unwrap();
+
+    let limits = casino.player_limits(&player.pubkey());
+    assert_eq!(limits.wager_limits, daily(10 * BET));
+    assert_eq!(limits.pending_at, 0);
+}
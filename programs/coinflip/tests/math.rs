@@ -0,0 +1,247 @@
+use anchor_lang::prelude::Pubkey;
+use coinflip::{
+    constants::{MAX_CHALLENGE_RAKE, MAX_JACKPOT_CONTRIBUTION, MAX_WIN_AMOUNT, RTP},
+    math::{
+        batch_win_amount, binomial, casino_top_up, casino_top_up_at_odds, jackpot_contribution,
+        multi_coin_odds, pays_above_stake, pot_rake, potential_profit, rakeback_amount, win_amount,
+        win_amount_at_chance, win_amount_at_odds, within_max_win,
+    },
+    utils::{derive_game_randomness, dice_roll_from_randomness, is_head_from_randomness},
+};
+use proptest::prelude::*;
+
+// Larger stakes cannot double within a u64, far beyond the SOL supply anyway
+const MAX_STAKE: u64 = u64::MAX / 2;
+
+proptest! {
+    #[test]
+    fn never_panics_for_any_input(stake: u64, first_bet: u64, rtp: u64, max_win_amount: u64) {
+        let _ = win_amount(stake, rtp);
+        let _ = casino_top_up(stake, rtp);
+        let _ = potential_profit(stake, first_bet, rtp);
+        let _ = within_max_win(stake, first_bet, rtp, max_win_amount);
+    }
+
+    #[test]
+    fn valid_rtp_never_overflows(stake in 0..=MAX_STAKE, rtp in 0u64..100) {
+        prop_assert!(win_amount(stake, rtp).is_some());
+        prop_assert!(casino_top_up(stake, rtp).is_some());
+    }
+
+    #[test]
+    fn payout_never_exceeds_max_win_plus_stake(
+        stake: u64,
+        first_bet: u64,
+        rtp in 0u64..100,
+        max_win_amount: u64,
+    ) {
+        prop_assume!(within_max_win(stake, first_bet, rtp, max_win_amount));
+
+        let payout = win_amount(stake, rtp).unwrap();
+        prop_assert!((payout as u128) < max_win_amount as u128 + first_bet as u128);
+    }
+
+    #[test]
+    fn first_round_profit_stays_below_max_win(bet: u64, rtp in 0u64..100) {
+        prop_assume!(within_max_win(bet, bet, rtp, MAX_WIN_AMOUNT));
+        prop_assert!(potential_profit(bet, bet, rtp).unwrap() < MAX_WIN_AMOUNT);
+    }
+
+    #[test]
+    fn house_edge_is_one_minus_rtp(stake in 0..=MAX_STAKE, rtp in 0u64..100) {
+        // A fair coin pays `win_amount` half the time, so the expected
+        // return is payout / 2 and should equal stake * rtp / 100 up to
+        // the rounding of a single lamport
+        let payout = win_amount(stake, rtp).unwrap() as u128;
+        let exact = 2 * stake as u128 * rtp as u128;
+
+        prop_assert!(payout * 100 <= exact);
+        prop_assert!(exact - payout * 100 < 100);
+    }
+
+    #[test]
+    fn top_up_completes_the_win(stake in 0..=MAX_STAKE, rtp in 50u64..100) {
+        let top_up = casino_top_up(stake, rtp).unwrap();
+        prop_assert_eq!(stake as u128 + top_up as u128, win_amount(stake, rtp).unwrap() as u128);
+    }
+
+    #[test]
+    fn even_dice_pays_like_a_coin_flip(stake: u64, rtp: u64) {
+        prop_assert_eq!(win_amount_at_chance(stake, rtp, 50), win_amount(stake, rtp));
+    }
+
+    #[test]
+    fn dice_house_edge_is_one_minus_rtp(
+        stake in 0..=u64::MAX / 100,
+        rtp in 0u64..100,
+        target in 1u64..100,
+    ) {
+        // A roll under `target` wins target% of the time
+        let payout = win_amount_at_chance(stake, rtp, target).unwrap() as u128;
+        let exact = stake as u128 * rtp as u128;
+
+        prop_assert!(payout * target as u128 <= exact);
+        prop_assert!(exact - payout * (target as u128) < target as u128);
+    }
+
+    #[test]
+    fn dice_roll_is_below_100(randomness: [u8; 32]) {
+        prop_assert!(dice_roll_from_randomness(&randomness) < 100);
+    }
+
+    #[test]
+    fn multi_coin_house_edge_is_one_minus_rtp(
+        stake in 0..=u64::MAX / 1024,
+        rtp in 0u64..100,
+        coins in 1u8..=10,
+        heads in 0u8..=10,
+        exact: bool,
+    ) {
+        prop_assume!(heads <= coins && (exact || heads > 0));
+
+        // Paying outcomes / wins times the RTP returns the RTP on average
+        let (wins, outcomes) = multi_coin_odds(coins, heads, exact);
+        let payout = win_amount_at_odds(stake, rtp, (wins, outcomes)).unwrap() as u128;
+        let exact_return = stake as u128 * rtp as u128 * outcomes as u128;
+
+        prop_assert!(payout * wins as u128 * 100 <= exact_return);
+        prop_assert!(exact_return - payout * wins as u128 * 100 < wins as u128 * 100);
+    }
+
+    #[test]
+    fn accepted_odds_pay_the_whole_win(
+        stake in 0..=u64::MAX / 1024,
+        rtp in 0u64..100,
+        wins in 1u64..=1024,
+        outcomes in 1u64..=1024,
+    ) {
+        prop_assume!(wins <= outcomes);
+
+        // Bets whose win covers the stake get exactly the win, the others are rejected
+        let odds = (wins, outcomes);
+        let win = win_amount_at_odds(stake, rtp, odds).unwrap();
+        if pays_above_stake(stake, rtp, odds) {
+            prop_assert_eq!(stake + casino_top_up_at_odds(stake, rtp, odds).unwrap(), win);
+        } else {
+            prop_assert!(win <= stake);
+        }
+    }
+
+    #[test]
+    fn challenge_rake_stays_a_tenth_of_the_pot(pot: u64, rake in 0..=MAX_CHALLENGE_RAKE) {
+        prop_assert!(pot_rake(pot, rake).unwrap() <= pot / 10);
+    }
+
+    #[test]
+    fn jackpot_contribution_stays_within_the_house_edge(
+        bet: u64,
+        rate in 0..=MAX_JACKPOT_CONTRIBUTION,
+    ) {
+        let edge = bet as u128 * (100 - RTP) as u128 / 100;
+        prop_assert!(jackpot_contribution(bet, rate).unwrap() as u128 <= edge);
+    }
+
+    #[test]
+    fn rakeback_never_exceeds_the_house_edge(stake: u64, rtp in 0u64..=100, rate in 0u16..=1000) {
+        let edge = stake as u128 * (100 - rtp) as u128 / 100;
+        prop_assert!(rakeback_amount(stake, rtp, rate).unwrap() as u128 <= edge);
+    }
+
+    #[test]
+    fn batch_never_pays_more_than_its_total(
+        amounts in prop::collection::vec(0..=MAX_STAKE / 64, 1..=64),
+        outcomes: u64,
+        rtp in 0u64..100,
+    ) {
+        // The max win check only sees the total, which must bound every payout
+        let total = amounts.iter().sum();
+        let payout = batch_win_amount(&amounts, outcomes, rtp).unwrap();
+        prop_assert!(payout <= win_amount(total, rtp).unwrap());
+        if outcomes == u64::MAX {
+            prop_assert!(win_amount(total, rtp).unwrap() - payout < amounts.len() as u64);
+        }
+    }
+
+    #[test]
+    fn double_bets_stop_at_max_win(bet in 1u64..100_000_000_000, rtp in 50u64..100) {
+        prop_assume!(within_max_win(bet, bet, rtp, MAX_WIN_AMOUNT));
+
+        // Keep doubling while allowed; every vault reached must stay in bounds
+        let mut vault = win_amount(bet, rtp).unwrap();
+        while within_max_win(vault, bet, rtp, MAX_WIN_AMOUNT) {
+            let next = win_amount(vault, rtp).unwrap();
+            if next <= vault {
+                break;
+            }
+            vault = next;
+        }
+        prop_assert!(vault < MAX_WIN_AMOUNT + bet);
+    }
+}
+
+#[test]
+fn realized_house_edge_matches_rtp() {
+    let server_seed = b"house-edge";
+    let player = Pubkey::new_unique();
+    let stake = 100_000_000u64;
+    let rounds = 20_000u64;
+
+    let mut returned = 0u128;
+    for game_session_id in 0..rounds {
+        let randomness = derive_game_randomness(server_seed, &player, game_session_id, 1);
+        if is_head_from_randomness(&randomness) {
+            returned += win_amount(stake, RTP).unwrap() as u128;
+        }
+    }
+
+    // Within 2% of the configured RTP over 20k fair flips
+    let rtp = returned as f64 / (stake as u128 * rounds as u128) as f64;
+    assert!(
+        (rtp - RTP as f64 / 100.0).abs() < 0.02,
+        "realized rtp {rtp}"
+    );
+}
+
+#[test]
+fn realized_dice_house_edge_matches_rtp() {
+    let server_seed = b"dice-house-edge";
+    let player = Pubkey::new_unique();
+    let stake = 100_000_000u64;
+    let rounds = 20_000u64;
+    let target = 25u8;
+
+    let mut returned = 0u128;
+    for game_session_id in 0..rounds {
+        let randomness = derive_game_randomness(server_seed, &player, game_session_id, 1);
+        if dice_roll_from_randomness(&randomness) < target {
+            returned += win_amount_at_chance(stake, RTP, target as u64).unwrap() as u128;
+        }
+    }
+
+    // Within 4% of the configured RTP, rarer wins vary more than coin flips
+    let rtp = returned as f64 / (stake as u128 * rounds as u128) as f64;
+    assert!(
+        (rtp - RTP as f64 / 100.0).abs() < 0.04,
+        "realized rtp {rtp}"
+    );
+}
+
+#[test]
+fn multi_coin_counts_cover_every_outcome() {
+    for coins in 1..=10u8 {
+        let counts: u64 = (0..=coins)
+            .map(|heads| binomial(coins as u64, heads as u64))
+            .sum();
+        assert_eq!(counts, 1 << coins);
+        assert_eq!(
+            multi_coin_odds(coins, 1, false),
+            ((1 << coins) - 1, 1 << coins)
+        );
+        assert_eq!(multi_coin_odds(coins, coins, true), (1, 1 << coins));
+    }
+    // A single coin on heads pays like a coin flip
+    assert_eq!(
+        win_amount_at_odds(100_000_000, RTP, multi_coin_odds(1, 1, true)),
+        win_amount(100_000_000, RTP)
+    );
+}
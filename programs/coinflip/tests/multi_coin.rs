@@ -0,0 +1,167 @@
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use coinflip::{
+    account::GameStatus,
+    constants::RTP,
+    error::GameError,
+    math::{multi_coin_odds, win_amount_at_odds},
+};
+use common::*;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer};
+
+const BET: u64 = LAMPORTS_PER_SOL / 5;
+const SESSION: u64 = 1;
+const COINS: u8 = 4;
+const HEADS: u8 = 3;
+
+#[test]
+fn play_multi_coin_opens_a_multi_coin_round() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    casino
+        .play_multi_coin(&player, COINS, HEADS, false, BET, SESSION)
+        .unwrap();
+
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert_eq!(pool.coin_count, COINS);
+    assert_eq!(pool.heads_target, HEADS);
+    assert!(!pool.exact_heads);
+    // 3 or 4 heads out of 4 coins: 4 + 1 of 16 outcomes
+    assert_eq!(pool.win_odds(), (5, 16));
+    assert!(pool.status == GameStatus::Processing);
+    assert_eq!(casino.balance(&game_vault(&player.pubkey(), SESSION)), BET);
+}
+
+#[test]
+fn multi_coin_win_pays_rtp_over_binomial_probability() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino
+        .play_multi_coin(&player, COINS, 2, true, BET, SESSION)
+        .unwrap();
+
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+
+    // Exactly 2 heads out of 4 coins: 6 of 16 outcomes
+    assert_eq!(
+        casino.balance(&game_vault(&player.pubkey(), SESSION)),
+        BET * RTP * 16 / (100 * 6)
+    );
+}
+
+#[test]
+fn double_bet_keeps_the_bet() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino
+        .play_multi_coin(&player, COINS, HEADS, false, BET, SESSION)
+        .unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+
+    casino.double_bet(&player, true, SESSION).unwrap();
+    casino
+        .set_result(&player.pubkey(), 2, true, SESSION)
+        .unwrap();
+
+    let odds = multi_coin_odds(COINS, HEADS, false);
+    let first = win_amount_at_odds(BET, RTP, odds).unwrap();
+    assert_eq!(
+        casino.balance(&game_vault(&player.pubkey(), SESSION)),
+        win_amount_at_odds(first, RTP, odds).unwrap()
+    );
+}
+
+#[test]
+fn rejects_invalid_bets() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    let play =
+        |coins, heads, exact| casino.play_multi_coin(&player, coins, heads, exact, BET, SESSION);
+
+    assert_game_error(play(0, 0, true), GameError::InvalidCoinCount);
+    assert_game_error(play(11, 5, false), GameError::InvalidCoinCount);
+    assert_game_error(play(COINS, COINS + 1, true), GameError::InvalidHeadsTarget);
+    // At least no heads always wins
+    assert_game_error(play(COINS, 0, false), GameError::InvalidHeadsTarget);
+}
+
+#[test]
+fn rejects_counts_paying_no_more_than_the_stake() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    // At least one head of ten wins 1023 times in 1024
+    assert_game_error(
+        casino.play_multi_coin(&player, 10, 1, false, BET, SESSION),
+        GameError::PayoutBelowStake,
+    );
+}
+
+#[test]
+fn max_win_applies_the_table_multiplier() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    // Ten heads out of ten coins would pay 972.8x
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+
+    assert_game_error(
+        casino.play_multi_coin(&player, 10, 10, true, BET, SESSION + 1),
+        GameError::InvalidBetAmountMaxWinAmountViolation,
+    );
+}
+
+fn play_with_provider(casino: &Casino, player: &Keypair, randomness: [u8; 32]) {
+    let nonce = casino.global_pool().game_nonce;
+    let mut ix = play_multi_coin_ix(
+        &player.pubkey(),
+        &casino.operator.pubkey(),
+        COINS,
+        HEADS,
+        false,
+        BET,
+        SESSION,
+    );
+    ix.accounts
+        .extend(provider_accounts(&player.pubkey(), SESSION, nonce));
+    casino.send(&[ix], &[player, &casino.operator]).unwrap();
+    casino
+        .fulfill(&player.pubkey(), SESSION, randomness)
+        .unwrap();
+}
+
+#[test]
+fn provider_randomness_records_the_coin_faces() {
+    let casino = Casino::with_mock_provider();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    // Head, head, tail, head
+    let mut randomness = [0; 32];
+    randomness[0] = 0b1011;
+    play_with_provider(&casino, &player, randomness);
+
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert!(pool.status == GameStatus::Win);
+    assert_eq!(pool.coin_faces[..4], [true, true, false, true]);
+    assert!(pool.coin_faces[4..].iter().all(|head| !head));
+}
+
+#[test]
+fn too_few_heads_lose() {
+    let casino = Casino::with_mock_provider();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    // One head among the four coins flipped
+    let mut randomness = [0; 32];
+    randomness[0] = 0b1111_0001;
+    play_with_provider(&casino, &player, randomness);
+
+    assert!(casino.player_pool(&player.pubkey(), SESSION).is_none());
+    assert_eq!(casino.balance(&casino_vault()), CASINO_FUNDS + BET);
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use coinflip::{constants::RTP, error::GameError, math::win_amount};
+use common::*;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer};
+
+const CREDIT: u64 = LAMPORTS_PER_SOL;
+const BET: u64 = LAMPORTS_PER_SOL / 2;
+
+/// A casino and a player holding `CREDIT` of promo credit to wager `requirement`.
+fn promo_player(requirement: u64) -> (Casino, Keypair) {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino
+        .issue_promo_credit(&player.pubkey(), CREDIT, requirement)
+        .unwrap();
+    (casino, player)
+}
+
+#[test]
+fn operation_authority_issues_promo_credit() {
+    let (casino, player) = promo_player(2 * CREDIT);
+    let stranger = casino.new_player(10 * LAMPORTS_PER_SOL);
+    assert_game_error(
+        casino.send(
+            &[issue_promo_credit_ix(
+                &stranger.pubkey(),
+                &player.pubkey(),
+                CREDIT,
+                0,
+            )],
+            &[&stranger],
+        ),
+        GameError::UnauthorizedOperator,
+    );
+
+    casino
+        .issue_promo_credit(&player.pubkey(), CREDIT, CREDIT)
+        .unwrap();
+
+    let promo = casino.promo_credit(&player.pubkey());
+    assert_eq!(promo.player, player.pubkey());
+    assert_eq!(promo.credit, 2 * CREDIT);
+    assert_eq!(promo.wagering_requirement, 3 * CREDIT);
+}
+
+#[test]
+fn the_house_stakes_promo_bets() {
+    let (casino, player) = promo_player(CREDIT);
+    let wallet = casino.balance(&player.pubkey());
+
+    casino
+        .play_game_with_promo_credit(&player, true, BET, 1)
+        .unwrap();
+
+    assert_eq!(casino.balance(&player.pubkey()), wallet);
+    assert_eq!(casino.balance(&casino_vault()), CASINO_FUNDS - BET);
+    assert_eq!(casino.balance(&game_vault(&player.pubkey(), 1)), BET);
+    let promo = casino.promo_credit(&player.pubkey());
+    assert_eq!((promo.credit, promo.wagered), (CREDIT - BET, BET));
+    let pool = casino.player_pool(&player.pubkey(), 1).unwrap();
+    assert_eq!(pool.promo_stake, BET);
+
+    assert_game_error(
+        casino.play_game_with_promo_credit(&player, true, CREDIT, 2),
+        GameError::InsufficientPromoCredit,
+    );
+}
+
+#[test]
+fn net_winnings_stay_locked_until_the_requirement_is_met() {
+    let (casino, player) = promo_player(CREDIT);
+    casino
+        .play_game_with_promo_credit(&player, true, BET, 1)
+        .unwrap();
+    casino.set_result(&player.pubkey(), 1, true, 1).unwrap();
+    assert_game_error(
+        casino.claim_reward(&player.pubkey(), 1),
+        GameError::PromoGame,
+    );
+    let vault = casino.balance(&casino_vault());
+
+    casino.claim_promo_reward(&player.pubkey(), 1).unwrap();
+
+    let winnings = win_amount(BET, RTP).unwrap() - BET;
+    assert_eq!(casino.balance(&casino_vault()), vault + BET);
+    assert_eq!(
+        casino.promo_credit(&player.pubkey()).locked_winnings,
+        winnings
+    );
+    assert!(casino.player_pool(&player.pubkey(), 1).is_none());
+    assert_game_error(
+        casino.withdraw_promo_winnings(&player),
+        GameError::WageringRequirementNotMet,
+    );
+
+    // Losing the rest of the credit completes the requirement
+    casino
+        .play_game_with_promo_credit(&player, true, BET, 2)
+        .unwrap();
+    casino.set_result(&player.pubkey(), 1, false, 2).unwrap();
+    let wallet = casino.balance(&player.pubkey());
+
+    casino.withdraw_promo_winnings(&player).unwrap();
+
+    assert_eq!(casino.balance(&player.pubkey()), wallet + winnings);
+    let promo = casino.promo_credit(&player.pubkey());
+    assert_eq!((promo.credit, promo.locked_winnings), (0, 0));
+    assert_game_error(
+        casino.withdraw_promo_winnings(&player),
+        GameError::NoPromoWinnings,
+    );
+}
+
+#[test]
+fn only_promo_games_are_claimed_into_the_credit() {
+    let (casino, player) = promo_player(0);
+    casino.play_game(&player, true, BET, 1).unwrap();
+    casino.set_result(&player.pubkey(), 1, true, 1).unwrap();
+
+    assert_game_error(
+        casino.claim_promo_reward(&player.pubkey(), 1),
+        GameError::NotPromoGame,
+    );
+}
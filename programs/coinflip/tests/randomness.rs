@@ -0,0 +1,170 @@
+mod common;
+
+use anchor_lang::{error::ErrorCode, AccountDeserialize, InstructionData, ToAccountMetas};
+use coinflip::{account::GameStatus, error::GameError, randomness::randomness_seed};
+use common::*;
+use mock_randomness::Request;
+use solana_sdk::{
+    instruction::Instruction, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signer::Signer,
+};
+
+const BET: u64 = LAMPORTS_PER_SOL / 5;
+const SESSION: u64 = 1;
+// The player bets on head, which the lowest bit of the randomness decides
+const HEAD: [u8; 32] = [1; 32];
+const TAIL: [u8; 32] = [0; 32];
+
+#[test]
+fn play_game_requests_randomness_from_provider() {
+    let casino = Casino::with_mock_provider();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    casino
+        .play_game_with_provider(&player, true, BET, SESSION)
+        .unwrap();
+
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert_eq!(pool.randomness_provider, mock_randomness::ID);
+    let account = casino
+        .account(&randomness_request(&player.pubkey(), SESSION, pool.nonce))
+        .unwrap();
+    let request = Request::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(request.requester, randomness_requester());
+    assert_eq!(
+        request.seed,
+        randomness_seed(&player_pool(&player.pubkey(), SESSION), pool.nonce)
+    );
+    assert_eq!(request.callback_program, coinflip::ID);
+}
+
+#[test]
+fn provider_callback_settles_win() {
+    let casino = Casino::with_mock_provider();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino
+        .play_game_with_provider(&player, true, BET, SESSION)
+        .unwrap();
+
+    casino.fulfill(&player.pubkey(), SESSION, HEAD).unwrap();
+
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert!(pool.status == GameStatus::Win);
+    assert_eq!(
+        casino.balance(&game_vault(&player.pubkey(), SESSION)),
+        BET * 2 * 95 / 100
+    );
+}
+
+#[test]
+fn provider_callback_settles_loss() {
+    let casino = Casino::with_mock_provider();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino
+        .play_game_with_provider(&player, true, BET, SESSION)
+        .unwrap();
+
+    casino.fulfill(&player.pubkey(), SESSION, TAIL).unwrap();
+
+    assert_eq!(casino.balance(&casino_vault()), CASINO_FUNDS + BET);
+    assert_eq!(casino.balance(&player_pool(&player.pubkey(), SESSION)), 0);
+}
+
+#[test]
+fn double_bet_requests_a_new_round() {
+    let casino = Casino::with_mock_provider();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino
+        .play_game_with_provider(&player, true, BET, SESSION)
+        .unwrap();
+    casino.fulfill(&player.pubkey(), SESSION, HEAD).unwrap();
+
+    casino
+        .double_bet_with_provider(&player, true, SESSION)
+        .unwrap();
+    casino.fulfill(&player.pubkey(), SESSION, HEAD).unwrap();
+
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert_eq!(pool.round, 2);
+    assert!(pool.status == GameStatus::Win);
+}
+
+#[test]
+fn operator_cannot_settle_provider_games() {
+    let casino = Casino::with_mock_provider();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino
+        .play_game_with_provider(&player, true, BET, SESSION)
+        .unwrap();
+
+    assert_game_error(
+        casino.set_result(&player.pubkey(), 1, true, SESSION),
+        GameError::SettledByProvider,
+    );
+    assert_game_error(
+        casino.settle_with_signature(&player, &player.pubkey(), 1, true, SESSION),
+        GameError::SettledByProvider,
+    );
+}
+
+#[test]
+fn callback_requires_provider_authority() {
+    let casino = Casino::with_mock_provider();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino
+        .play_game_with_provider(&player, true, BET, SESSION)
+        .unwrap();
+
+    let ix = Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::ConsumeRandomness {
+            provider_authority: player.pubkey(),
+            operator: casino.operator.pubkey(),
+            owner: player.pubkey(),
+            global_authority: global_authority(),
+            player_pool: player_pool(&player.pubkey(), SESSION),
+            game_vault: game_vault(&player.pubkey(), SESSION),
+            casino_vault: casino_vault(),
+            player_stats: player_stats(&player.pubkey()),
+            vip_config: vip_config(),
+            system_program: anchor_lang::system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::ConsumeRandomness {
+            game_session_id: SESSION,
+            randomness: HEAD,
+        }
+        .data(),
+    };
+
+    assert_game_error(
+        casino.send(&[ix], &[&player]),
+        GameError::UnauthorizedRandomnessProvider,
+    );
+}
+
+#[test]
+fn play_game_requires_provider_accounts() {
+    let casino = Casino::with_mock_provider();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    assert_game_error(
+        casino.play_game(&player, true, BET, SESSION),
+        GameError::InvalidRandomnessProvider,
+    );
+}
+
+#[test]
+fn randomness_provider_requires_super_admin() {
+    let casino = Casino::new();
+
+    let result = casino.send(
+        &[set_authority_ix(
+            &casino.update.pubkey(),
+            coinflip::instruction::SetRandomnessProvider {
+                new_randomness_provider: Pubkey::new_unique(),
+            },
+        )],
+        &[&casino.update],
+    );
+    assert_anchor_error(result, ErrorCode::ConstraintAddress);
+}
@@ -0,0 +1,213 @@
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use coinflip::{
+    constants::{RTP, SESSION_ALLOW_CLAIM, SESSION_ALLOW_DOUBLE_BET, SESSION_ALLOW_PLAY},
+    error::GameError,
+    math::win_amount,
+};
+use common::*;
+use litesvm::types::TransactionResult;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer};
+
+const DEPOSIT: u64 = 2 * LAMPORTS_PER_SOL;
+const CAP: u64 = LAMPORTS_PER_SOL;
+const BET: u64 = 3 * LAMPORTS_PER_SOL / 5;
+const SESSION: u64 = 1;
+const ALLOW_ALL: u8 = SESSION_ALLOW_PLAY | SESSION_ALLOW_DOUBLE_BET | SESSION_ALLOW_CLAIM;
+
+struct Delegation {
+    casino: Casino,
+    owner: Keypair,
+    key: Keypair,
+}
+
+impl Delegation {
+    fn new(allowed: u8) -> Self {
+        let casino = Casino::new();
+        let owner = casino.new_player(10 * LAMPORTS_PER_SOL);
+        let key = Keypair::new();
+        casino.deposit_balance(&owner, DEPOSIT).unwrap();
+        casino
+            .create_session_key(&owner, &key.pubkey(), CAP, allowed)
+            .unwrap();
+        Self { casino, owner, key }
+    }
+
+    fn play(&self, bet_amount: u64, game_session_id: u64) -> TransactionResult {
+        self.casino.send(
+            &[play_game_with_session_key_ix(
+                &self.key.pubkey(),
+                &self.owner.pubkey(),
+                &self.casino.operator.pubkey(),
+                true,
+                bet_amount,
+                game_session_id,
+            )],
+            &[&self.key, &self.casino.operator],
+        )
+    }
+
+    fn double_bet(&self) -> TransactionResult {
+        self.casino.send(
+            &[double_bet_with_session_key_ix(
+                &self.key.pubkey(),
+                &self.owner.pubkey(),
+                &self.casino.operator.pubkey(),
+                true,
+                SESSION,
+            )],
+            &[&self.key, &self.casino.operator],
+        )
+    }
+
+    fn claim(&self) -> TransactionResult {
+        self.casino.send(
+            &[claim_reward_with_session_key_ix(
+                &self.key.pubkey(),
+                &self.owner.pubkey(),
+                &self.casino.operator.pubkey(),
+                SESSION,
+            )],
+            &[&self.key],
+        )
+    }
+}
+
+#[test]
+fn session_key_bets_from_the_owner_balance() {
+    let session = Delegation::new(ALLOW_ALL);
+    let wallet = session.casino.balance(&session.owner.pubkey());
+
+    session.play(BET, SESSION).unwrap();
+
+    let owner = session.owner.pubkey();
+    assert_eq!(session.casino.player_balance(&owner), DEPOSIT - BET);
+    assert_eq!(session.casino.balance(&game_vault(&owner, SESSION)), BET);
+    assert_eq!(session.casino.balance(&owner), wallet);
+    let pool = session.casino.player_pool(&owner, SESSION).unwrap();
+    assert_eq!(pool.player, owner);
+    let key = session
+        .casino
+        .session_key(&owner, &session.key.pubkey())
+        .unwrap();
+    assert_eq!(key.spent, BET);
+}
+
+#[test]
+fn stakes_stop_at_the_spending_cap() {
+    let session = Delegation::new(ALLOW_ALL);
+
+    session.play(BET, SESSION).unwrap();
+    assert_game_error(
+        session.play(BET, SESSION + 1),
+        GameError::SessionSpendingCapExceeded,
+    );
+    session.play(CAP - BET, SESSION + 1).unwrap();
+}
+
+#[test]
+fn wins_are_doubled_and_claimed_to_the_owner() {
+    let session = Delegation::new(ALLOW_ALL);
+    let owner = session.owner.pubkey();
+    session.play(BET, SESSION).unwrap();
+    session.casino.set_result(&owner, 1, true, SESSION).unwrap();
+
+    session.double_bet().unwrap();
+    session.casino.set_result(&owner, 2, true, SESSION).unwrap();
+
+    let wallet = session.casino.balance(&owner);
+    session.claim().unwrap();
+
+    let payout = win_amount(win_amount(BET, RTP).unwrap(), RTP).unwrap();
+    assert_eq!(session.casino.balance(&owner), wallet + payout);
+    assert!(session.casino.player_pool(&owner, SESSION).is_none());
+    // Doubling moves no new funds, so only the first stake was spent
+    let key = session
+        .casino
+        .session_key(&owner, &session.key.pubkey())
+        .unwrap();
+    assert_eq!(key.spent, BET);
+}
+
+#[test]
+fn session_key_signs_only_what_it_was_allowed() {
+    let session = Delegation::new(SESSION_ALLOW_PLAY);
+    let owner = session.owner.pubkey();
+    session.play(BET, SESSION).unwrap();
+    session.casino.set_result(&owner, 1, true, SESSION).unwrap();
+
+    assert_game_error(session.double_bet(), GameError::SessionKeyNotAllowed);
+    assert_game_error(session.claim(), GameError::SessionKeyNotAllowed);
+
+    let session = Delegation::new(SESSION_ALLOW_CLAIM);
+    assert_game_error(session.play(BET, SESSION), GameError::SessionKeyNotAllowed);
+}
+
+#[test]
+fn expired_session_key_is_rejected() {
+    let session = Delegation::new(ALLOW_ALL);
+    session.casino.advance_clock(3600);
+
+    assert_game_error(session.play(BET, SESSION), GameError::SessionKeyExpired);
+
+    let key = Keypair::new();
+    assert_game_error(
+        session.casino.send(
+            &[create_session_key_ix(
+                &session.owner.pubkey(),
+                &key.pubkey(),
+                CAP,
+                session.casino.now(),
+                ALLOW_ALL,
+            )],
+            &[&session.owner],
+        ),
+        GameError::SessionKeyExpired,
+    );
+}
+
+#[test]
+fn session_key_only_signs_for_its_owner() {
+    let session = Delegation::new(ALLOW_ALL);
+    let other = session.casino.new_player(10 * LAMPORTS_PER_SOL);
+    session.casino.deposit_balance(&other, DEPOSIT).unwrap();
+
+    assert_anchor_error(
+        session.casino.send(
+            &[play_game_with_session_key_ix(
+                &session.key.pubkey(),
+                &other.pubkey(),
+                &session.casino.operator.pubkey(),
+                true,
+                BET,
+                SESSION,
+            )],
+            &[&session.key, &session.casino.operator],
+        ),
+        ErrorCode::AccountNotInitialized,
+    );
+}
+
+#[test]
+fn revoked_session_key_stops_working() {
+    let session = Delegation::new(ALLOW_ALL);
+    let owner = session.owner.pubkey();
+    let wallet = session.casino.balance(&owner);
+    let rent = session
+        .casino
+        .balance(&session_key(&owner, &session.key.pubkey()));
+
+    session
+        .casino
+        .send(
+            &[revoke_session_key_ix(&owner, &session.key.pubkey())],
+            &[&session.owner],
+        )
+        .unwrap();
+
+    assert_eq!(session.casino.balance(&owner), wallet + rent);
+    assert_anchor_error(session.play(BET, SESSION), ErrorCode::AccountNotInitialized);
+}
@@ -0,0 +1,210 @@
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use coinflip::{account::GameStatus, error::GameError};
+use common::*;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer};
+
+const BET: u64 = LAMPORTS_PER_SOL / 5;
+const SESSION: u64 = 1;
+
+#[test]
+fn player_submits_operator_signed_win() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    let operator_before = casino.balance(&casino.operator.pubkey());
+
+    casino
+        .settle_with_signature(&player, &player.pubkey(), 1, true, SESSION)
+        .unwrap();
+
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert!(pool.status == GameStatus::Win);
+    assert_eq!(
+        casino.balance(&game_vault(&player.pubkey(), SESSION)),
+        BET * 2 * 95 / 100
+    );
+    assert_eq!(casino.balance(&casino.operator.pubkey()), operator_before);
+}
+
+#[test]
+fn signed_loss_closes_player_pool_to_operator() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    let pool_rent = casino.balance(&player_pool(&player.pubkey(), SESSION));
+    let operator_before = casino.balance(&casino.operator.pubkey());
+
+    casino
+        .settle_with_signature(&player, &player.pubkey(), 1, false, SESSION)
+        .unwrap();
+
+    assert_eq!(casino.balance(&casino_vault()), CASINO_FUNDS + BET);
+    assert_eq!(casino.balance(&game_vault(&player.pubkey(), SESSION)), 0);
+    assert_eq!(casino.balance(&player_pool(&player.pubkey(), SESSION)), 0);
+    assert_eq!(
+        casino.balance(&casino.operator.pubkey()),
+        operator_before + pool_rent
+    );
+}
+
+#[test]
+fn every_round_gets_a_fresh_nonce() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    let first = casino.player_pool(&player.pubkey(), SESSION).unwrap().nonce;
+    casino
+        .settle_with_signature(&player, &player.pubkey(), 1, true, SESSION)
+        .unwrap();
+
+    casino.double_bet(&player, true, SESSION).unwrap();
+    let second = casino.player_pool(&player.pubkey(), SESSION).unwrap().nonce;
+
+    assert_ne!(first, second);
+    assert_eq!(casino.global_pool().game_nonce, second + 1);
+}
+
+#[test]
+fn rejects_signature_from_another_key() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    let nonce = casino.player_pool(&player.pubkey(), SESSION).unwrap().nonce;
+
+    assert_game_error(
+        casino.send_with_payer(
+            &player,
+            &[
+                signed_settlement_ix(&player, &player.pubkey(), SESSION, 1, true, nonce),
+                settle_with_signature_ix(
+                    &casino.operator.pubkey(),
+                    &player.pubkey(),
+                    1,
+                    true,
+                    SESSION,
+                    nonce,
+                ),
+            ],
+            &[],
+        ),
+        GameError::InvalidSignature,
+    );
+}
+
+#[test]
+fn rejects_tampered_outcome() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    let nonce = casino.player_pool(&player.pubkey(), SESSION).unwrap().nonce;
+
+    assert_game_error(
+        casino.send_with_payer(
+            &player,
+            &[
+                signed_settlement_ix(&casino.operator, &player.pubkey(), SESSION, 1, false, nonce),
+                settle_with_signature_ix(
+                    &casino.operator.pubkey(),
+                    &player.pubkey(),
+                    1,
+                    true,
+                    SESSION,
+                    nonce,
+                ),
+            ],
+            &[],
+        ),
+        GameError::InvalidSignature,
+    );
+}
+
+#[test]
+fn rejects_settlement_without_signature_instruction() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    let nonce = casino.player_pool(&player.pubkey(), SESSION).unwrap().nonce;
+
+    assert_game_error(
+        casino.send_with_payer(
+            &player,
+            &[settle_with_signature_ix(
+                &casino.operator.pubkey(),
+                &player.pubkey(),
+                1,
+                true,
+                SESSION,
+                nonce,
+            )],
+            &[],
+        ),
+        GameError::InvalidSignature,
+    );
+}
+
+#[test]
+fn rejects_replay_of_a_settled_round() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    let nonce = casino.player_pool(&player.pubkey(), SESSION).unwrap().nonce;
+    let replay = |casino: &Casino, submitter: &Keypair| {
+        casino.send_with_payer(
+            submitter,
+            &[
+                signed_settlement_ix(&casino.operator, &player.pubkey(), SESSION, 1, true, nonce),
+                settle_with_signature_ix(
+                    &casino.operator.pubkey(),
+                    &player.pubkey(),
+                    1,
+                    true,
+                    SESSION,
+                    nonce,
+                ),
+            ],
+            &[],
+        )
+    };
+
+    replay(&casino, &player).unwrap();
+    assert_game_error(replay(&casino, &player), GameError::NotAllowedStatus);
+
+    casino.double_bet(&player, true, SESSION).unwrap();
+    assert_game_error(replay(&casino, &player), GameError::InvalidNonce);
+}
+
+#[test]
+fn rejects_replay_in_a_later_game_of_the_same_session() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+    let nonce = casino.player_pool(&player.pubkey(), SESSION).unwrap().nonce;
+    casino
+        .settle_with_signature(&player, &player.pubkey(), 1, false, SESSION)
+        .unwrap();
+
+    casino.play_game(&player, true, BET, SESSION).unwrap();
+
+    assert_game_error(
+        casino.send_with_payer(
+            &player,
+            &[
+                signed_settlement_ix(&casino.operator, &player.pubkey(), SESSION, 1, false, nonce),
+                settle_with_signature_ix(
+                    &casino.operator.pubkey(),
+                    &player.pubkey(),
+                    1,
+                    false,
+                    SESSION,
+                    nonce,
+                ),
+            ],
+            &[],
+        ),
+        GameError::InvalidNonce,
+    );
+}
@@ -0,0 +1,239 @@
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use coinflip::{
+    account::GameStatus,
+    error::GameError,
+    instruction,
+    utils::{is_head_from_randomness, slot_game_randomness},
+};
+use common::*;
+use solana_sdk::{hash::Hash, native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer};
+
+const BET: u64 = LAMPORTS_PER_SOL / 5;
+const SESSION: u64 = 1;
+const DELAY: u64 = 10;
+const START: u64 = 100;
+const SEED: [u8; 32] = [7; 32];
+
+/// A casino with slot hash games enabled for stakes up to `max_bet`.
+fn slot_hash_casino(max_bet: u64) -> Casino {
+    let casino = Casino::new();
+    casino
+        .send(
+            &[set_global_pool_ix(
+                &casino.update.pubkey(),
+                instruction::SetSlotHashMode {
+                    delay: DELAY,
+                    max_bet,
+                },
+            )],
+            &[&casino.update],
+        )
+        .unwrap();
+    casino.warp_to_slot(START);
+    casino
+}
+
+fn play(casino: &Casino, player: &Keypair, bet_amount: u64) -> litesvm::types::TransactionResult {
+    casino.send(
+        &[play_game_with_slot_hash_ix(
+            &player.pubkey(),
+            &casino.operator.pubkey(),
+            true,
+            bet_amount,
+            SESSION,
+            SEED,
+        )],
+        &[player, &casino.operator],
+    )
+}
+
+fn settle(casino: &Casino, player: &Keypair) -> litesvm::types::TransactionResult {
+    casino.send(
+        &[settle_with_slot_hash_ix(
+            &casino.operator.pubkey(),
+            &player.pubkey(),
+            SESSION,
+        )],
+        &[],
+    )
+}
+
+/// A slot hash making a head bet in `round` win or lose.
+fn slot_hash_for(win: bool, round: u8) -> Hash {
+    (0..=u8::MAX)
+        .map(|byte| Hash::new_from_array([byte; 32]))
+        .find(|hash| {
+            let randomness = slot_game_randomness(&hash.to_bytes(), &SEED, SESSION, round);
+            is_head_from_randomness(&randomness) == win
+        })
+        .unwrap()
+}
+
+#[test]
+fn bet_commits_to_a_future_slot() {
+    let casino = slot_hash_casino(BET);
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    play(&casino, &player, BET).unwrap();
+
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert_eq!(pool.target_slot, START + DELAY);
+    assert_eq!(pool.player_seed, SEED);
+}
+
+#[test]
+fn anyone_settles_after_the_target_slot() {
+    let casino = slot_hash_casino(BET);
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    play(&casino, &player, BET).unwrap();
+
+    casino.warp_to_slot(START + DELAY);
+    assert_game_error(settle(&casino, &player), GameError::SlotHashNotReady);
+
+    casino.warp_to_slot(START + DELAY + 1);
+    casino.set_slot_hashes(&[
+        (START + DELAY, slot_hash_for(true, 1)),
+        (START + DELAY - 1, slot_hash_for(false, 1)),
+    ]);
+    settle(&casino, &player).unwrap();
+
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert!(pool.status == GameStatus::Win);
+    assert_eq!(
+        casino.balance(&game_vault(&player.pubkey(), SESSION)),
+        BET * 2 * 95 / 100
+    );
+}
+
+#[test]
+fn losing_slot_hash_closes_the_game() {
+    let casino = slot_hash_casino(BET);
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    play(&casino, &player, BET).unwrap();
+
+    casino.warp_to_slot(START + DELAY + 1);
+    casino.set_slot_hashes(&[(START + DELAY, slot_hash_for(false, 1))]);
+    settle(&casino, &player).unwrap();
+
+    assert_eq!(casino.balance(&casino_vault()), CASINO_FUNDS + BET);
+    assert_eq!(casino.balance(&player_pool(&player.pubkey(), SESSION)), 0);
+}
+
+#[test]
+fn skipped_target_slot_uses_the_next_slot() {
+    let casino = slot_hash_casino(BET);
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    play(&casino, &player, BET).unwrap();
+
+    casino.warp_to_slot(START + DELAY + 3);
+    casino.set_slot_hashes(&[
+        (START + DELAY + 2, slot_hash_for(false, 1)),
+        (START + DELAY + 1, slot_hash_for(true, 1)),
+        (START + DELAY - 1, slot_hash_for(false, 1)),
+    ]);
+    settle(&casino, &player).unwrap();
+
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert!(pool.status == GameStatus::Win);
+}
+
+#[test]
+fn expired_slot_hash_loses_the_round() {
+    let casino = slot_hash_casino(BET);
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    play(&casino, &player, BET).unwrap();
+    let wallet = casino.balance(&player.pubkey());
+    let vault = casino.balance(&casino_vault());
+
+    // The only hash left would have won the round
+    let now = START + 1000;
+    casino.warp_to_slot(now);
+    casino.set_slot_hashes(&[(now - 1, slot_hash_for(true, 1))]);
+    settle(&casino, &player).unwrap();
+
+    assert!(casino.player_pool(&player.pubkey(), SESSION).is_none());
+    assert_eq!(casino.balance(&player.pubkey()), wallet);
+    assert_eq!(casino.balance(&casino_vault()), vault + BET);
+}
+
+#[test]
+fn double_bet_commits_again_within_the_ceiling() {
+    let casino = slot_hash_casino(2 * BET);
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    play(&casino, &player, BET).unwrap();
+    casino.warp_to_slot(START + DELAY + 1);
+    casino.set_slot_hashes(&[(START + DELAY, slot_hash_for(true, 1))]);
+    settle(&casino, &player).unwrap();
+
+    casino.double_bet(&player, true, SESSION).unwrap();
+
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert_eq!(pool.round, 2);
+    assert_eq!(pool.target_slot, START + DELAY + 1 + DELAY);
+}
+
+#[test]
+fn double_bet_respects_the_ceiling() {
+    let casino = slot_hash_casino(BET);
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    play(&casino, &player, BET).unwrap();
+    casino.warp_to_slot(START + DELAY + 1);
+    casino.set_slot_hashes(&[(START + DELAY, slot_hash_for(true, 1))]);
+    settle(&casino, &player).unwrap();
+
+    assert_game_error(
+        casino.double_bet(&player, true, SESSION),
+        GameError::SlotHashBetTooLarge,
+    );
+}
+
+#[test]
+fn bet_above_the_ceiling_is_rejected() {
+    let casino = slot_hash_casino(BET);
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    assert_game_error(
+        play(&casino, &player, BET + 1),
+        GameError::SlotHashBetTooLarge,
+    );
+}
+
+#[test]
+fn slot_hash_games_are_off_by_default() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    assert_game_error(play(&casino, &player, BET), GameError::SlotHashDisabled);
+}
+
+#[test]
+fn operator_cannot_settle_slot_hash_games() {
+    let casino = slot_hash_casino(BET);
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    play(&casino, &player, BET).unwrap();
+
+    assert_game_error(
+        casino.set_result(&player.pubkey(), 1, true, SESSION),
+        GameError::SettledBySlotHash,
+    );
+}
+
+#[test]
+fn slot_hash_delay_must_fit_the_sysvar() {
+    let casino = Casino::new();
+
+    let result = casino.send(
+        &[set_global_pool_ix(
+            &casino.update.pubkey(),
+            instruction::SetSlotHashMode {
+                delay: 512,
+                max_bet: BET,
+            },
+        )],
+        &[&casino.update],
+    );
+    assert_game_error(result, GameError::InvalidSlotHashDelay);
+}
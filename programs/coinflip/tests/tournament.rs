@@ -0,0 +1,382 @@
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use coinflip::{
+    constants::{
+        RTP, SESSION_ALLOW_CLAIM, TOURNAMENT_RANKING_PERIOD, TOURNAMENT_SCORE_NET_PROFIT,
+        TOURNAMENT_SCORE_VOLUME,
+    },
+    error::GameError,
+    math::win_amount,
+};
+use common::*;
+use litesvm::types::TransactionResult;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer};
+
+const TOURNAMENT: u64 = 7;
+const FEE: u64 = LAMPORTS_PER_SOL / 10;
+const BET: u64 = LAMPORTS_PER_SOL / 2;
+const SESSION: u64 = 1;
+
+/// A running tournament with `count` players entered in it.
+fn running_tournament(scoring: u8, prize_split: Vec<u16>, count: usize) -> (Casino, Vec<Keypair>) {
+    let casino = Casino::new();
+    casino
+        .create_tournament(TOURNAMENT, FEE, scoring, prize_split)
+        .unwrap();
+    let players = (0..count)
+        .map(|_| {
+            let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+            casino.enter_tournament(&player, TOURNAMENT).unwrap();
+            player
+        })
+        .collect();
+    (casino, players)
+}
+
+fn rank(casino: &Casino, player: &Keypair) -> TransactionResult {
+    casino.send(
+        &[rank_tournament_entry_ix(&player.pubkey(), TOURNAMENT)],
+        &[&casino.payer],
+    )
+}
+
+fn finalize(casino: &Casino, winners: &[&Keypair]) -> TransactionResult {
+    let winners: Vec<_> = winners.iter().map(|winner| winner.pubkey()).collect();
+    casino.send(
+        &[finalize_tournament_ix(TOURNAMENT, &winners)],
+        &[&casino.payer],
+    )
+}
+
+#[test]
+fn entry_fees_fill_the_prize_pool() {
+    let (casino, players) = running_tournament(TOURNAMENT_SCORE_VOLUME, vec![600, 400], 2);
+
+    assert_eq!(casino.prize_pool(TOURNAMENT), 2 * FEE);
+    assert_eq!(casino.tournament(TOURNAMENT).entrants, 2);
+    let entry = casino.tournament_entry(TOURNAMENT, &players[0].pubkey());
+    assert_eq!(entry.player, players[0].pubkey());
+    assert_eq!(entry.tournament, tournament(TOURNAMENT));
+
+    assert!(casino.enter_tournament(&players[0], TOURNAMENT).is_err());
+    assert_eq!(casino.tournament(TOURNAMENT).entrants, 2);
+}
+
+#[test]
+fn games_score_the_players_entry() {
+    let (casino, players) = running_tournament(TOURNAMENT_SCORE_NET_PROFIT, vec![1000], 1);
+    let player = &players[0];
+
+    casino
+        .play_tournament_game(player, TOURNAMENT, BET, SESSION)
+        .unwrap();
+    let entry = casino.tournament_entry(TOURNAMENT, &player.pubkey());
+    assert_eq!(entry.volume, BET);
+    assert_eq!(entry.net_profit, -(BET as i64));
+    let pool = casino.player_pool(&player.pubkey(), SESSION).unwrap();
+    assert_eq!(pool.tournament, tournament(TOURNAMENT));
+
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+    casino
+        .claim_tournament_reward(&player.pubkey(), TOURNAMENT, SESSION)
+        .unwrap();
+
+    let entry = casino.tournament_entry(TOURNAMENT, &player.pubkey());
+    assert_eq!(
+        entry.net_profit,
+        win_amount(BET, RTP).unwrap() as i64 - BET as i64
+    );
+    assert_eq!(entry.best_streak, 1);
+}
+
+#[test]
+fn every_game_and_double_bet_is_scored() {
+    let (casino, players) = running_tournament(TOURNAMENT_SCORE_NET_PROFIT, vec![1000], 1);
+    let player = &players[0];
+    let operator = casino.operator.pubkey();
+
+    casino
+        .send(
+            &[play_dice_in_tournament_ix(
+                &player.pubkey(),
+                &operator,
+                Some(TOURNAMENT),
+                50,
+                BET,
+                SESSION,
+            )],
+            &[player, &casino.operator],
+        )
+        .unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+    let doubled = casino.balance(&game_vault(&player.pubkey(), SESSION));
+    casino
+        .send(
+            &[double_bet_in_tournament_ix(
+                &player.pubkey(),
+                &operator,
+                Some(TOURNAMENT),
+                true,
+                SESSION,
+            )],
+            &[player, &casino.operator],
+        )
+        .unwrap();
+
+    // The double bet stakes winnings the entry was never credited with
+    let entry = casino.tournament_entry(TOURNAMENT, &player.pubkey());
+    assert_eq!(entry.volume, BET + doubled);
+    assert_eq!(entry.net_profit, -(BET as i64));
+
+    casino
+        .set_result(&player.pubkey(), 2, true, SESSION)
+        .unwrap();
+    let payout = casino.balance(&game_vault(&player.pubkey(), SESSION));
+    casino
+        .claim_tournament_reward(&player.pubkey(), TOURNAMENT, SESSION)
+        .unwrap();
+
+    let entry = casino.tournament_entry(TOURNAMENT, &player.pubkey());
+    assert_eq!(entry.net_profit, payout as i64 - BET as i64);
+    assert_eq!(entry.best_streak, 2);
+}
+
+#[test]
+fn games_need_an_entry_in_a_running_tournament() {
+    let (casino, players) = running_tournament(TOURNAMENT_SCORE_NET_PROFIT, vec![1000], 1);
+    let stranger = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    assert_anchor_error(
+        casino.play_tournament_game(&stranger, TOURNAMENT, BET, SESSION),
+        ErrorCode::AccountNotInitialized,
+    );
+
+    casino.advance_clock(3600);
+    assert_game_error(
+        casino.play_tournament_game(&players[0], TOURNAMENT, BET, SESSION),
+        GameError::TournamentNotLive,
+    );
+    assert_game_error(
+        casino.enter_tournament(&stranger, TOURNAMENT),
+        GameError::TournamentNotLive,
+    );
+}
+
+#[test]
+fn claims_after_the_end_are_not_scored() {
+    let (casino, players) = running_tournament(TOURNAMENT_SCORE_NET_PROFIT, vec![1000], 1);
+    let player = &players[0];
+    casino
+        .play_tournament_game(player, TOURNAMENT, BET, SESSION)
+        .unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, true, SESSION)
+        .unwrap();
+    casino.advance_clock(3600);
+
+    casino
+        .claim_tournament_reward(&player.pubkey(), TOURNAMENT, SESSION)
+        .unwrap();
+
+    let entry = casino.tournament_entry(TOURNAMENT, &player.pubkey());
+    assert_eq!(entry.net_profit, -(BET as i64));
+}
+
+#[test]
+fn every_claim_of_a_tournament_game_is_scored() {
+    let (casino, players) = running_tournament(TOURNAMENT_SCORE_NET_PROFIT, vec![1000], 1);
+    let player = &players[0];
+    let key = Keypair::new();
+    casino.deposit_balance(player, BET).unwrap();
+    casino
+        .create_session_key(player, &key.pubkey(), BET, SESSION_ALLOW_CLAIM)
+        .unwrap();
+    for session in [SESSION, SESSION + 1, SESSION + 2] {
+        casino
+            .play_tournament_game(player, TOURNAMENT, BET, session)
+            .unwrap();
+        casino
+            .set_result(&player.pubkey(), 1, true, session)
+            .unwrap();
+    }
+    let operator = casino.operator.pubkey();
+    let owner = player.pubkey();
+
+    // The operator cannot leave the entry out to keep a win off the scores
+    assert_game_error(
+        casino.claim_reward(&owner, SESSION),
+        GameError::TournamentEntryRequired,
+    );
+    assert_game_error(
+        casino.claim_reward_to_balance(&owner, SESSION + 1),
+        GameError::TournamentEntryRequired,
+    );
+    assert_game_error(
+        casino.send(
+            &[claim_reward_with_session_key_ix(
+                &key.pubkey(),
+                &owner,
+                &operator,
+                SESSION + 2,
+            )],
+            &[&key],
+        ),
+        GameError::TournamentEntryRequired,
+    );
+
+    casino
+        .claim_tournament_reward(&owner, TOURNAMENT, SESSION)
+        .unwrap();
+    casino
+        .send(
+            &[claim_reward_to_balance_in_tournament_ix(
+                &operator,
+                &owner,
+                Some(TOURNAMENT),
+                SESSION + 1,
+            )],
+            &[&casino.operator],
+        )
+        .unwrap();
+    casino
+        .send(
+            &[claim_reward_with_session_key_in_tournament_ix(
+                &key.pubkey(),
+                &owner,
+                &operator,
+                Some(TOURNAMENT),
+                SESSION + 2,
+            )],
+            &[&key],
+        )
+        .unwrap();
+
+    let entry = casino.tournament_entry(TOURNAMENT, &owner);
+    assert_eq!(
+        entry.net_profit,
+        3 * (win_amount(BET, RTP).unwrap() as i64 - BET as i64)
+    );
+}
+
+#[test]
+fn finalize_pays_the_ranked_places() {
+    let (casino, players) = running_tournament(TOURNAMENT_SCORE_VOLUME, vec![500, 300], 3);
+    for (session, (player, bet)) in players.iter().zip([BET, 3 * BET, 2 * BET]).enumerate() {
+        casino
+            .play_tournament_game(player, TOURNAMENT, bet, session as u64)
+            .unwrap();
+    }
+    let sponsor = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino
+        .send(
+            &[fund_tournament_ix(
+                &sponsor.pubkey(),
+                TOURNAMENT,
+                LAMPORTS_PER_SOL,
+            )],
+            &[&sponsor],
+        )
+        .unwrap();
+    let pool = 3 * FEE + LAMPORTS_PER_SOL;
+    assert_eq!(casino.prize_pool(TOURNAMENT), pool);
+
+    assert_game_error(rank(&casino, &players[0]), GameError::TournamentNotOver);
+    casino.advance_clock(3600);
+    for player in &players {
+        rank(&casino, player).unwrap();
+    }
+    assert_game_error(rank(&casino, &players[1]), GameError::EntryAlreadyRanked);
+
+    let leaders = casino.tournament(TOURNAMENT).leaders;
+    assert_eq!(leaders.len(), 2);
+    assert_eq!(leaders[0].player, players[1].pubkey());
+    assert_eq!(leaders[0].score, 3 * BET as i64);
+    assert_eq!(leaders[1].player, players[2].pubkey());
+
+    assert_game_error(
+        finalize(&casino, &[&players[1], &players[2]]),
+        GameError::TournamentRanking,
+    );
+    casino.advance_clock(TOURNAMENT_RANKING_PERIOD);
+    assert_game_error(
+        finalize(&casino, &[&players[2], &players[1]]),
+        GameError::InvalidPrizeRecipient,
+    );
+    assert_game_error(
+        finalize(&casino, &[&players[1]]),
+        GameError::InvalidPrizeRecipient,
+    );
+
+    let first = casino.balance(&players[1].pubkey());
+    let second = casino.balance(&players[2].pubkey());
+    let vault = casino.balance(&casino_vault());
+    finalize(&casino, &[&players[1], &players[2]]).unwrap();
+
+    assert_eq!(casino.balance(&players[1].pubkey()), first + pool / 2);
+    assert_eq!(casino.balance(&players[2].pubkey()), second + pool * 3 / 10);
+    assert_eq!(
+        casino.balance(&casino_vault()),
+        vault + pool - pool / 2 - pool * 3 / 10
+    );
+    assert_eq!(casino.prize_pool(TOURNAMENT), 0);
+    assert!(casino.tournament(TOURNAMENT).finalized);
+    assert_game_error(
+        finalize(&casino, &[&players[1], &players[2]]),
+        GameError::TournamentFinalized,
+    );
+}
+
+#[test]
+fn update_authority_creates_valid_tournaments() {
+    let casino = Casino::new();
+    let now = casino.now();
+    let stranger = casino.new_player(10 * LAMPORTS_PER_SOL);
+
+    assert_game_error(
+        casino.send(
+            &[create_tournament_ix(
+                &stranger.pubkey(),
+                TOURNAMENT,
+                now,
+                now + 3600,
+                0,
+                TOURNAMENT_SCORE_VOLUME,
+                vec![1000],
+            )],
+            &[&stranger],
+        ),
+        GameError::UnauthorizedUpdateAdmin,
+    );
+
+    for (start_at, end_at, scoring, prize_split) in [
+        (now, now, TOURNAMENT_SCORE_VOLUME, vec![1000]),
+        (now - 7200, now - 3600, TOURNAMENT_SCORE_VOLUME, vec![1000]),
+        (now, now + 3600, 3, vec![1000]),
+        (now, now + 3600, TOURNAMENT_SCORE_VOLUME, vec![]),
+        (now, now + 3600, TOURNAMENT_SCORE_VOLUME, vec![600, 401]),
+        (now, now + 3600, TOURNAMENT_SCORE_VOLUME, vec![10; 11]),
+    ] {
+        assert_game_error(
+            casino.send(
+                &[create_tournament_ix(
+                    &casino.update.pubkey(),
+                    TOURNAMENT,
+                    start_at,
+                    end_at,
+                    0,
+                    scoring,
+                    prize_split,
+                )],
+                &[&casino.update],
+            ),
+            GameError::InvalidTournament,
+        );
+    }
+}
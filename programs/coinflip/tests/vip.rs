@@ -0,0 +1,163 @@
+mod common;
+
+use coinflip::{
+    account::VipTier,
+    constants::{MAX_VIP_TIERS, RTP, SETTLEMENT_TIMEOUT},
+    error::GameError,
+    math::rakeback_amount,
+};
+use common::*;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer};
+
+const BET: u64 = LAMPORTS_PER_SOL / 2;
+
+fn tier(threshold: u64, rakeback: u16) -> VipTier {
+    VipTier {
+        threshold,
+        rakeback,
+    }
+}
+
+/// A casino with two VIP tiers and a player with their stats open.
+fn vip_player() -> (Casino, Keypair) {
+    let casino = Casino::new();
+    casino
+        .set_vip_config(vec![tier(2 * BET, 100), tier(4 * BET, 500)])
+        .unwrap();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.open_player_stats(&player.pubkey()).unwrap();
+    (casino, player)
+}
+
+/// Plays a game of `bet` that `player` loses.
+fn lose(casino: &Casino, player: &Keypair, bet: u64, session: u64) {
+    casino.play_game(player, true, bet, session).unwrap();
+    casino
+        .set_result(&player.pubkey(), 1, false, session)
+        .unwrap();
+}
+
+#[test]
+fn stakes_accrue_the_tier_rakeback() {
+    let (casino, player) = vip_player();
+
+    lose(&casino, &player, BET, 1);
+    let stats = casino.player_stats(&player.pubkey());
+    assert_eq!((stats.vip_tier, stats.rakeback), (0, 0));
+
+    lose(&casino, &player, 2 * BET, 2);
+    let first = rakeback_amount(2 * BET, RTP, 100).unwrap();
+    let stats = casino.player_stats(&player.pubkey());
+    assert_eq!((stats.vip_tier, stats.rakeback), (1, first));
+
+    lose(&casino, &player, 2 * BET, 3);
+    let second = rakeback_amount(2 * BET, RTP, 500).unwrap();
+    let stats = casino.player_stats(&player.pubkey());
+    assert_eq!((stats.vip_tier, stats.rakeback), (2, first + second));
+    assert!(first > 0 && second > first);
+}
+
+#[test]
+fn claim_rakeback_pays_from_the_casino_vault() {
+    let (casino, player) = vip_player();
+    assert_game_error(casino.claim_rakeback(&player), GameError::NoRakeback);
+    lose(&casino, &player, 4 * BET, 1);
+    let rakeback = casino.player_stats(&player.pubkey()).rakeback;
+    let wallet = casino.balance(&player.pubkey());
+    let vault = casino.balance(&casino_vault());
+
+    casino.claim_rakeback(&player).unwrap();
+
+    assert_eq!(casino.balance(&player.pubkey()), wallet + rakeback);
+    assert_eq!(casino.balance(&casino_vault()), vault - rakeback);
+    let stats = casino.player_stats(&player.pubkey());
+    assert_eq!((stats.rakeback, stats.rakeback_paid), (0, rakeback));
+    assert_game_error(casino.claim_rakeback(&player), GameError::NoRakeback);
+}
+
+#[test]
+fn stakes_accrue_no_rakeback_before_the_tiers_are_set() {
+    let casino = Casino::new();
+    let player = casino.new_player(10 * LAMPORTS_PER_SOL);
+    casino.open_player_stats(&player.pubkey()).unwrap();
+    lose(&casino, &player, 4 * BET, 1);
+
+    let stats = casino.player_stats(&player.pubkey());
+    assert_eq!(stats.total_volume, 4 * BET);
+    assert_eq!((stats.vip_tier, stats.rakeback), (0, 0));
+}
+
+#[test]
+fn bets_accrue_rakeback_once_settled() {
+    let (casino, player) = vip_player();
+    casino.play_dice(&player, 50, 4 * BET, 1).unwrap();
+
+    let stats = casino.player_stats(&player.pubkey());
+    assert_eq!((stats.total_volume, stats.rakeback), (0, 0));
+
+    casino.set_result(&player.pubkey(), 1, false, 1).unwrap();
+    let stats = casino.player_stats(&player.pubkey());
+    assert_eq!(stats.total_volume, 4 * BET);
+    assert_eq!(
+        (stats.vip_tier, stats.rakeback),
+        (2, rakeback_amount(4 * BET, RTP, 500).unwrap())
+    );
+}
+
+#[test]
+fn promo_stakes_accrue_nothing() {
+    let (casino, player) = vip_player();
+    casino
+        .issue_promo_credit(&player.pubkey(), 4 * BET, 4 * BET)
+        .unwrap();
+    casino
+        .play_game_with_promo_credit(&player, true, 4 * BET, 1)
+        .unwrap();
+    casino.set_result(&player.pubkey(), 1, false, 1).unwrap();
+
+    let stats = casino.player_stats(&player.pubkey());
+    assert_eq!((stats.total_volume, stats.rakeback), (0, 0));
+}
+
+#[test]
+fn slashed_rounds_accrue_nothing() {
+    let (casino, player) = vip_player();
+    casino.post_bond(5 * LAMPORTS_PER_SOL).unwrap();
+    casino.play_game(&player, true, 4 * BET, 1).unwrap();
+
+    casino.advance_clock(SETTLEMENT_TIMEOUT + 1);
+    casino.slash_operator(&player, 1).unwrap();
+
+    let stats = casino.player_stats(&player.pubkey());
+    assert_eq!((stats.total_volume, stats.rakeback), (0, 0));
+}
+
+#[test]
+fn update_authority_sets_valid_tiers() {
+    let casino = Casino::new();
+    assert!(casino.vip_config().tiers.is_empty());
+
+    let stranger = casino.new_player(10 * LAMPORTS_PER_SOL);
+    assert_game_error(
+        casino.send(
+            &[set_vip_config_ix(&stranger.pubkey(), vec![tier(BET, 100)])],
+            &[&stranger],
+        ),
+        GameError::UnauthorizedUpdateAdmin,
+    );
+
+    for tiers in [
+        vec![tier(BET, 100), tier(BET, 200)],
+        vec![tier(2 * BET, 100), tier(BET, 200)],
+        vec![tier(BET, 1001)],
+        (1..=MAX_VIP_TIERS as u64 + 1)
+            .map(|n| tier(n * BET, 100))
+            .collect(),
+    ] {
+        assert_game_error(casino.set_vip_config(tiers), GameError::InvalidVipTiers);
+    }
+
+    let tiers = vec![tier(BET, 100), tier(10 * BET, 1000)];
+    casino.set_vip_config(tiers.clone()).unwrap();
+    assert_eq!(casino.vip_config().tiers, tiers);
+}
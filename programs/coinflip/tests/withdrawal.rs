@@ -0,0 +1,179 @@
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use coinflip::{constants::MAX_WITHDRAWAL_APPROVERS, error::GameError};
+use common::*;
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Keypair, signer::Signer,
+};
+
+const AMOUNT: u64 = 3 * LAMPORTS_PER_SOL;
+const TIMEOUT: i64 = 3600;
+
+/// A casino whose withdrawals need 2 of 3 approvers.
+fn multisig_casino() -> (Casino, [Keypair; 3]) {
+    let casino = Casino::new();
+    let approvers = [Keypair::new(), Keypair::new(), Keypair::new()];
+    casino
+        .set_withdrawal_approvers(&approvers.iter().collect::<Vec<_>>(), 2, TIMEOUT)
+        .unwrap();
+    (casino, approvers)
+}
+
+#[test]
+fn approved_proposals_pay_the_recipient() {
+    let (casino, [a, b, c]) = multisig_casino();
+    let recipient = Pubkey::new_unique();
+    let proposal_id = casino.propose_withdrawal(&recipient, AMOUNT);
+
+    let proposal = casino.withdrawal_proposal(proposal_id).unwrap();
+    assert_eq!(
+        (proposal.recipient, proposal.amount, proposal.expires_at),
+        (recipient, AMOUNT, casino.now() + TIMEOUT)
+    );
+
+    casino.approve_withdrawal(&a, proposal_id).unwrap();
+    assert_game_error(
+        casino.approve_withdrawal(&a, proposal_id),
+        GameError::WithdrawalAlreadyApproved,
+    );
+    assert_game_error(
+        casino.execute_withdrawal(&c, proposal_id),
+        GameError::WithdrawalNotApproved,
+    );
+
+    casino.approve_withdrawal(&b, proposal_id).unwrap();
+    casino.execute_withdrawal(&c, proposal_id).unwrap();
+
+    assert_eq!(casino.balance(&recipient), AMOUNT);
+    assert_eq!(casino.balance(&casino_vault()), CASINO_FUNDS - AMOUNT);
+    assert!(casino.withdrawal_proposal(proposal_id).is_none());
+    assert_eq!(
+        casino.propose_withdrawal(&recipient, AMOUNT),
+        proposal_id + 1
+    );
+}
+
+#[test]
+fn approvers_replace_the_single_signer_withdraw() {
+    let (casino, _) = multisig_casino();
+    let withdraw = || {
+        casino.send(
+            &[withdraw_ix(
+                &casino.finance.pubkey(),
+                &casino.finance.pubkey(),
+                AMOUNT,
+            )],
+            &[&casino.finance],
+        )
+    };
+    assert_game_error(withdraw(), GameError::WithdrawalApprovalRequired);
+
+    casino.set_withdrawal_approvers(&[], 0, TIMEOUT).unwrap();
+    withdraw().unwrap();
+}
+
+#[test]
+fn expired_proposals_can_only_be_cancelled() {
+    let (casino, [a, b, _]) = multisig_casino();
+    let proposal_id = casino.propose_withdrawal(&Pubkey::new_unique(), AMOUNT);
+    casino.approve_withdrawal(&a, proposal_id).unwrap();
+
+    casino.advance_clock(TIMEOUT);
+    assert_game_error(
+        casino.approve_withdrawal(&b, proposal_id),
+        GameError::WithdrawalProposalExpired,
+    );
+    assert_game_error(
+        casino.execute_withdrawal(&a, proposal_id),
+        GameError::WithdrawalProposalExpired,
+    );
+
+    casino
+        .cancel_withdrawal(&casino.finance, proposal_id)
+        .unwrap();
+    assert!(casino.withdrawal_proposal(proposal_id).is_none());
+    assert_eq!(casino.balance(&casino_vault()), CASINO_FUNDS);
+}
+
+#[test]
+fn only_current_approvers_count() {
+    let (casino, [a, b, c]) = multisig_casino();
+    let stranger = Keypair::new();
+    let proposal_id = casino.propose_withdrawal(&Pubkey::new_unique(), AMOUNT);
+
+    assert_game_error(
+        casino.approve_withdrawal(&stranger, proposal_id),
+        GameError::UnauthorizedWithdrawalSigner,
+    );
+    casino.approve_withdrawal(&a, proposal_id).unwrap();
+    casino.approve_withdrawal(&b, proposal_id).unwrap();
+    assert_game_error(
+        casino.execute_withdrawal(&stranger, proposal_id),
+        GameError::UnauthorizedWithdrawalSigner,
+    );
+    assert_game_error(
+        casino.cancel_withdrawal(&stranger, proposal_id),
+        GameError::UnauthorizedWithdrawalSigner,
+    );
+
+    // Dropping `a` leaves the proposal one approval short
+    casino
+        .set_withdrawal_approvers(&[&b, &c], 2, TIMEOUT)
+        .unwrap();
+    assert_game_error(
+        casino.execute_withdrawal(&casino.finance, proposal_id),
+        GameError::WithdrawalNotApproved,
+    );
+    casino.approve_withdrawal(&c, proposal_id).unwrap();
+    assert_eq!(
+        casino.withdrawal_proposal(proposal_id).unwrap().approvals,
+        vec![b.pubkey(), c.pubkey()]
+    );
+    casino
+        .execute_withdrawal(&casino.finance, proposal_id)
+        .unwrap();
+}
+
+#[test]
+fn super_admin_sets_valid_approvers() {
+    let casino = Casino::new();
+    let approvers: Vec<Keypair> = (0..=MAX_WITHDRAWAL_APPROVERS)
+        .map(|_| Keypair::new())
+        .collect();
+    let [a, b] = [&approvers[0], &approvers[1]];
+
+    for (approvers, threshold, timeout) in [
+        (vec![a, a], 1, TIMEOUT),
+        (vec![a, b], 3, TIMEOUT),
+        (vec![a, b], 0, TIMEOUT),
+        (vec![a, b], 2, 0),
+        (approvers.iter().collect(), 1, TIMEOUT),
+    ] {
+        assert_game_error(
+            casino.set_withdrawal_approvers(&approvers, threshold, timeout),
+            GameError::InvalidWithdrawalApprovers,
+        );
+    }
+
+    assert_game_error(
+        casino.send(
+            &[set_withdrawal_approvers_ix(
+                &casino.finance.pubkey(),
+                vec![a.pubkey()],
+                1,
+                TIMEOUT,
+            )],
+            &[&casino.finance],
+        ),
+        GameError::UnauthorizedSuperAdmin,
+    );
+
+    casino
+        .set_withdrawal_approvers(&[a, b], 2, TIMEOUT)
+        .unwrap();
+    let config = casino.withdrawal_config();
+    assert_eq!(config.approvers, vec![a.pubkey(), b.pubkey()]);
+    assert_eq!((config.threshold, config.proposal_timeout), (2, TIMEOUT));
+}
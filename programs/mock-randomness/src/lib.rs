@@ -0,0 +1,163 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
+
+declare_id!("J45m2c95dKDpZYjt6Hmh2awbSiVwihQzqru1zk6DKoKc");
+
+pub const REQUEST_SEED: &str = "request";
+pub const PROVIDER_AUTHORITY_SEED: &str = "provider-authority";
+
+pub const MAX_CALLBACK_ACCOUNTS: usize = 16;
+pub const MAX_CALLBACK_DATA: usize = 64;
+
+/// Randomness provider for local tests, implementing the interface described
+/// in `coinflip::randomness`. Whoever calls `fulfill` chooses the randomness,
+/// so tests decide every outcome.
+#[program]
+pub mod mock_randomness {
+    use super::*;
+
+    pub fn request_randomness(
+        ctx: Context<RequestRandomness>,
+        seed: [u8; 32],
+        callback_program: Pubkey,
+        callback_data: Vec<u8>,
+    ) -> Result<()> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_CALLBACK_ACCOUNTS
+                && callback_data.len() <= MAX_CALLBACK_DATA,
+            MockError::CallbackTooLarge
+        );
+
+        let request = &mut ctx.accounts.request;
+        request.requester = ctx.accounts.requester.key();
+        request.seed = seed;
+        request.callback_program = callback_program;
+        request.callback_accounts = ctx
+            .remaining_accounts
+            .iter()
+            .map(|account| CallbackAccount {
+                pubkey: account.key(),
+                is_writable: account.is_writable,
+            })
+            .collect();
+        request.callback_data = callback_data;
+
+        Ok(())
+    }
+
+    /// Call the requester back with `randomness`, passing the callback
+    /// accounts of the request as remaining accounts.
+    pub fn fulfill<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Fulfill<'info>>,
+        randomness: [u8; 32],
+    ) -> Result<()> {
+        let request = &ctx.accounts.request;
+        require!(
+            ctx.remaining_accounts.len() == request.callback_accounts.len()
+                && ctx
+                    .remaining_accounts
+                    .iter()
+                    .zip(&request.callback_accounts)
+                    .all(|(info, account)| info.key() == account.pubkey),
+            MockError::CallbackAccountMismatch
+        );
+
+        let authority = &ctx.accounts.provider_authority;
+        let mut accounts = vec![AccountMeta::new_readonly(authority.key(), true)];
+        accounts.extend(request.callback_accounts.iter().map(|account| AccountMeta {
+            pubkey: account.pubkey,
+            is_signer: false,
+            is_writable: account.is_writable,
+        }));
+
+        let mut data = request.callback_data.clone();
+        data.extend_from_slice(&randomness);
+
+        let mut infos = vec![
+            authority.to_account_info(),
+            ctx.accounts.callback_program.to_account_info(),
+        ];
+        infos.extend_from_slice(ctx.remaining_accounts);
+
+        invoke_signed(
+            &Instruction {
+                program_id: request.callback_program,
+                accounts,
+                data,
+            },
+            &infos,
+            &[&[
+                PROVIDER_AUTHORITY_SEED.as_bytes(),
+                &[ctx.bumps.provider_authority],
+            ]],
+        )?;
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(seed: [u8; 32])]
+pub struct RequestRandomness<'info> {
+    pub requester: Signer<'info>,
+
+    #[account(
+        init,
+        space = 8 + Request::INIT_SPACE,
+        seeds = [REQUEST_SEED.as_bytes(), requester.key().as_ref(), &seed],
+        bump,
+        payer = payer
+    )]
+    pub request: Account<'info, Request>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Fulfill<'info> {
+    #[account(mut)]
+    pub fulfiller: Signer<'info>,
+
+    #[account(mut, close = fulfiller)]
+    pub request: Account<'info, Request>,
+
+    #[account(seeds = [PROVIDER_AUTHORITY_SEED.as_bytes()], bump)]
+    /// CHECK: PDA signing the callback
+    pub provider_authority: UncheckedAccount<'info>,
+
+    #[account(address = request.callback_program)]
+    /// CHECK: The program the requester asked to be called back
+    pub callback_program: UncheckedAccount<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Request {
+    pub requester: Pubkey,
+    pub seed: [u8; 32],
+    pub callback_program: Pubkey,
+    #[max_len(MAX_CALLBACK_ACCOUNTS)]
+    pub callback_accounts: Vec<CallbackAccount>,
+    #[max_len(MAX_CALLBACK_DATA)]
+    pub callback_data: Vec<u8>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct CallbackAccount {
+    pub pubkey: Pubkey,
+    pub is_writable: bool,
+}
+
+#[error_code]
+pub enum MockError {
+    #[msg("Too many callback accounts or too much callback data")]
+    CallbackTooLarge,
+    #[msg("Callback accounts do not match the request")]
+    CallbackAccountMismatch,
+}
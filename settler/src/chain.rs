@@ -0,0 +1,137 @@
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anyhow::{anyhow, Result};
+use coinflip::{
+    account::{GlobalPool, PlayerPool},
+    constants::GLOBAL_AUTHORITY_SEED,
+};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+
+// Offset of `PlayerPool::status`: discriminator (8) + first_bet (8)
+const STATUS_OFFSET: usize = 8 + 8;
+// Borsh index of `GameStatus::Processing`
+const STATUS_PROCESSING: u8 = 4;
+
+/// Everything the settler needs from the cluster, so it can run against a
+/// local stand-in as well as a real RPC node.
+pub trait Chain {
+    /// The operation authority signing every settlement.
+    fn operator(&self) -> Pubkey;
+
+    /// All player pools currently waiting for a result.
+    fn pending_games(&self) -> Result<Vec<(Pubkey, PlayerPool)>>;
+
+    /// The program's global pool.
+    fn global(&self) -> Result<GlobalPool>;
+
+    /// Re-read one player pool, `None` once it has been closed.
+    fn game(&self, player_pool: &Pubkey) -> Result<Option<PlayerPool>>;
+
+    /// The slot the cluster is at.
+    fn slot(&self) -> Result<u64>;
+
+    /// Sign the instructions with the operator and wait for confirmation.
+    fn send(&self, instructions: &[Instruction]) -> Result<Signature>;
+}
+
+pub struct RpcChain {
+    client: RpcClient,
+    operator: Keypair,
+}
+
+impl RpcChain {
+    pub fn new(rpc_url: String, operator: Keypair) -> Self {
+        Self {
+            client: RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed()),
+            operator,
+        }
+    }
+}
+
+impl Chain for RpcChain {
+    fn operator(&self) -> Pubkey {
+        self.operator.pubkey()
+    }
+
+    fn pending_games(&self) -> Result<Vec<(Pubkey, PlayerPool)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, PlayerPool::DISCRIMINATOR.to_vec())),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    STATUS_OFFSET,
+                    vec![STATUS_PROCESSING],
+                )),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+
+        let accounts = self
+            .client
+            .get_program_accounts_with_config(&coinflip::ID, config)?;
+
+        accounts
+            .into_iter()
+            .map(|(key, account)| {
+                let pool = PlayerPool::try_deserialize(&mut account.data.as_slice())?;
+                Ok((key, pool))
+            })
+            .collect()
+    }
+
+    fn global(&self) -> Result<GlobalPool> {
+        let (global_authority, _) =
+            Pubkey::find_program_address(&[GLOBAL_AUTHORITY_SEED.as_bytes()], &coinflip::ID);
+        let account = self.client.get_account(&global_authority)?;
+        Ok(GlobalPool::try_deserialize(&mut account.data.as_slice())?)
+    }
+
+    fn game(&self, player_pool: &Pubkey) -> Result<Option<PlayerPool>> {
+        let account = self
+            .client
+            .get_account_with_commitment(player_pool, CommitmentConfig::confirmed())?
+            .value;
+
+        match account {
+            Some(account) if account.owner == coinflip::ID && !account.data.is_empty() => {
+                // Lost games are zeroed before the lamports drain, so a failed
+                // deserialization means the pool is already closed
+                Ok(PlayerPool::try_deserialize(&mut account.data.as_slice()).ok())
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn slot(&self) -> Result<u64> {
+        Ok(self.client.get_slot()?)
+    }
+
+    fn send(&self, instructions: &[Instruction]) -> Result<Signature> {
+        let blockhash = self.client.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.operator.pubkey()),
+            &[&self.operator],
+            blockhash,
+        );
+
+        self.client
+            .send_and_confirm_transaction(&tx)
+            .map_err(|e| anyhow!("set_result failed: {e}"))
+    }
+}
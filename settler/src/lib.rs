@@ -0,0 +1,11 @@
+pub mod chain;
+pub mod progress;
+pub mod settler;
+
+pub use chain::{Chain, RpcChain};
+pub use progress::{Progress, Submission};
+pub use settler::{
+    batch_outcome, game_outcome, hash_chain_element, publish_hash_chain_instruction,
+    set_batch_result_instructions, set_result_instructions, settle_with_reveal_instructions,
+    settle_with_slot_hash_instructions, Settler, SettlerConfig,
+};
@@ -0,0 +1,46 @@
+use std::{env, fs, thread, time::Duration};
+
+use anyhow::{anyhow, Context, Result};
+use coinflip_settler::{Progress, RpcChain, Settler, SettlerConfig};
+use solana_sdk::signature::read_keypair_file;
+
+fn var_or(name: &str, default: &str) -> String {
+    env::var(name).unwrap_or_else(|_| default.to_string())
+}
+
+fn main() -> Result<()> {
+    let rpc_url = var_or("COINFLIP_RPC_URL", "http://127.0.0.1:8899");
+    let keypair_path = var_or("COINFLIP_OPERATOR_KEYPAIR", "./operator.json");
+    let seed_path = var_or("COINFLIP_SERVER_SEED", "./server-seed.bin");
+    let state_path = var_or("COINFLIP_STATE_PATH", "./settler-state.txt");
+    let poll_interval = Duration::from_millis(var_or("COINFLIP_POLL_INTERVAL_MS", "2000").parse()?);
+
+    let operator = read_keypair_file(&keypair_path)
+        .map_err(|e| anyhow!("reading operator keypair {keypair_path}: {e}"))?;
+    let server_seed =
+        fs::read(&seed_path).with_context(|| format!("reading server seed {seed_path}"))?;
+
+    let config = SettlerConfig {
+        server_seed,
+        priority_fee: var_or("COINFLIP_PRIORITY_FEE", "10000").parse()?,
+        hash_chain_length: var_or("COINFLIP_HASH_CHAIN_LENGTH", "0").parse()?,
+        ..SettlerConfig::default()
+    };
+
+    let progress = Progress::load(&state_path)?;
+    println!(
+        "Resuming with {} settlement(s) in flight from {state_path}",
+        progress.len()
+    );
+
+    let mut settler = Settler::new(RpcChain::new(rpc_url, operator), config, progress);
+
+    loop {
+        match settler.run_once() {
+            Ok(0) => {}
+            Ok(settled) => println!("Settled {settled} game(s)"),
+            Err(e) => eprintln!("Polling pending games failed: {e}"),
+        }
+        thread::sleep(poll_interval);
+    }
+}
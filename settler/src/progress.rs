@@ -0,0 +1,103 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{anyhow, Context, Result};
+use solana_sdk::pubkey::Pubkey;
+
+/// A settlement that was handed to the cluster for a given round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Submission {
+    pub round: u8,
+    pub submitted_at: u64,
+}
+
+/// Settlements in flight, persisted after every change so a restarted
+/// daemon does not resubmit rounds whose transaction may still land.
+///
+/// The file holds one `<player_pool> <round> <submitted_at>` line per entry.
+pub struct Progress {
+    path: PathBuf,
+    entries: HashMap<Pubkey, Submission>,
+}
+
+impl Progress {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut entries = HashMap::new();
+
+        if path.exists() {
+            let contents =
+                fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+
+            for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+                let mut fields = line.split_whitespace();
+                let (Some(key), Some(round), Some(submitted_at)) =
+                    (fields.next(), fields.next(), fields.next())
+                else {
+                    return Err(anyhow!("malformed progress line: {line}"));
+                };
+
+                entries.insert(
+                    Pubkey::from_str(key)?,
+                    Submission {
+                        round: round.parse()?,
+                        submitted_at: submitted_at.parse()?,
+                    },
+                );
+            }
+        }
+
+        Ok(Self { path, entries })
+    }
+
+    pub fn get(&self, player_pool: &Pubkey) -> Option<Submission> {
+        self.entries.get(player_pool).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn record(&mut self, player_pool: Pubkey, submission: Submission) -> Result<()> {
+        self.entries.insert(player_pool, submission);
+        self.save()
+    }
+
+    /// Forget every pool that is no longer waiting for a result.
+    pub fn retain_pending(&mut self, pending: &[Pubkey]) -> Result<()> {
+        let before = self.entries.len();
+        self.entries.retain(|key, _| pending.contains(key));
+
+        if self.entries.len() != before {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    fn save(&self) -> Result<()> {
+        let tmp = self.path.with_extension("tmp");
+        let mut file =
+            fs::File::create(&tmp).with_context(|| format!("writing {}", tmp.display()))?;
+
+        for (key, submission) in &self.entries {
+            writeln!(
+                file,
+                "{} {} {}",
+                key, submission.round, submission.submitted_at
+            )?;
+        }
+        file.sync_all()?;
+
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,486 @@
+use std::{
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anyhow::{anyhow, Result};
+use coinflip::{
+    account::{GameStatus, GlobalPool, PlayerPool},
+    constants::{
+        GLOBAL_AUTHORITY_SEED, PLAYER_POOL_SEED, PLAYER_STATS_SEED, VAULT_AUTHORITY_SEED,
+        VIP_CONFIG_SEED,
+    },
+    utils::{batch_outcomes_from_randomness, derive_game_randomness, is_win_from_randomness},
+};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    hash::{hash, hashv},
+    instruction::Instruction,
+    pubkey::Pubkey,
+    sysvar,
+};
+
+use crate::{
+    chain::Chain,
+    progress::{Progress, Submission},
+};
+
+pub struct SettlerConfig {
+    /// Operator secret mixed into every outcome, see `derive_game_randomness`.
+    pub server_seed: Vec<u8>,
+    /// Priority fee in micro-lamports per compute unit.
+    pub priority_fee: u64,
+    pub compute_unit_limit: u32,
+    pub max_retries: u32,
+    pub retry_backoff: Duration,
+    /// How long a submission may stay unconfirmed before it is sent again.
+    pub resubmit_after: Duration,
+    /// Length of the hash chains published for new games, 0 leaves outcomes
+    /// to `set_result`.
+    pub hash_chain_length: u64,
+}
+
+impl Default for SettlerConfig {
+    fn default() -> Self {
+        Self {
+            server_seed: vec![],
+            priority_fee: 10_000,
+            compute_unit_limit: 50_000,
+            max_retries: 5,
+            retry_backoff: Duration::from_millis(500),
+            resubmit_after: Duration::from_secs(90),
+            hash_chain_length: 0,
+        }
+    }
+}
+
+pub struct Settler<C: Chain> {
+    chain: C,
+    config: SettlerConfig,
+    progress: Progress,
+}
+
+/// Whether the player wins the current round of `pool` under `server_seed`.
+pub fn game_outcome(server_seed: &[u8], pool: &PlayerPool) -> bool {
+    let randomness =
+        derive_game_randomness(server_seed, &pool.player, pool.game_session_id, pool.round);
+    is_win_from_randomness(&randomness, pool)
+}
+
+/// Flips of `pool`'s batch the player wins under `server_seed`, one bit each.
+pub fn batch_outcome(server_seed: &[u8], pool: &PlayerPool) -> u64 {
+    let randomness =
+        derive_game_randomness(server_seed, &pool.player, pool.game_session_id, pool.round);
+    batch_outcomes_from_randomness(&randomness, pool)
+}
+
+/// Element `index` of the hash chain whose first position follows `start`.
+/// Element 0 is derived from the server seed and every further element hashes
+/// the one before, so the chain is published by its last element and revealed
+/// backwards.
+pub fn hash_chain_element(server_seed: &[u8], start: u64, index: u64) -> [u8; 32] {
+    let mut element = hashv(&[b"hash-chain", server_seed, &start.to_be_bytes()]).to_bytes();
+    for _ in 0..index {
+        element = hash(&element).to_bytes();
+    }
+    element
+}
+
+/// Program accounts of `pool`'s game: global authority, casino vault, player
+/// pool and game vault.
+fn game_accounts(pool: &PlayerPool) -> (Pubkey, Pubkey, Pubkey, Pubkey) {
+    let session = pool.game_session_id.to_be_bytes();
+    let (global_authority, _) =
+        Pubkey::find_program_address(&[GLOBAL_AUTHORITY_SEED.as_bytes()], &coinflip::ID);
+    let (casino_vault, _) =
+        Pubkey::find_program_address(&[VAULT_AUTHORITY_SEED.as_bytes()], &coinflip::ID);
+    let (player_pool, _) = Pubkey::find_program_address(
+        &[pool.player.as_ref(), PLAYER_POOL_SEED.as_bytes(), &session],
+        &coinflip::ID,
+    );
+    let (game_vault, _) = Pubkey::find_program_address(
+        &[
+            pool.player.as_ref(),
+            VAULT_AUTHORITY_SEED.as_bytes(),
+            &session,
+        ],
+        &coinflip::ID,
+    );
+    (global_authority, casino_vault, player_pool, game_vault)
+}
+
+/// Wagered totals of `player`, counted and ranked when their rounds are settled.
+fn player_stats(player: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[player.as_ref(), PLAYER_STATS_SEED.as_bytes()],
+        &coinflip::ID,
+    )
+    .0
+}
+
+/// VIP tiers the rakeback of a settled round accrues at.
+fn vip_config() -> Pubkey {
+    Pubkey::find_program_address(&[VIP_CONFIG_SEED.as_bytes()], &coinflip::ID).0
+}
+
+/// Priority fee, compute limit and `set_result` for the current round of `pool`.
+pub fn set_result_instructions(
+    operator: Pubkey,
+    pool: &PlayerPool,
+    is_win: bool,
+    priority_fee: u64,
+    compute_unit_limit: u32,
+) -> Vec<Instruction> {
+    let (global_authority, casino_vault, player_pool, game_vault) = game_accounts(pool);
+
+    let set_result = Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::SetResult {
+            operator,
+            owner: pool.player,
+            global_authority,
+            player_pool,
+            game_vault,
+            casino_vault,
+            player_stats: player_stats(&pool.player),
+            vip_config: vip_config(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::SetResult {
+            round_id: pool.round,
+            is_win,
+            game_session_id: pool.game_session_id,
+        }
+        .data(),
+    };
+
+    vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit),
+        ComputeBudgetInstruction::set_compute_unit_price(priority_fee),
+        set_result,
+    ]
+}
+
+/// Priority fee, compute limit and `set_batch_result` for the batch of `pool`.
+pub fn set_batch_result_instructions(
+    operator: Pubkey,
+    pool: &PlayerPool,
+    outcomes: u64,
+    priority_fee: u64,
+    compute_unit_limit: u32,
+) -> Vec<Instruction> {
+    let (global_authority, casino_vault, player_pool, game_vault) = game_accounts(pool);
+
+    let set_batch_result = Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::SetBatchResult {
+            operator,
+            owner: pool.player,
+            global_authority,
+            player_pool,
+            game_vault,
+            casino_vault,
+            player_stats: player_stats(&pool.player),
+            vip_config: vip_config(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::SetBatchResult {
+            round_id: pool.round,
+            outcomes,
+            game_session_id: pool.game_session_id,
+        }
+        .data(),
+    };
+
+    vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit),
+        ComputeBudgetInstruction::set_compute_unit_price(priority_fee),
+        set_batch_result,
+    ]
+}
+
+/// Priority fee, compute limit and `settle_with_slot_hash` for a slot hash
+/// game. Anyone may send it; the operator does so the game cannot sit until
+/// its slot hash expires.
+pub fn settle_with_slot_hash_instructions(
+    operator: Pubkey,
+    pool: &PlayerPool,
+    priority_fee: u64,
+    compute_unit_limit: u32,
+) -> Vec<Instruction> {
+    let (global_authority, casino_vault, player_pool, game_vault) = game_accounts(pool);
+
+    let settle = Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::SettleWithSlotHash {
+            operator,
+            owner: pool.player,
+            global_authority,
+            player_pool,
+            game_vault,
+            casino_vault,
+            slot_hashes: sysvar::slot_hashes::ID,
+            player_stats: player_stats(&pool.player),
+            vip_config: vip_config(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::SettleWithSlotHash {
+            game_session_id: pool.game_session_id,
+        }
+        .data(),
+    };
+
+    vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit),
+        ComputeBudgetInstruction::set_compute_unit_price(priority_fee),
+        settle,
+    ]
+}
+
+/// Priority fee, compute limit and `settle_with_reveal` for a hash chain
+/// round, revealing `reveal` as the preimage of the chain head.
+pub fn settle_with_reveal_instructions(
+    operator: Pubkey,
+    pool: &PlayerPool,
+    reveal: [u8; 32],
+    priority_fee: u64,
+    compute_unit_limit: u32,
+) -> Vec<Instruction> {
+    let (global_authority, casino_vault, player_pool, game_vault) = game_accounts(pool);
+
+    let settle = Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::SettleWithReveal {
+            operator,
+            owner: pool.player,
+            global_authority,
+            player_pool,
+            game_vault,
+            casino_vault,
+            player_stats: player_stats(&pool.player),
+            vip_config: vip_config(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::SettleWithReveal {
+            game_session_id: pool.game_session_id,
+            reveal,
+        }
+        .data(),
+    };
+
+    vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit),
+        ComputeBudgetInstruction::set_compute_unit_price(priority_fee),
+        settle,
+    ]
+}
+
+/// `publish_hash_chain` for a chain of `length` preimages behind `tail`.
+pub fn publish_hash_chain_instruction(
+    operator: Pubkey,
+    tail: [u8; 32],
+    length: u64,
+) -> Instruction {
+    let (global_authority, _) =
+        Pubkey::find_program_address(&[GLOBAL_AUTHORITY_SEED.as_bytes()], &coinflip::ID);
+
+    Instruction {
+        program_id: coinflip::ID,
+        accounts: coinflip::accounts::PublishHashChain {
+            operator,
+            global_authority,
+        }
+        .to_account_metas(None),
+        data: coinflip::instruction::PublishHashChain { tail, length }.data(),
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+impl<C: Chain> Settler<C> {
+    pub fn new(chain: C, config: SettlerConfig, progress: Progress) -> Self {
+        Self {
+            chain,
+            config,
+            progress,
+        }
+    }
+
+    pub fn chain(&self) -> &C {
+        &self.chain
+    }
+
+    pub fn progress(&self) -> &Progress {
+        &self.progress
+    }
+
+    /// Settle every pending game once, returning how many were settled.
+    pub fn run_once(&mut self) -> Result<usize> {
+        let mut pending = self.chain.pending_games()?;
+        let keys: Vec<Pubkey> = pending.iter().map(|(key, _)| *key).collect();
+        self.progress.retain_pending(&keys)?;
+
+        let global = self.chain.global()?;
+        if self.config.hash_chain_length > 0 && global.hash_chain_revealed == global.hash_chain_end
+        {
+            self.publish_hash_chain(global.hash_chain_revealed)?;
+        }
+
+        // Hash chain rounds only settle in the order of their positions
+        pending.sort_by_key(|(_, pool)| pool.chain_position);
+        let mut next_position = global.hash_chain_revealed + 1;
+
+        let now = unix_now();
+        let slot = self.chain.slot()?;
+        let mut settled = 0;
+
+        for (key, pool) in pending {
+            // Rounds requested from a randomness provider settle in its callback
+            if pool.randomness_provider != Pubkey::default() {
+                continue;
+            }
+            if pool.chain_position != 0 && pool.chain_position != next_position {
+                continue;
+            }
+            // Slot hash rounds wait for their slot to pass, a later pass settles them
+            if pool.target_slot != 0 && slot <= pool.target_slot {
+                continue;
+            }
+
+            if let Some(submission) = self.progress.get(&key) {
+                if submission.round == pool.round
+                    && now < submission.submitted_at + self.config.resubmit_after.as_secs()
+                {
+                    continue;
+                }
+            }
+
+            self.progress.record(
+                key,
+                Submission {
+                    round: pool.round,
+                    submitted_at: now,
+                },
+            )?;
+
+            match self.settle(&key, &pool, &global) {
+                Ok(()) => {
+                    settled += 1;
+                    if pool.chain_position != 0 {
+                        next_position += 1;
+                    }
+                }
+                Err(e) => eprintln!("Failed to settle {key} round {}: {e}", pool.round),
+            }
+        }
+
+        Ok(settled)
+    }
+
+    /// Publish a fresh chain for the positions after `start`.
+    fn publish_hash_chain(&self, start: u64) -> Result<()> {
+        let length = self.config.hash_chain_length;
+        let tail = hash_chain_element(&self.config.server_seed, start, length);
+        let signature = self.chain.send(&[publish_hash_chain_instruction(
+            self.chain.operator(),
+            tail,
+            length,
+        )])?;
+        println!(
+            "Published hash chain for positions {} to {}: {signature}",
+            start + 1,
+            start + length
+        );
+        Ok(())
+    }
+
+    fn settle(&self, key: &Pubkey, pool: &PlayerPool, global: &GlobalPool) -> Result<()> {
+        let (instructions, outcome) = if pool.chain_position != 0 {
+            // The live chain was published by `publish_hash_chain` with the
+            // configured length, and its last position reveals element 0
+            let start = global
+                .hash_chain_end
+                .saturating_sub(self.config.hash_chain_length);
+            let index = global.hash_chain_end.saturating_sub(pool.chain_position);
+            let reveal = hash_chain_element(&self.config.server_seed, start, index);
+            let instructions = settle_with_reveal_instructions(
+                self.chain.operator(),
+                pool,
+                reveal,
+                self.config.priority_fee,
+                self.config.compute_unit_limit,
+            );
+            (
+                instructions,
+                format!("from chain position {}", pool.chain_position),
+            )
+        } else if pool.target_slot != 0 {
+            let instructions = settle_with_slot_hash_instructions(
+                self.chain.operator(),
+                pool,
+                self.config.priority_fee,
+                self.config.compute_unit_limit,
+            );
+            (instructions, "from slot hash".to_string())
+        } else if pool.is_batch() {
+            let outcomes = batch_outcome(&self.config.server_seed, pool);
+            let instructions = set_batch_result_instructions(
+                self.chain.operator(),
+                pool,
+                outcomes,
+                self.config.priority_fee,
+                self.config.compute_unit_limit,
+            );
+            (instructions, format!("outcomes={outcomes:#b}"))
+        } else {
+            let is_win = game_outcome(&self.config.server_seed, pool);
+            let instructions = set_result_instructions(
+                self.chain.operator(),
+                pool,
+                is_win,
+                self.config.priority_fee,
+                self.config.compute_unit_limit,
+            );
+            (instructions, format!("win={is_win}"))
+        };
+
+        let mut backoff = self.config.retry_backoff;
+        for attempt in 0..=self.config.max_retries {
+            match self.chain.send(&instructions) {
+                Ok(signature) => {
+                    println!("Settled {key} round {} {outcome}: {signature}", pool.round);
+                    return Ok(());
+                }
+                Err(e) => {
+                    eprintln!("Attempt {attempt} for {key} round {}: {e}", pool.round);
+
+                    // An earlier attempt may have landed after all
+                    match self.chain.game(key)? {
+                        Some(current)
+                            if current.status == GameStatus::Processing
+                                && current.round == pool.round => {}
+                        _ => return Ok(()),
+                    }
+
+                    thread::sleep(backoff);
+                    backoff *= 2;
+                }
+            }
+        }
+
+        Err(anyhow!(
+            "giving up after {} retries",
+            self.config.max_retries
+        ))
+    }
+}
@@ -0,0 +1,400 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    env, fs,
+    path::PathBuf,
+    time::Duration,
+};
+
+use anchor_lang::{AnchorDeserialize, Discriminator, InstructionData};
+use anyhow::{anyhow, Result};
+use coinflip::{
+    account::{GameStatus, GlobalPool, PlayerPool},
+    constants::PLAYER_POOL_SEED,
+    instruction::{
+        PublishHashChain, SetBatchResult, SetResult, SettleWithReveal, SettleWithSlotHash,
+    },
+    utils::{derive_game_randomness, is_win_from_randomness},
+};
+use coinflip_settler::{
+    batch_outcome, game_outcome, hash_chain_element, Chain, Progress, Settler, SettlerConfig,
+};
+use solana_sdk::{hash::hash, instruction::Instruction, pubkey::Pubkey, signature::Signature};
+
+/// In-memory stand-in for the cluster, applying `set_result`,
+/// `set_batch_result`, `settle_with_reveal` and `publish_hash_chain` the way
+/// the program does.
+#[derive(Default)]
+struct LocalChain {
+    operator: Pubkey,
+    global: RefCell<GlobalPool>,
+    pools: RefCell<HashMap<Pubkey, PlayerPool>>,
+    sent: RefCell<Vec<Vec<Instruction>>>,
+    failures: Cell<u32>,
+    slot: Cell<u64>,
+}
+
+impl LocalChain {
+    fn with_game(player: Pubkey, game_session_id: u64, is_head: bool) -> (Self, Pubkey) {
+        let chain = Self {
+            operator: Pubkey::new_unique(),
+            ..Self::default()
+        };
+        let (key, _) = Pubkey::find_program_address(
+            &[
+                player.as_ref(),
+                PLAYER_POOL_SEED.as_bytes(),
+                &game_session_id.to_be_bytes(),
+            ],
+            &coinflip::ID,
+        );
+        chain.pools.borrow_mut().insert(
+            key,
+            PlayerPool {
+                first_bet: 100_000_000,
+                status: GameStatus::Processing,
+                round: 1,
+                player,
+                is_head,
+                game_session_id,
+                ..PlayerPool::default()
+            },
+        );
+        (chain, key)
+    }
+}
+
+impl Chain for LocalChain {
+    fn operator(&self) -> Pubkey {
+        self.operator
+    }
+
+    fn pending_games(&self) -> Result<Vec<(Pubkey, PlayerPool)>> {
+        Ok(self
+            .pools
+            .borrow()
+            .iter()
+            .filter(|(_, pool)| pool.status == GameStatus::Processing)
+            .map(|(key, pool)| (*key, pool.clone()))
+            .collect())
+    }
+
+    fn global(&self) -> Result<GlobalPool> {
+        Ok(self.global.borrow().clone())
+    }
+
+    fn game(&self, player_pool: &Pubkey) -> Result<Option<PlayerPool>> {
+        Ok(self.pools.borrow().get(player_pool).cloned())
+    }
+
+    fn slot(&self) -> Result<u64> {
+        Ok(self.slot.get())
+    }
+
+    fn send(&self, instructions: &[Instruction]) -> Result<Signature> {
+        self.sent.borrow_mut().push(instructions.to_vec());
+
+        if self.failures.get() > 0 {
+            self.failures.set(self.failures.get() - 1);
+            return Err(anyhow!("blockhash not found"));
+        }
+
+        let ix = instructions.last().unwrap();
+        let (discriminator, args) = ix.data.split_at(8);
+
+        if discriminator == PublishHashChain::DISCRIMINATOR {
+            let args = PublishHashChain::try_from_slice(args)?;
+            let mut global = self.global.borrow_mut();
+            global.hash_chain_head = args.tail;
+            global.hash_chain_end = global.hash_chain_revealed + args.length;
+            return Ok(Signature::default());
+        }
+
+        // The first settlement accounts are operator, owner, global_authority, player_pool
+        let key = ix.accounts[3].pubkey;
+        let mut pools = self.pools.borrow_mut();
+        let pool = pools.get_mut(&key).ok_or(anyhow!("unknown pool"))?;
+
+        let (round_id, is_win) = if discriminator == SetResult::DISCRIMINATOR {
+            (args[0], args[1] == 1)
+        } else if discriminator == SetBatchResult::DISCRIMINATOR {
+            let args = SetBatchResult::try_from_slice(args)?;
+            pool.batch_outcomes = args.outcomes;
+            (args.round_id, args.outcomes != 0)
+        } else if discriminator == SettleWithReveal::DISCRIMINATOR {
+            let args = SettleWithReveal::try_from_slice(args)?;
+            let mut global = self.global.borrow_mut();
+            if pool.chain_position != global.hash_chain_revealed + 1
+                || hash(&args.reveal).to_bytes() != global.hash_chain_head
+            {
+                return Err(anyhow!("custom program error: 0x1783"));
+            }
+            global.hash_chain_head = args.reveal;
+            global.hash_chain_revealed += 1;
+
+            let randomness = derive_game_randomness(
+                &args.reveal,
+                &pool.player,
+                pool.game_session_id,
+                pool.round,
+            );
+            (pool.round, is_win_from_randomness(&randomness, pool))
+        } else {
+            // Other settlements are just recorded
+            return Ok(Signature::default());
+        };
+
+        if pool.round != round_id || pool.status != GameStatus::Processing {
+            return Err(anyhow!("custom program error: 0x1777"));
+        }
+
+        if is_win {
+            pool.status = GameStatus::Win;
+        } else {
+            pools.remove(&key);
+        }
+        Ok(Signature::default())
+    }
+}
+
+fn state_path(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("settler-{}-{name}.txt", std::process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn config() -> SettlerConfig {
+    SettlerConfig {
+        server_seed: b"test-server-seed".to_vec(),
+        retry_backoff: Duration::from_millis(1),
+        ..SettlerConfig::default()
+    }
+}
+
+#[test]
+fn settles_pending_game_with_derived_outcome() {
+    let player = Pubkey::new_unique();
+    let (chain, key) = LocalChain::with_game(player, 7, true);
+    let expected = game_outcome(b"test-server-seed", &chain.game(&key).unwrap().unwrap());
+
+    let progress = Progress::load(state_path("settle")).unwrap();
+    let mut settler = Settler::new(chain, config(), progress);
+
+    assert_eq!(settler.run_once().unwrap(), 1);
+
+    let sent = settler.chain().sent.borrow();
+    assert_eq!(sent.len(), 1);
+    // Compute unit limit, priority fee and set_result
+    assert_eq!(sent[0].len(), 3);
+    assert_eq!(sent[0][2].program_id, coinflip::ID);
+    assert_eq!(sent[0][2].data[9] == 1, expected);
+
+    let pool = settler.chain().game(&key).unwrap();
+    match expected {
+        true => assert!(pool.unwrap().status == GameStatus::Win),
+        false => assert!(pool.is_none()),
+    }
+}
+
+#[test]
+fn settles_batches_with_a_derived_outcome_per_flip() {
+    let (chain, key) = LocalChain::with_game(Pubkey::new_unique(), 7, true);
+    {
+        let mut pools = chain.pools.borrow_mut();
+        let pool = pools.get_mut(&key).unwrap();
+        pool.batch_choices = 0b0110;
+        pool.batch_amounts = vec![100_000_000; 4];
+    }
+    let expected = batch_outcome(b"test-server-seed", &chain.game(&key).unwrap().unwrap());
+
+    let progress = Progress::load(state_path("batch")).unwrap();
+    let mut settler = Settler::new(chain, config(), progress);
+
+    assert_eq!(settler.run_once().unwrap(), 1);
+
+    let sent = settler.chain().sent.borrow();
+    assert_eq!(
+        sent[0][2].data,
+        SetBatchResult {
+            round_id: 1,
+            outcomes: expected,
+            game_session_id: 7,
+        }
+        .data()
+    );
+    assert_eq!(expected >> 4, 0);
+}
+
+#[test]
+fn leaves_provider_games_to_the_provider() {
+    let (chain, key) = LocalChain::with_game(Pubkey::new_unique(), 7, true);
+    chain
+        .pools
+        .borrow_mut()
+        .get_mut(&key)
+        .unwrap()
+        .randomness_provider = Pubkey::new_unique();
+
+    let progress = Progress::load(state_path("provider")).unwrap();
+    let mut settler = Settler::new(chain, config(), progress);
+
+    assert_eq!(settler.run_once().unwrap(), 0);
+    assert!(settler.chain().sent.borrow().is_empty());
+}
+
+#[test]
+fn settles_slot_hash_games_from_the_sysvar() {
+    let (chain, key) = LocalChain::with_game(Pubkey::new_unique(), 7, true);
+    chain.pools.borrow_mut().get_mut(&key).unwrap().target_slot = 42;
+    chain.slot.set(43);
+
+    let progress = Progress::load(state_path("slot-hash")).unwrap();
+    let mut settler = Settler::new(chain, config(), progress);
+
+    assert_eq!(settler.run_once().unwrap(), 1);
+
+    let sent = settler.chain().sent.borrow();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(
+        sent[0][2].data,
+        SettleWithSlotHash { game_session_id: 7 }.data()
+    );
+}
+
+#[test]
+fn defers_slot_hash_games_until_their_slot_has_passed() {
+    let (chain, key) = LocalChain::with_game(Pubkey::new_unique(), 7, true);
+    chain.pools.borrow_mut().get_mut(&key).unwrap().target_slot = 42;
+    chain.slot.set(42);
+
+    let progress = Progress::load(state_path("slot-hash-early")).unwrap();
+    let mut settler = Settler::new(chain, config(), progress);
+
+    assert_eq!(settler.run_once().unwrap(), 0);
+    assert!(settler.chain().sent.borrow().is_empty());
+
+    settler.chain().slot.set(43);
+    assert_eq!(settler.run_once().unwrap(), 1);
+    assert_eq!(settler.chain().sent.borrow().len(), 1);
+}
+
+#[test]
+fn publishes_a_hash_chain_when_none_is_live() {
+    let chain = LocalChain::default();
+    let config = SettlerConfig {
+        hash_chain_length: 4,
+        ..config()
+    };
+
+    let progress = Progress::load(state_path("publish")).unwrap();
+    let mut settler = Settler::new(chain, config, progress);
+    settler.run_once().unwrap();
+
+    let global = settler.chain().global().unwrap();
+    assert_eq!(
+        global.hash_chain_head,
+        hash_chain_element(b"test-server-seed", 0, 4)
+    );
+    assert_eq!(global.hash_chain_end, 4);
+
+    // A live chain is left alone
+    settler.run_once().unwrap();
+    assert_eq!(settler.chain().sent.borrow().len(), 1);
+}
+
+#[test]
+fn reveals_hash_chain_rounds_in_position_order() {
+    let config = SettlerConfig {
+        hash_chain_length: 4,
+        ..config()
+    };
+    let chain = LocalChain::default();
+    let mut keys = vec![];
+    for (session, position) in [(1, 2), (2, 1)] {
+        let (game, key) = LocalChain::with_game(Pubkey::new_unique(), session, true);
+        let mut pool = game.pools.into_inner().remove(&key).unwrap();
+        pool.chain_position = position;
+        chain.pools.borrow_mut().insert(key, pool);
+        keys.push(key);
+    }
+    {
+        let mut global = chain.global.borrow_mut();
+        global.hash_chain_head = hash_chain_element(b"test-server-seed", 0, 4);
+        global.hash_chain_end = 4;
+        global.hash_chain_assigned = 2;
+    }
+
+    let progress = Progress::load(state_path("reveal")).unwrap();
+    let mut settler = Settler::new(chain, config, progress);
+
+    assert_eq!(settler.run_once().unwrap(), 2);
+
+    let sent = settler.chain().sent.borrow();
+    assert_eq!(sent[0][2].accounts[3].pubkey, keys[1]);
+    assert_eq!(sent[1][2].accounts[3].pubkey, keys[0]);
+    let global = settler.chain().global().unwrap();
+    assert_eq!(global.hash_chain_revealed, 2);
+    assert_eq!(
+        global.hash_chain_head,
+        hash_chain_element(b"test-server-seed", 0, 2)
+    );
+}
+
+#[test]
+fn retries_transient_failures() {
+    let (chain, key) = LocalChain::with_game(Pubkey::new_unique(), 1, false);
+    chain.failures.set(2);
+
+    let progress = Progress::load(state_path("retry")).unwrap();
+    let mut settler = Settler::new(chain, config(), progress);
+
+    assert_eq!(settler.run_once().unwrap(), 1);
+    assert_eq!(settler.chain().sent.borrow().len(), 3);
+    assert!(settler
+        .chain()
+        .game(&key)
+        .unwrap()
+        .is_none_or(|pool| pool.status == GameStatus::Win));
+}
+
+#[test]
+fn resumes_without_resubmitting_in_flight_rounds() {
+    let path = state_path("resume");
+    let (chain, key) = LocalChain::with_game(Pubkey::new_unique(), 3, true);
+    chain.failures.set(u32::MAX);
+
+    let single_attempt = SettlerConfig {
+        max_retries: 0,
+        ..config()
+    };
+    let mut settler = Settler::new(chain, single_attempt, Progress::load(&path).unwrap());
+    assert_eq!(settler.run_once().unwrap(), 0);
+    assert_eq!(settler.chain().sent.borrow().len(), 1);
+
+    // A restarted daemon picks the submission up from disk and waits it out
+    let progress = Progress::load(&path).unwrap();
+    assert_eq!(progress.get(&key).unwrap().round, 1);
+
+    let (chain, _) = LocalChain::with_game(Pubkey::new_unique(), 3, true);
+    let pools = settler.chain().pools.borrow().clone();
+    *chain.pools.borrow_mut() = pools;
+
+    let mut settler = Settler::new(chain, config(), progress);
+    assert_eq!(settler.run_once().unwrap(), 0);
+    assert!(settler.chain().sent.borrow().is_empty());
+}
+
+#[test]
+fn forgets_games_that_are_no_longer_pending() {
+    let path = state_path("prune");
+    let (chain, key) = LocalChain::with_game(Pubkey::new_unique(), 9, true);
+
+    let mut settler = Settler::new(chain, config(), Progress::load(&path).unwrap());
+    settler.run_once().unwrap();
+    assert!(settler.progress().get(&key).is_some());
+
+    settler.chain().pools.borrow_mut().clear();
+    settler.run_once().unwrap();
+    assert!(settler.progress().is_empty());
+    assert!(Progress::load(&path).unwrap().is_empty());
+}